@@ -141,5 +141,1344 @@ namespace TaffySharp.Tests
             Assert.AreEqual(50f, childLayout.Size.Width);
             Assert.AreEqual(100f, childLayout.Size.Height);
         }
+
+        [TestMethod]
+        public void TestBatchRollsBackOnFailure()
+        {
+            using var tree = new TaffyTree();
+
+            var root = tree.NewLeaf(new Style()
+            {
+                Size = new(100f, 100f),
+            });
+            var child = tree.NewLeaf(new Style()
+            {
+                Size = new(50f, 50f),
+            });
+
+            Assert.IsTrue(tree.BeginBatch());
+            Assert.IsTrue(tree.AddChild(root, child));
+            // a node can't become a child of its own descendant
+            Assert.IsFalse(tree.AddChild(child, root));
+            // the batch failed, so further operations are refused
+            Assert.IsFalse(tree.MarkDirty(root));
+            Assert.IsFalse(tree.CommitBatch());
+
+            Assert.IsTrue(root.ComputeLayout(new(100f, 100f)));
+            Assert.IsTrue(child.GetLayout(out var childLayout));
+            Assert.AreEqual(0f, childLayout.Size.Width);
+            Assert.AreEqual(0f, childLayout.Size.Height);
+        }
+
+        [TestMethod]
+        public void TestApplyCommands()
+        {
+            using var tree = new TaffyTree();
+
+            var child = tree.NewLeaf(new Style() { Size = new(10f, 10f) });
+            var root = tree.NewWithChildren(new Style() { Size = new(200f, 100f) }, child);
+
+            using var stream = new MemoryStream();
+            using (var writer = new BinaryWriter(stream))
+            {
+                writer.Write("TFCB"u8);
+                writer.Write(1u);
+                // create leaf, 40 x 20
+                writer.Write((byte)1);
+                WriteStyle(writer, 40f, 20f);
+                // insert child: the new leaf first under root
+                writer.Write((byte)2);
+                WriteNode(writer, 0, root.Id);
+                WriteNode(writer, 1, 0);
+                writer.Write(0u);
+                // set property: width of child to 30 px
+                writer.Write((byte)6);
+                WriteNode(writer, 0, child.Id);
+                writer.Write((ushort)4);
+                WriteLength(writer, 1, 30f);
+            }
+            var commands = stream.ToArray();
+
+            // a truncated buffer fails on its last command without applying the others
+            var results = new ulong[1];
+            Assert.AreEqual(-2 - 2, tree.ApplyCommands(commands[..^2], results));
+            Assert.AreEqual(0ul, results[0]);
+            // so does a buffer creating more nodes than there are result slots
+            Assert.AreEqual(-2, tree.ApplyCommands(commands, Array.Empty<ulong>()));
+            Assert.AreEqual(-1, tree.ApplyCommands(commands[1..], results));
+
+            Assert.IsTrue(root.ComputeLayout(new(200f, 100f)));
+            Assert.IsTrue(child.GetLayout(out var childLayout));
+            Assert.AreEqual(0f, childLayout.Location.X);
+            Assert.AreEqual(10f, childLayout.Size.Width);
+
+            Assert.AreEqual(1, tree.ApplyCommands(commands, results));
+            Assert.AreNotEqual(0ul, results[0]);
+            Assert.IsTrue(root.ComputeLayout(new(200f, 100f)));
+            Assert.IsTrue(child.GetLayout(out childLayout));
+            Assert.AreEqual(40f, childLayout.Location.X);
+            Assert.AreEqual(30f, childLayout.Size.Width);
+        }
+
+        [TestMethod]
+        public void TestApplyCommandsResolvesRelativeProperties()
+        {
+            using var tree = new TaffyTree();
+
+            var child = tree.NewLeaf(new Style() { Size = new(10f, 10f) });
+            var root = tree.NewWithChildren(new Style() { Size = new(400f, 100f) }, child);
+            Assert.IsTrue(tree.SetViewport(new(400f, 300f)));
+
+            byte[] SetWidth(int dim, float value)
+            {
+                using var stream = new MemoryStream();
+                using (var writer = new BinaryWriter(stream))
+                {
+                    writer.Write("TFCB"u8);
+                    writer.Write(1u);
+                    writer.Write((byte)6);
+                    WriteNode(writer, 0, child.Id);
+                    writer.Write((ushort)4);
+                    WriteLength(writer, dim, value);
+                }
+                return stream.ToArray();
+            }
+
+            // 50vw
+            Assert.AreEqual(0, tree.ApplyCommands(SetWidth(8, 50f), Array.Empty<ulong>()));
+            Assert.IsTrue(root.ComputeLayout(new(400f, 100f)));
+            Assert.IsTrue(child.GetLayout(out var childLayout));
+            Assert.AreEqual(200f, childLayout.Size.Width);
+
+            // an unknown dim, and min-content, which is not a valid width, fail the command
+            Assert.AreEqual(-2, tree.ApplyCommands(SetWidth(99, 50f), Array.Empty<ulong>()));
+            Assert.AreEqual(-2, tree.ApplyCommands(SetWidth(3, 0f), Array.Empty<ulong>()));
+            Assert.IsTrue(root.ComputeLayout(new(400f, 100f)));
+            Assert.IsTrue(child.GetLayout(out childLayout));
+            Assert.AreEqual(200f, childLayout.Size.Width);
+        }
+
+        private static void WriteNode(BinaryWriter writer, byte tag, ulong value)
+        {
+            writer.Write(tag);
+            writer.Write(value);
+        }
+
+        private static void WriteLength(BinaryWriter writer, int dim, float value)
+        {
+            writer.Write(dim);
+            writer.Write(value);
+        }
+
+        /// <summary>
+        /// Encodes a flex style of the given size, with every other field at its default
+        /// </summary>
+        private static void WriteStyle(BinaryWriter writer, float width, float height)
+        {
+            void Lengths(int count, int dim)
+            {
+                for (var i = 0; i < count; i++)
+                    WriteLength(writer, dim, 0f);
+            }
+
+            writer.Write(1); // display: flex
+            writer.Write(0); // item_is_table
+            writer.Write(0); // item_is_replaced
+            writer.Write(0); // box_sizing
+            writer.Write(0); // overflow_x
+            writer.Write(0); // overflow_y
+            writer.Write(0f); // scrollbar_width
+            writer.Write(0); // position
+            Lengths(4, 0); // inset
+            Lengths(2, 1); // gap
+            writer.Write(0); // text_align
+            Lengths(4, 1); // margin
+            Lengths(4, 1); // border
+            Lengths(4, 1); // padding
+            WriteLength(writer, 1, width);
+            WriteLength(writer, 1, height);
+            Lengths(2, 0); // min_size
+            Lengths(2, 0); // max_size
+            writer.Write(0); // flex_wrap
+            writer.Write(0); // flex_direction
+            writer.Write(0f); // flex_grow
+            writer.Write(1f); // flex_shrink
+            Lengths(1, 0); // flex_basis
+            writer.Write(0u); // grid_template_rows
+            writer.Write(0u); // grid_template_columns
+            writer.Write(0u); // grid_auto_rows
+            writer.Write(0u); // grid_auto_columns
+            writer.Write(0); // grid_auto_flow
+            for (var i = 0; i < 4; i++)
+            {
+                // grid_row and grid_column: auto start and end
+                writer.Write((sbyte)0);
+                writer.Write((short)0);
+            }
+            writer.Write(0f); // aspect_ratio
+            writer.Write(0); // has_aspect_ratio
+            for (var i = 0; i < 6; i++)
+            {
+                // the alignments and their has_ flags
+                writer.Write(0);
+                writer.Write(0);
+            }
+        }
+
+        [TestMethod]
+        public void TestBatchRollbackRestoresRemovedNodes()
+        {
+            using var tree = new TaffyTree();
+
+            var first = tree.NewLeaf(new Style() { Size = new(10f, 10f) });
+            var second = tree.NewLeaf(new Style() { Size = new(20f, 10f) });
+            var root = tree.NewWithChildren(new Style() { Size = new(100f, 100f) }, first, second);
+
+            Assert.IsTrue(tree.BeginBatch());
+            Assert.IsTrue(tree.Remove(first));
+            Assert.IsTrue(second.SetStyle(new Style() { Size = new(50f, 10f) }));
+            Assert.IsTrue(tree.RollbackBatch());
+
+            Assert.IsTrue(root.ComputeLayout(new(100f, 100f)));
+            Assert.IsTrue(first.GetLayout(out var firstLayout));
+            Assert.IsTrue(second.GetLayout(out var secondLayout));
+            Assert.AreEqual(10f, firstLayout.Size.Width);
+            Assert.AreEqual(10f, secondLayout.Location.X);
+            Assert.AreEqual(20f, secondLayout.Size.Width);
+        }
+
+        [TestMethod]
+        public void TestStylesheetMatchesClasses()
+        {
+            using var tree = new TaffyTree();
+            using var stylesheet = new Stylesheet(".toolbar > .button { width: 40px; height: 20px } .toolbar .button.wide { width: 80px }");
+
+            var toolbar = tree.NewLeaf(new Style()
+            {
+                Size = new(200f, 100f),
+            });
+            var button = tree.NewLeaf(new Style());
+            toolbar.AddChild(button);
+            Assert.IsTrue(tree.SetStylesheet(stylesheet));
+            Assert.IsTrue(toolbar.SetClasses("toolbar"));
+            Assert.IsTrue(button.AddClass("button"));
+
+            Assert.IsTrue(toolbar.ComputeLayout(new(200f, 100f)));
+            Assert.IsTrue(button.GetLayout(out var buttonLayout));
+            Assert.AreEqual(40f, buttonLayout.Size.Width);
+            Assert.AreEqual(20f, buttonLayout.Size.Height);
+
+            // the more specific rule wins once the class is added
+            Assert.IsTrue(button.AddClass("wide"));
+            Assert.IsTrue(toolbar.ComputeLayout(new(200f, 100f)));
+            Assert.IsTrue(button.GetLayout(out buttonLayout));
+            Assert.AreEqual(80f, buttonLayout.Size.Width);
+        }
+
+        [TestMethod]
+        public void TestStylesheetInheritsIntoUnstyledNodes()
+        {
+            using var tree = new TaffyTree();
+            using var stylesheet = new Stylesheet("* { display: block } .center { text-align: center }");
+
+            var item = tree.NewLeaf(new Style()
+            {
+                Size = new(40f, 10f),
+            });
+            var wrapper = tree.NewWithChildren(new Style(), item);
+            var root = tree.NewWithChildren(new Style()
+            {
+                Size = new(200f, 100f),
+            }, wrapper);
+            Assert.IsTrue(tree.SetStylesheet(stylesheet));
+            Assert.IsTrue(root.SetClasses("center"));
+
+            // the wrapper has no tag, id or classes but still inherits text-align from the root
+            Assert.IsTrue(root.ComputeLayout(new(200f, 100f)));
+            Assert.IsTrue(item.GetLayout(out var itemLayout));
+            Assert.AreEqual(80f, itemLayout.Location.X);
+        }
+
+        [TestMethod]
+        public void TestStylesheetRelativeLengths()
+        {
+            using var tree = new TaffyTree();
+            using var stylesheet = new Stylesheet(".panel { width: 50vw; height: calc(100% - 10px); padding: 1em 0 }");
+
+            var panel = tree.NewLeaf(new Style());
+            var root = tree.NewWithChildren(new Style()
+            {
+                Size = new(400f, 200f),
+            }, panel);
+            Assert.IsTrue(tree.SetViewport(new(400f, 300f)));
+            Assert.IsTrue(tree.SetStylesheet(stylesheet));
+            Assert.IsTrue(panel.SetClasses("panel"));
+            Assert.IsTrue(tree.SetFontSize(panel, 10f));
+
+            Assert.IsTrue(root.ComputeLayout(new(400f, 200f)));
+            Assert.IsTrue(panel.GetLayout(out var panelLayout));
+            Assert.AreEqual(200f, panelLayout.Size.Width);
+            Assert.AreEqual(190f, panelLayout.Size.Height);
+            Assert.AreEqual(10f, panelLayout.Padding.Top);
+
+            // the rules are resolved again when the units change
+            Assert.IsTrue(tree.SetViewport(new(300f, 300f)));
+            Assert.IsTrue(tree.SetFontSize(panel, 20f));
+            Assert.IsTrue(root.ComputeLayout(new(400f, 200f)));
+            Assert.IsTrue(panel.GetLayout(out panelLayout));
+            Assert.AreEqual(150f, panelLayout.Size.Width);
+            Assert.AreEqual(20f, panelLayout.Padding.Top);
+        }
+
+        [TestMethod]
+        public void TestStylesheetOverridesHostStyle()
+        {
+            using var tree = new TaffyTree();
+            using var stylesheet = new Stylesheet(".button { width: 40px }");
+
+            var button = tree.NewLeaf(new Style()
+            {
+                Size = new(80f, 20f),
+            });
+            var root = tree.NewWithChildren(new Style()
+            {
+                Size = new(200f, 100f),
+            }, button);
+            Assert.IsTrue(tree.SetStylesheet(stylesheet));
+            Assert.IsTrue(button.SetClasses("button"));
+
+            // the rule wins over the host's width, and the host's height applies since no rule declares one
+            Assert.IsTrue(root.ComputeLayout(new(200f, 100f)));
+            Assert.IsTrue(button.GetLayout(out var buttonLayout));
+            Assert.AreEqual(40f, buttonLayout.Size.Width);
+            Assert.AreEqual(20f, buttonLayout.Size.Height);
+        }
+
+        private static (Node Container, Node First, Node Second) ScrolledList(TaffyTree tree)
+        {
+            var item = new Style()
+            {
+                Size = new(80f, 60f),
+                FlexShrink = 0f,
+            };
+            var first = tree.NewLeaf(item);
+            var second = tree.NewLeaf(item);
+            var container = tree.NewWithChildren(new Style()
+            {
+                Size = new(100f, 100f),
+                Border = new(10f),
+                Overflow = new(Overflow.Hidden, Overflow.Hidden),
+                FlexDirection = FlexDirection.Column,
+            }, first, second);
+            Assert.IsTrue(container.ComputeLayout(new(1000f, 1000f)));
+            return (container, first, second);
+        }
+
+        [TestMethod]
+        public void TestScrollOffsetClampsToExtent()
+        {
+            using var tree = new TaffyTree();
+            var (container, _, _) = ScrolledList(tree);
+
+            // the two items end 40 below the padding box, and nothing overflows horizontally
+            Assert.IsTrue(tree.GetScrollExtent(container, out var extent));
+            Assert.AreEqual(0f, extent.X);
+            Assert.AreEqual(40f, extent.Y);
+
+            Assert.IsTrue(tree.SetScrollOffset(container, new(-5f, 1000f)));
+            Assert.IsTrue(tree.GetScrollOffset(container, out var offset));
+            Assert.AreEqual(0f, offset.X);
+            Assert.AreEqual(40f, offset.Y);
+        }
+
+        [TestMethod]
+        public void TestHitTestThroughScrolledContainer()
+        {
+            using var tree = new TaffyTree();
+            var (container, first, second) = ScrolledList(tree);
+            Assert.IsTrue(tree.SetScrollOffset(container, new(0f, 40f)));
+
+            Assert.IsTrue(tree.GetAbsoluteLocation(second, out var location));
+            Assert.AreEqual(10f, location.X);
+            Assert.AreEqual(30f, location.Y);
+            Assert.AreEqual(second.Id, tree.HitTest(container, new(50f, 50f))?.Id);
+            Assert.AreEqual(first.Id, tree.HitTest(container, new(50f, 20f))?.Id);
+
+            // the first item is scrolled under the top border, which clips it
+            Assert.AreEqual(container.Id, tree.HitTest(container, new(50f, 5f))?.Id);
+            Assert.AreEqual(container.Id, tree.HitTest(container, new(50f, 95f))?.Id);
+        }
+
+        [TestMethod]
+        public void TestHitTestVisibleOverflowOnOneAxis()
+        {
+            using var tree = new TaffyTree();
+
+            var child = tree.NewLeaf(new Style()
+            {
+                Size = new(200f, 200f),
+                FlexShrink = 0f,
+            });
+            var container = tree.NewWithChildren(new Style()
+            {
+                Size = new(100f, 100f),
+                Overflow = new(Overflow.Hidden, Overflow.Visible),
+            }, child);
+            Assert.IsTrue(container.ComputeLayout(new(1000f, 1000f)));
+
+            // the child overflows below the container, but is clipped on its right
+            Assert.AreEqual(child.Id, tree.HitTest(container, new(50f, 150f))?.Id);
+            Assert.IsNull(tree.HitTest(container, new(150f, 50f)));
+            Assert.AreEqual(child.Id, tree.HitTest(container, new(50f, 50f))?.Id);
+        }
+
+        private static void AssertClip(ClipRect[] rects, Node node, float minX, float minY, float maxX, float maxY)
+        {
+            var rect = rects.Single(rect => rect.Node.Id == node.Id);
+            Assert.AreEqual(minX, rect.Min.X);
+            Assert.AreEqual(minY, rect.Min.Y);
+            Assert.AreEqual(maxX, rect.Max.X);
+            Assert.AreEqual(maxY, rect.Max.Y);
+        }
+
+        [TestMethod]
+        public void TestClipRectsIntersectNestedClips()
+        {
+            using var tree = new TaffyTree();
+
+            var leaf = tree.NewLeaf(new Style()
+            {
+                Size = new(200f, 200f),
+                FlexShrink = 0f,
+            });
+            var inner = tree.NewWithChildren(new Style()
+            {
+                Size = new(80f, 80f),
+                Margin = new(50f, 0f, 0f, 0f),
+                Overflow = new(Overflow.Hidden, Overflow.Hidden),
+                FlexShrink = 0f,
+            }, leaf);
+            var outer = tree.NewWithChildren(new Style()
+            {
+                Size = new(100f, 100f),
+                Overflow = new(Overflow.Hidden, Overflow.Hidden),
+            }, inner);
+            Assert.IsTrue(outer.ComputeLayout(new(1000f, 1000f)));
+
+            var rects = tree.GetClipRects(outer);
+            Assert.AreEqual(3, rects.Length);
+            AssertClip(rects, outer, float.NegativeInfinity, float.NegativeInfinity, float.PositiveInfinity, float.PositiveInfinity);
+            AssertClip(rects, inner, 0f, 0f, 100f, 100f);
+            // the inner box sticks out of the outer one on the right, and is shorter
+            AssertClip(rects, leaf, 50f, 0f, 100f, 80f);
+        }
+
+        [TestMethod]
+        public void TestClipRectsVisibleOverflowOnOneAxis()
+        {
+            using var tree = new TaffyTree();
+
+            var child = tree.NewLeaf(new Style()
+            {
+                Size = new(200f, 200f),
+                FlexShrink = 0f,
+            });
+            var container = tree.NewWithChildren(new Style()
+            {
+                Size = new(100f, 100f),
+                Overflow = new(Overflow.Hidden, Overflow.Visible),
+            }, child);
+            Assert.IsTrue(container.ComputeLayout(new(1000f, 1000f)));
+
+            var rects = tree.GetClipRects(container);
+            AssertClip(rects, child, 0f, float.NegativeInfinity, 100f, float.PositiveInfinity);
+        }
+
+        [TestMethod]
+        public void TestClipRectsFollowScrollOffset()
+        {
+            using var tree = new TaffyTree();
+
+            var item = new Style()
+            {
+                Size = new(80f, 60f),
+                FlexShrink = 0f,
+            };
+            var first = tree.NewLeaf(item);
+            var leaf = tree.NewLeaf(new Style()
+            {
+                Size = new(200f, 200f),
+            });
+            item.Overflow = new(Overflow.Hidden, Overflow.Hidden);
+            var second = tree.NewWithChildren(item, leaf);
+            var container = tree.NewWithChildren(new Style()
+            {
+                Size = new(100f, 100f),
+                Border = new(10f),
+                Overflow = new(Overflow.Hidden, Overflow.Hidden),
+                FlexDirection = FlexDirection.Column,
+            }, first, second);
+            Assert.IsTrue(container.ComputeLayout(new(1000f, 1000f)));
+
+            // the second item starts 70 down, so only its top 20 are inside the container's padding box
+            AssertClip(tree.GetClipRects(container), leaf, 10f, 70f, 90f, 90f);
+
+            // scrolling moves the second item up while the padding box of the container stays in place
+            Assert.IsTrue(tree.SetScrollOffset(container, new(0f, 40f)));
+            var rects = tree.GetClipRects(container);
+            AssertClip(rects, second, 10f, 10f, 90f, 90f);
+            AssertClip(rects, leaf, 10f, 30f, 90f, 90f);
+        }
+
+        [TestMethod]
+        public void TestRenderAsciiSnapshot()
+        {
+            using var tree = new TaffyTree();
+
+            var sidebar = tree.NewLeaf(new Style()
+            {
+                Size = new(100f, 60f),
+            });
+            var header = tree.NewLeaf(new Style()
+            {
+                Size = new(100f, 30f),
+            });
+            var root = tree.NewWithChildren(new Style()
+            {
+                Size = new(200f, 60f),
+            }, sidebar, header);
+            sidebar.SetTag("side");
+            header.SetTag("head");
+
+            Assert.IsTrue(root.ComputeLayout(new(200f, 60f)));
+            var expected =
+                "┌─────────┬─────────┐\n" +
+                "│side     │head     │\n" +
+                "│         │         │\n" +
+                "│         ├─────────┤\n" +
+                "│         │         │\n" +
+                "│         │         │\n" +
+                "└─────────┴─────────┘\n";
+            Assert.AreEqual(expected, tree.RenderAscii(root, 0.1f));
+        }
+
+        [TestMethod]
+        public void TestRenderSvgSnapshot()
+        {
+            using var tree = new TaffyTree();
+
+            var item = tree.NewLeaf(new Style()
+            {
+                Size = new(40f, 20f),
+                Margin = new(2f, 0f, 0f, 0f),
+                Border = new(1f),
+            });
+            var root = tree.NewWithChildren(new Style()
+            {
+                Size = new(100f, 50f),
+                Padding = new(5f),
+            }, item);
+            Assert.IsTrue(root.SetTag("panel"));
+            Assert.IsTrue(item.SetTag("item"));
+            Assert.IsTrue(item.SetElementId("main"));
+            Assert.IsTrue(item.SetClasses("a", "b"));
+
+            Assert.IsTrue(root.ComputeLayout(new(100f, 50f)));
+            var expected =
+                "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"100\" height=\"50\" viewBox=\"0 0 100 50\">\n" +
+                $"  <g data-node=\"{root.Id}\" class=\"flex\">\n" +
+                "    <rect x=\"0\" y=\"0\" width=\"100\" height=\"50\" fill=\"none\" stroke=\"#f0ad4e\" stroke-dasharray=\"4 2\"/>\n" +
+                "    <rect x=\"0\" y=\"0\" width=\"100\" height=\"50\" fill=\"#2f7ed8\" fill-opacity=\"0.12\" stroke=\"#2f7ed8\"/>\n" +
+                "    <rect x=\"0\" y=\"0\" width=\"100\" height=\"50\" fill=\"none\" stroke=\"#2f7ed8\" stroke-dasharray=\"2 2\"/>\n" +
+                "    <rect x=\"5\" y=\"5\" width=\"90\" height=\"40\" fill=\"#2f7ed8\" fill-opacity=\"0.08\" stroke=\"#2f7ed8\" stroke-opacity=\"0.5\"/>\n" +
+                "    <text x=\"2\" y=\"11\" font-family=\"monospace\" font-size=\"10\" fill=\"#2f7ed8\">panel</text>\n" +
+                "  </g>\n" +
+                $"  <g data-node=\"{item.Id}\" class=\"flex\">\n" +
+                "    <rect x=\"5\" y=\"5\" width=\"42\" height=\"20\" fill=\"none\" stroke=\"#f0ad4e\" stroke-dasharray=\"4 2\"/>\n" +
+                "    <rect x=\"7\" y=\"5\" width=\"40\" height=\"20\" fill=\"#2f7ed8\" fill-opacity=\"0.12\" stroke=\"#2f7ed8\"/>\n" +
+                "    <rect x=\"8\" y=\"6\" width=\"38\" height=\"18\" fill=\"none\" stroke=\"#2f7ed8\" stroke-dasharray=\"2 2\"/>\n" +
+                "    <rect x=\"8\" y=\"6\" width=\"38\" height=\"18\" fill=\"#2f7ed8\" fill-opacity=\"0.08\" stroke=\"#2f7ed8\" stroke-opacity=\"0.5\"/>\n" +
+                "    <text x=\"9\" y=\"16\" font-family=\"monospace\" font-size=\"10\" fill=\"#2f7ed8\">item#main.a.b</text>\n" +
+                "  </g>\n" +
+                "</svg>\n";
+            Assert.AreEqual(expected, tree.RenderSvg(root));
+        }
+
+        [TestMethod]
+        public void TestCalcLength()
+        {
+            using var tree = new TaffyTree();
+
+            var sidebar = tree.NewLeaf(new Style()
+            {
+                Size = new(Dimension.FromCalc("calc(100% - 32px)"), Dimension.FromCalc("min(50%, 40px)")),
+            });
+            var root = tree.NewWithChildren(new Style()
+            {
+                Size = new(300f, 100f),
+            }, sidebar);
+
+            Assert.IsTrue(root.ComputeLayout(new(300f, 100f)));
+            Assert.IsTrue(sidebar.GetLayout(out var sidebarLayout));
+            Assert.AreEqual(268f, sidebarLayout.Size.Width);
+            Assert.AreEqual(40f, sidebarLayout.Size.Height);
+
+            Assert.ThrowsException<ArgumentException>(() => Dimension.FromCalc("calc(100% * 2px)"));
+        }
+
+        [TestMethod]
+        public void TestCalcInterning()
+        {
+            if (!TaffyTree.SupportedFeatures.HasFlag(TaffyFeatures.Calc))
+                Assert.Inconclusive("taffy_ffi was built without the calc feature");
+
+            // equal expressions share an id, however they are spaced
+            var calc = Dimension.FromCalc("calc(100% - 24px)");
+            Assert.AreEqual(calc.Value, Dimension.FromCalc("calc( 100%   -  24px )").Value);
+            Assert.AreEqual(calc.Value, LengthPercentage.FromCalc("calc(100% - 24px)").Value);
+            Assert.AreNotEqual(calc.Value, Dimension.FromCalc("calc(100% - 25px)").Value);
+            Assert.AreEqual(MathF.Floor(calc.Value), calc.Value);
+            Assert.IsTrue(calc.Value >= 1f && calc.Value <= 1 << 24);
+
+            // calc only combines px and percentages, in styles and stylesheets alike
+            Assert.ThrowsException<ArgumentException>(() => Dimension.FromCalc("calc(50vw - 10px)"));
+            Assert.ThrowsException<ArgumentException>(() => Dimension.FromCalc("calc(2em + 4px)"));
+            Assert.ThrowsException<ArgumentException>(() => new Stylesheet(".panel { width: calc(50vw - 10px) }"));
+            using var stylesheet = new Stylesheet(".panel { width: calc(50% - 10px) }");
+        }
+
+        [TestMethod]
+        public void TestComputeLayoutsSkipsUpToDateRoots()
+        {
+            using var tree = new TaffyTree();
+
+            var first = tree.NewLeaf(new Style() { Size = new(100f, 50f) });
+            var second = tree.NewLeaf(new Style() { Size = new(200f, 50f) });
+            var roots = new[] { first, second };
+            var spaces = new AvailableSpace[] { new(800f, 600f), new(800f, 600f) };
+
+            CollectionAssert.AreEqual(new[] { LayoutResult.Computed, LayoutResult.Computed }, tree.ComputeLayouts(roots, spaces));
+            CollectionAssert.AreEqual(new[] { LayoutResult.Skipped, LayoutResult.Skipped }, tree.ComputeLayouts(roots, spaces));
+
+            Assert.IsTrue(second.SetStyle(new Style() { Size = new(300f, 50f) }));
+            CollectionAssert.AreEqual(new[] { LayoutResult.Skipped, LayoutResult.Computed }, tree.ComputeLayouts(roots, spaces));
+            Assert.IsTrue(second.GetLayout(out var layout));
+            Assert.AreEqual(300f, layout.Size.Width);
+        }
+
+        private static (Node Root, Node Leaf) FractionalLeaf(TaffyTree tree)
+        {
+            var leaf = tree.NewLeaf(new Style() { Size = new(10.6f, 10f) });
+            var root = tree.NewWithChildren(new Style() { Size = new(100f, 100f) }, leaf);
+            return (root, leaf);
+        }
+
+        [TestMethod]
+        public void TestComputeLayoutsAfterRoundingChange()
+        {
+            using var tree = new TaffyTree();
+            var (root, leaf) = FractionalLeaf(tree);
+            var roots = new[] { root };
+            var spaces = new AvailableSpace[] { new(100f, 100f) };
+
+            CollectionAssert.AreEqual(new[] { LayoutResult.Computed }, tree.ComputeLayouts(roots, spaces));
+            Assert.IsTrue(leaf.GetLayout(out var layout));
+            Assert.AreEqual(11f, layout.Size.Width);
+
+            tree.DisableRounding();
+            CollectionAssert.AreEqual(new[] { LayoutResult.Computed }, tree.ComputeLayouts(roots, spaces));
+            Assert.IsTrue(leaf.GetLayout(out layout));
+            Assert.AreEqual(10.6f, layout.Size.Width);
+            CollectionAssert.AreEqual(new[] { LayoutResult.Skipped }, tree.ComputeLayouts(roots, spaces));
+
+            tree.EnableRounding();
+            CollectionAssert.AreEqual(new[] { LayoutResult.Computed }, tree.ComputeLayouts(roots, spaces));
+            Assert.IsTrue(leaf.GetLayout(out layout));
+            Assert.AreEqual(11f, layout.Size.Width);
+        }
+
+        [TestMethod]
+        public void TestComputeLayoutsAfterViewportChange()
+        {
+            using var tree = new TaffyTree();
+            var (root, _) = FractionalLeaf(tree);
+            var roots = new[] { root };
+            var spaces = new AvailableSpace[] { new(100f, 100f) };
+
+            CollectionAssert.AreEqual(new[] { LayoutResult.Computed }, tree.ComputeLayouts(roots, spaces));
+            // no style uses viewport units, but the root was laid out in another viewport
+            Assert.IsTrue(tree.SetViewport(new(800f, 600f)));
+            CollectionAssert.AreEqual(new[] { LayoutResult.Computed }, tree.ComputeLayouts(roots, spaces));
+            Assert.IsTrue(tree.SetViewport(new(800f, 600f)));
+            CollectionAssert.AreEqual(new[] { LayoutResult.Skipped }, tree.ComputeLayouts(roots, spaces));
+        }
+
+        [TestMethod]
+        public void TestComputeLayoutsAfterRootFontSizeChange()
+        {
+            using var tree = new TaffyTree();
+            var (root, _) = FractionalLeaf(tree);
+            var roots = new[] { root };
+            var spaces = new AvailableSpace[] { new(100f, 100f) };
+
+            CollectionAssert.AreEqual(new[] { LayoutResult.Computed }, tree.ComputeLayouts(roots, spaces));
+            Assert.IsTrue(tree.SetRootFontSize(20f));
+            CollectionAssert.AreEqual(new[] { LayoutResult.Computed }, tree.ComputeLayouts(roots, spaces));
+            Assert.IsTrue(tree.SetRootFontSize(20f));
+            CollectionAssert.AreEqual(new[] { LayoutResult.Skipped }, tree.ComputeLayouts(roots, spaces));
+        }
+
+        [TestMethod]
+        public void TestTextWrapsBetweenWords()
+        {
+            using var tree = new TaffyTree();
+            using var font = new Font(BuildTestFont());
+
+            // at 20px every letter is 10px wide, a space 5px and a line 20px high
+            var text = tree.NewLeaf(new Style());
+            Assert.IsTrue(text.SetText(font, "aaaa bbbb cccc", 20f));
+
+            Assert.IsTrue(text.ComputeLayout(AvailableSpace.MinContent));
+            Assert.IsTrue(text.GetLayout(out var layout));
+            Assert.AreEqual(40f, layout.Size.Width);
+            Assert.AreEqual(60f, layout.Size.Height);
+
+            Assert.IsTrue(text.ComputeLayout(AvailableSpace.MaxContent));
+            Assert.IsTrue(text.GetLayout(out layout));
+            Assert.AreEqual(130f, layout.Size.Width);
+            Assert.AreEqual(20f, layout.Size.Height);
+
+            Assert.IsTrue(text.ComputeLayout(new(90f, 100f)));
+            Assert.IsTrue(text.GetLayout(out layout));
+            Assert.AreEqual(85f, layout.Size.Width);
+            Assert.AreEqual(40f, layout.Size.Height);
+
+            Assert.ThrowsException<ArgumentException>(() => new Font("nope"u8.ToArray()));
+        }
+
+        /// <summary>
+        /// A TrueType font without outlines: 1000 units per em, an ascent of 800 and a descent of 200,
+        /// and advances of 250 for the space and 500 for 'a' to 'z'
+        /// </summary>
+        private static byte[] BuildTestFont()
+        {
+            const ushort glyphs = 28;
+
+            var cmap = new List<byte>();
+            WriteBigEndian(cmap, 0, 2); // version
+            WriteBigEndian(cmap, 1, 2); // number of subtables
+            WriteBigEndian(cmap, 0, 2); // unicode platform
+            WriteBigEndian(cmap, 4, 2); // full repertoire encoding
+            WriteBigEndian(cmap, 12, 4); // subtable offset
+            WriteBigEndian(cmap, 12, 2); // format 12
+            WriteBigEndian(cmap, 0, 2);
+            WriteBigEndian(cmap, 16 + 2 * 12, 4); // length
+            WriteBigEndian(cmap, 0, 4); // language
+            WriteBigEndian(cmap, 2, 4); // groups
+            foreach (var (first, last, glyph) in new[] { (' ', ' ', 1u), ('a', 'z', 2u) })
+            {
+                WriteBigEndian(cmap, first, 4);
+                WriteBigEndian(cmap, last, 4);
+                WriteBigEndian(cmap, glyph, 4);
+            }
+
+            var head = new List<byte>();
+            WriteBigEndian(head, 0x10000, 4); // version
+            WriteBigEndian(head, 0, 4); // font revision
+            WriteBigEndian(head, 0, 4); // checksum adjustment
+            WriteBigEndian(head, 0x5F0F3CF5, 4); // magic
+            WriteBigEndian(head, 0, 2); // flags
+            WriteBigEndian(head, 1000, 2); // units per em
+            head.AddRange(new byte[16 + 8 + 10]); // dates, bounds, mac style, lowest ppem, direction, loca and glyf formats
+
+            var hhea = new List<byte>();
+            WriteBigEndian(hhea, 0x10000, 4); // version
+            WriteBigEndian(hhea, 800, 2); // ascent
+            WriteBigEndian(hhea, unchecked((ushort)-200), 2); // descent
+            WriteBigEndian(hhea, 0, 2); // line gap
+            hhea.AddRange(new byte[24]);
+            WriteBigEndian(hhea, glyphs, 2); // horizontal metrics
+
+            var hmtx = new List<byte>();
+            for (var glyph = 0; glyph < glyphs; glyph++)
+            {
+                WriteBigEndian(hmtx, glyph == 1 ? 250u : 500u, 2); // advance
+                WriteBigEndian(hmtx, 0, 2); // left side bearing
+            }
+
+            var maxp = new List<byte>();
+            WriteBigEndian(maxp, 0x5000, 4); // version 0.5
+            WriteBigEndian(maxp, glyphs, 2);
+
+            // the table directory has to be sorted by tag
+            var tables = new (string Tag, List<byte> Data)[] { ("cmap", cmap), ("head", head), ("hhea", hhea), ("hmtx", hmtx), ("maxp", maxp) };
+            var font = new List<byte>();
+            WriteBigEndian(font, 0x10000, 4); // TrueType outlines
+            WriteBigEndian(font, (uint)tables.Length, 2);
+            font.AddRange(new byte[6]);
+            var offset = 12 + 16 * tables.Length;
+            foreach (var (tag, data) in tables)
+            {
+                font.AddRange(System.Text.Encoding.ASCII.GetBytes(tag));
+                WriteBigEndian(font, 0, 4); // checksum
+                WriteBigEndian(font, (uint)offset, 4);
+                WriteBigEndian(font, (uint)data.Count, 4);
+                offset += (data.Count + 3) & ~3;
+            }
+            foreach (var (_, data) in tables)
+            {
+                font.AddRange(data);
+                font.AddRange(new byte[(4 - data.Count % 4) % 4]);
+            }
+            return font.ToArray();
+        }
+
+        private static void WriteBigEndian(List<byte> bytes, uint value, int size)
+        {
+            for (var shift = 8 * (size - 1); shift >= 0; shift -= 8)
+                bytes.Add((byte)(value >> shift));
+        }
+
+        [TestMethod]
+        public void TestSupportedFeaturesMatchLibrary()
+        {
+            var features = TaffyTree.SupportedFeatures;
+            var known = TaffyFeatures.Flexbox | TaffyFeatures.Grid | TaffyFeatures.BlockLayout | TaffyFeatures.Calc | TaffyFeatures.Serde | TaffyFeatures.Debug;
+            Assert.AreEqual(TaffyFeatures.None, features & ~known);
+
+            // each flag is set exactly when the library accepts what it stands for. Serde adds nothing to the exports.
+            Assert.AreEqual(features.HasFlag(TaffyFeatures.Flexbox), Parses("* { display: flex }"));
+            Assert.AreEqual(features.HasFlag(TaffyFeatures.Flexbox), Parses("* { flex-direction: column }"));
+            Assert.AreEqual(features.HasFlag(TaffyFeatures.Grid), Parses("* { display: grid }"));
+            Assert.AreEqual(features.HasFlag(TaffyFeatures.Grid), Parses("* { grid-template-columns: 1fr 2fr }"));
+            Assert.AreEqual(features.HasFlag(TaffyFeatures.BlockLayout), Parses("* { display: block }"));
+            Assert.AreEqual(features.HasFlag(TaffyFeatures.BlockLayout), Parses("* { text-align: center }"));
+            Assert.AreEqual(features.HasFlag(TaffyFeatures.Calc), Parses("* { width: calc(100% - 10px) }"));
+            Assert.IsTrue(Parses("* { display: none; width: 10px }"));
+
+            using var tree = new TaffyTree();
+            Assert.AreEqual(features.HasFlag(TaffyFeatures.Debug), tree.SetLogger((_, _, _) => { }));
+        }
+
+        private static bool Parses(string css)
+        {
+            try
+            {
+                using var stylesheet = new Stylesheet(css);
+                return true;
+            }
+            catch (ArgumentException)
+            {
+                return false;
+            }
+        }
+
+        [TestMethod]
+        public void TestLogger()
+        {
+            using var tree = new TaffyTree();
+            var inner = tree.NewLeaf(new Style()
+            {
+                Size = new(50f, 10f),
+            });
+            var collapsed = tree.NewWithChildren(new Style()
+            {
+                Size = new(Dimension.FromLength(0f), Dimension.Auto),
+            }, inner);
+            var root = tree.NewWithChildren(new Style()
+            {
+                Size = new(100f, 100f),
+            }, collapsed);
+
+            var lines = new List<(LogSeverity Severity, ulong? Node, string Message)>();
+            if (!tree.SetLogger((severity, node, message) => lines.Add((severity, node?.Id, message))))
+            {
+                Assert.AreEqual(TaffyFeatures.None, TaffyTree.SupportedFeatures & TaffyFeatures.Debug);
+                Assert.Inconclusive("taffy_ffi was built without the debug feature");
+            }
+
+            // the lines come once the layout is done, in the order they were logged, without touching stdout
+            var stdout = Console.Out;
+            using var captured = new StringWriter();
+            Console.SetOut(captured);
+            try
+            {
+                Assert.IsTrue(root.ComputeLayout(new(100f, 100f)));
+            }
+            finally
+            {
+                Console.SetOut(stdout);
+            }
+            Assert.AreEqual("", captured.ToString());
+
+            Assert.AreEqual(LogSeverity.Trace, lines[0].Severity);
+            Assert.AreEqual(root.Id, lines[0].Node);
+            Assert.AreEqual(LogSeverity.Info, lines[^1].Severity);
+            Assert.AreEqual(root.Id, lines[^1].Node);
+            Assert.IsTrue(lines.All(line => line.Node == root.Id || line.Node == collapsed.Id || line.Node == inner.Id));
+            Assert.IsTrue(lines.Any(line => line.Severity == LogSeverity.Warning && line.Node == inner.Id && line.Message.Contains("0 px wide although its size is 50 px")));
+
+            // no more lines once the logger is removed
+            lines.Clear();
+            Assert.IsTrue(tree.SetLogger(null));
+            Assert.IsTrue(tree.MarkDirty(root));
+            Assert.IsTrue(root.ComputeLayout(new(100f, 100f)));
+            Assert.AreEqual(0, lines.Count);
+        }
+
+        [TestMethod]
+        public void TestStatsOfCachedLayout()
+        {
+            using var tree = new TaffyTree();
+            var measures = 0;
+            tree.SetMeasureFunction((node, knownDimensions, availableSpace) =>
+            {
+                measures++;
+                return new MeasureOutput(40f, 20f);
+            });
+
+            var leaf = tree.NewLeaf(new Style());
+            var root = tree.NewWithChildren(new Style()
+            {
+                Size = new(100f, 100f),
+            }, leaf);
+            Assert.IsTrue(leaf.SetMeasured());
+
+            Assert.IsTrue(root.ComputeLayout(new(100f, 100f)));
+            var first = tree.GetStats();
+            Assert.AreEqual(2ul, first.NodesVisited);
+            Assert.IsTrue(first.CacheMisses >= 2);
+            Assert.IsTrue(first.Measurements > 0);
+            Assert.IsTrue(measures > 0);
+
+            // nothing changed, so the second pass is answered from the caches without measuring again
+            measures = 0;
+            Assert.IsTrue(root.ComputeLayout(new(100f, 100f)));
+            var second = tree.GetStats();
+            Assert.IsTrue(second.CacheHits > first.CacheHits);
+            Assert.AreEqual(0ul, second.CacheMisses);
+            Assert.AreEqual(0ul, second.MeasureCalls);
+            Assert.AreEqual(0ul, second.Measurements);
+            Assert.AreEqual(0, measures);
+            Assert.AreEqual(0ul, tree.GetNodeStats(leaf).Measurements);
+        }
+
+        [TestMethod]
+        public void TestResetStats()
+        {
+            using var tree = new TaffyTree();
+            var leaf = tree.NewLeaf(new Style()
+            {
+                Size = new(50f, 50f),
+            });
+            var root = tree.NewWithChildren(new Style()
+            {
+                Size = new(100f, 100f),
+            }, leaf);
+
+            Assert.IsTrue(root.ComputeLayout(new(100f, 100f)));
+            Assert.AreNotEqual(0ul, tree.GetStats().LayoutCalls);
+            Assert.AreNotEqual(0ul, tree.GetNodeStats(leaf).LayoutCalls);
+
+            tree.ResetStats();
+            var stats = tree.GetStats();
+            Assert.AreEqual(0ul, stats.NodesVisited);
+            Assert.AreEqual(0ul, stats.LayoutCalls);
+            Assert.AreEqual(0ul, stats.CacheHits);
+            Assert.AreEqual(0ul, stats.CacheMisses);
+            Assert.AreEqual(TimeSpan.Zero, stats.Total);
+            Assert.AreEqual(0ul, tree.GetNodeStats(root).LayoutCalls);
+            Assert.AreEqual(0ul, tree.GetNodeStats(leaf).LayoutCalls);
+        }
+
+        [TestMethod]
+        public void TestBatchMeasurePredictsQueries()
+        {
+            using var tree = new TaffyTree();
+            var calls = new List<MeasureQuery[]>();
+            tree.SetBatchMeasureFunction((queries, outputs) =>
+            {
+                calls.Add(queries);
+                for (var i = 0; i < queries.Length; i++)
+                {
+                    // 100px of text wrapping into 20px high lines
+                    var available = queries[i].AvailableSpace.Width;
+                    var width = queries[i].KnownDimensions.Width ?? available.Type switch
+                    {
+                        AvailableSpaceLength.AvailableSpaceType.Definite => Math.Min(available.Value, 100f),
+                        AvailableSpaceLength.AvailableSpaceType.MinContent => 30f,
+                        _ => 100f,
+                    };
+                    outputs[i] = new MeasureOutput(width, MathF.Ceiling(100f / width) * 20f);
+                }
+            });
+
+            var leaf = tree.NewLeaf(new Style());
+            var root = tree.NewWithChildren(new Style()
+            {
+                Size = new(240f, 200f),
+            }, leaf);
+            Assert.IsTrue(leaf.SetMeasured());
+
+            // a leaf that was never laid out gets its min-content and max-content sizes predicted, and every
+            // other measurement falls back to a call with a single query
+            Assert.IsTrue(root.ComputeLayout(new(240f, 200f)));
+            Assert.AreEqual(5, calls.Count);
+            Assert.AreEqual(2, calls[0].Length);
+            Assert.AreEqual(AvailableSpaceLength.AvailableSpaceType.MinContent, calls[0][0].AvailableSpace.Width.Type);
+            Assert.AreEqual(AvailableSpaceLength.AvailableSpaceType.MaxContent, calls[0][1].AvailableSpace.Width.Type);
+            Assert.IsTrue(calls[0].All(query => query.Node.Id == leaf.Id && query.KnownDimensions.Width == null && query.KnownDimensions.Height == null));
+            Assert.IsTrue(calls.Skip(1).All(call => call.Length == 1 && call[0].Node.Id == leaf.Id));
+            Assert.IsTrue(leaf.GetLayout(out var layout));
+            Assert.AreEqual(100f, layout.Size.Width);
+            Assert.AreEqual(200f, layout.Size.Height);
+
+            // once dirty again, the measurements of the last pass are all asked for up front
+            var fallbacks = calls.Skip(1).Select(call => Describe(call[0])).ToArray();
+            calls.Clear();
+            Assert.IsTrue(tree.MarkDirty(leaf));
+            Assert.IsTrue(root.ComputeLayout(new(240f, 200f)));
+            Assert.AreEqual(1, calls.Count);
+            CollectionAssert.AreEqual(fallbacks, calls[0].Select(Describe).ToArray());
+            Assert.IsTrue(leaf.GetLayout(out layout));
+            Assert.AreEqual(100f, layout.Size.Width);
+            Assert.AreEqual(200f, layout.Size.Height);
+
+            static string Describe(MeasureQuery query) =>
+                $"{query.KnownDimensions.Width} {query.KnownDimensions.Height} {query.AvailableSpace.Width.Type} {query.AvailableSpace.Width.Value} {query.AvailableSpace.Height.Type} {query.AvailableSpace.Height.Value}";
+        }
+
+        [TestMethod]
+        public void TestRelativeUnitsResolveAgain()
+        {
+            using var tree = new TaffyTree();
+            Assert.IsTrue(tree.SetViewport(new(400f, 300f)));
+
+            var panel = tree.NewLeaf(new Style()
+            {
+                Size = new(Dimension.FromViewportWidth(50f), Dimension.FromEm(2f)),
+            });
+            var label = tree.NewLeaf(new Style()
+            {
+                Size = new(Dimension.FromLength(10f), Dimension.FromEm(3f)),
+            });
+            var root = tree.NewWithChildren(new Style()
+            {
+                Size = new(1000f, 300f),
+            }, panel, label);
+
+            Assert.IsTrue(root.ComputeLayout(new(1000f, 300f)));
+            Assert.IsTrue(panel.GetLayout(out var panelLayout));
+            Assert.AreEqual(200f, panelLayout.Size.Width);
+            Assert.AreEqual(32f, panelLayout.Size.Height);
+
+            // only the node using viewport units changes
+            Assert.IsTrue(tree.SetViewport(new(800f, 300f)));
+            Assert.IsTrue(panel.IsDirty);
+            Assert.IsFalse(label.IsDirty);
+            Assert.IsTrue(root.ComputeLayout(new(1000f, 300f)));
+            Assert.IsTrue(panel.GetLayout(out panelLayout));
+            Assert.AreEqual(400f, panelLayout.Size.Width);
+
+            Assert.IsTrue(tree.SetFontSize(panel, 10f));
+            Assert.IsFalse(label.IsDirty);
+            Assert.IsTrue(root.ComputeLayout(new(1000f, 300f)));
+            Assert.IsTrue(panel.GetLayout(out panelLayout));
+            Assert.AreEqual(20f, panelLayout.Size.Height);
+
+            // the panel keeps its own font size
+            Assert.IsTrue(tree.SetFontSize(root, 20f));
+            Assert.IsTrue(root.ComputeLayout(new(1000f, 300f)));
+            Assert.IsTrue(panel.GetLayout(out panelLayout));
+            Assert.IsTrue(label.GetLayout(out var labelLayout));
+            Assert.AreEqual(20f, panelLayout.Size.Height);
+            Assert.AreEqual(60f, labelLayout.Size.Height);
+        }
+
+        // A root (1) laying out two children (2 and 3) in a row, the second one measured if the host can
+        private sealed class RowHost : HostTree
+        {
+            public readonly Dictionary<ulong, SharedStyle> Styles = new();
+            public readonly Dictionary<ulong, Layout> Layouts = new();
+
+            public RowHost(bool measureLeaves) : base(measureLeaves)
+            {
+            }
+
+            protected override int GetChildCount(ulong node) => node == 1 ? 2 : 0;
+
+            protected override ulong GetChildAt(ulong node, int index) => (ulong)index + 2;
+
+            protected override ulong GetParent(ulong node) => node == 1 ? 0ul : 1ul;
+
+            protected override SharedStyle? GetStyle(ulong node) => Styles.GetValueOrDefault(node);
+
+            protected override void SetLayout(ulong node, Layout layout) => Layouts[node] = layout;
+
+            protected override MeasureOutput Measure(ulong node, Size<float?> knownDimensions, AvailableSpace availableSpace) =>
+                node == 3 ? new MeasureOutput(30f, 10f) : default;
+        }
+
+        [TestMethod]
+        public void TestHostTreeLayout()
+        {
+            using var rootStyle = new SharedStyle(new Style() { Size = new(100f, 50f) });
+            using var growStyle = new SharedStyle(new Style() { FlexGrow = 1f });
+
+            foreach (var measured in new[] { false, true })
+            {
+                using var host = new RowHost(measured);
+                host.Styles[1] = rootStyle;
+                host.Styles[2] = growStyle;
+
+                Assert.IsTrue(host.ComputeLayout(1, new(100f, 50f)));
+                Assert.AreEqual(100f, host.Layouts[1].Size.Width);
+                // the third node has the default style, so it is only as wide as its measured content
+                var contentWidth = measured ? 30f : 0f;
+                Assert.AreEqual(contentWidth, host.Layouts[3].Size.Width);
+                Assert.AreEqual(50f, host.Layouts[3].Size.Height);
+                Assert.AreEqual(100f - contentWidth, host.Layouts[2].Size.Width);
+                Assert.AreEqual(100f - contentWidth, host.Layouts[3].Location.X);
+            }
+        }
+
+        [TestMethod]
+        public void TestCustomLayoutPlacesChildren()
+        {
+            using var tree = new TaffyTree();
+
+            var first = tree.NewLeaf(new Style() { Size = new(30f, 10f) });
+            var second = tree.NewLeaf(new Style() { Size = new(40f, 20f) });
+            var stack = tree.NewWithChildren(new Style(), first, second);
+            var root = tree.NewWithChildren(new Style()
+            {
+                Size = new(200f, 100f),
+                AlignItems = AlignItems.FlexStart,
+            }, stack);
+            Assert.IsTrue(tree.SetCustomLayout(stack, true));
+
+            int calls = 0, rejected = 0;
+            tree.SetCustomLayoutFunction((context, node, input) =>
+            {
+                calls++;
+                // stacks the children with 5 between them
+                float width = 0f, y = 0f;
+                for (var i = 0; i < context.ChildCount(node); i++)
+                {
+                    var child = context.ChildAt(node, i)!.Value;
+                    var size = context.ComputeChild(child, new LayoutInput() { RunMode = input.RunMode })!.Value;
+                    if (input.RunMode == RunMode.PerformLayout)
+                        Assert.IsTrue(context.SetChildLayout(child, new(0f, y), new(size.Width, size.Height), i));
+                    width = Math.Max(width, size.Width);
+                    y += size.Height + 5f;
+                }
+
+                // only the children of the node being laid out can be laid out or placed
+                if (context.ComputeChild(root, new LayoutInput() { RunMode = RunMode.ComputeSize }) == null)
+                    rejected++;
+                if (!context.SetChildLayout(node, new(0f, 0f), new(1f, 1f)))
+                    rejected++;
+                return new LayoutOutput() { Width = width, Height = y - 5f };
+            });
+
+            Assert.IsTrue(root.ComputeLayout(new(200f, 100f)));
+            Assert.IsTrue(stack.GetLayout(out var stackLayout));
+            Assert.IsTrue(second.GetLayout(out var secondLayout));
+            Assert.AreEqual(40f, stackLayout.Size.Width);
+            Assert.AreEqual(35f, stackLayout.Size.Height);
+            Assert.AreEqual(0f, secondLayout.Location.X);
+            Assert.AreEqual(15f, secondLayout.Location.Y);
+            Assert.AreEqual(40f, secondLayout.Size.Width);
+            Assert.IsTrue(calls > 0);
+            Assert.AreEqual(2 * calls, rejected);
+        }
+
+        [TestMethod]
+        public void TestStyleLerp()
+        {
+            using var tree = new TaffyTree();
+
+            var first = tree.NewLeaf(new Style() { Size = new(10f, 10f) });
+            var second = tree.NewLeaf(new Style() { Size = new(10f, 10f) });
+            var root = tree.NewWithChildren(new Style(), first, second);
+            var from = new Style() { Size = new(100f, 50f), FlexDirection = FlexDirection.Row };
+            var to = new Style() { Size = new(300f, 50f), FlexDirection = FlexDirection.Column };
+
+            Layout Apply(float t)
+            {
+                using var style = SharedStyle.Lerp(from, to, t);
+                Assert.IsTrue(root.SetStyle(style));
+                Assert.IsTrue(root.ComputeLayout(AvailableSpace.MaxContent));
+                Assert.IsTrue(second.GetLayout(out var secondLayout));
+                return secondLayout;
+            }
+
+            // lengths interpolate and the flex direction switches halfway
+            var layout = Apply(0.25f);
+            Assert.IsTrue(root.GetLayout(out var rootLayout));
+            Assert.AreEqual(150f, rootLayout.Size.Width);
+            Assert.AreEqual(10f, layout.Location.X);
+            Assert.AreEqual(0f, layout.Location.Y);
+
+            layout = Apply(0.5f);
+            Assert.IsTrue(root.GetLayout(out rootLayout));
+            Assert.AreEqual(200f, rootLayout.Size.Width);
+            Assert.AreEqual(0f, layout.Location.X);
+            Assert.AreEqual(10f, layout.Location.Y);
+
+            // overshooting easing curves extrapolate, but sizes do not go below 0
+            Apply(2f);
+            Assert.IsTrue(root.GetLayout(out rootLayout));
+            Assert.AreEqual(500f, rootLayout.Size.Width);
+
+            Apply(-1f);
+            Assert.IsTrue(root.GetLayout(out rootLayout));
+            Assert.AreEqual(0f, rootLayout.Size.Width);
+
+            Assert.ThrowsException<InvalidOperationException>(() => SharedStyle.Lerp(from, to, float.NaN));
+        }
+
+        [TestMethod]
+        public void TestUpdateSharedStyle()
+        {
+            using var tree = new TaffyTree();
+            using var shared = new SharedStyle(new Style() { Size = new(10f, 10f) });
+
+            var first = tree.NewLeaf(shared);
+            var second = tree.NewLeaf(new Style());
+            Assert.IsTrue(second.SetStyle(shared));
+            var detached = tree.NewLeaf(shared);
+            Assert.IsTrue(detached.SetStyle(new Style() { Size = new(5f, 5f) }));
+            var root = tree.NewWithChildren(new Style() { FlexDirection = FlexDirection.Column }, first, second, detached);
+
+            Assert.IsTrue(tree.UpdateSharedStyle(shared, new Style() { Size = new(30f, 20f) }));
+            Assert.IsTrue(root.ComputeLayout(AvailableSpace.MaxContent));
+
+            // both nodes using the shared style are restyled, the one given its own style since is not
+            Assert.IsTrue(first.GetLayout(out var firstLayout));
+            Assert.IsTrue(second.GetLayout(out var secondLayout));
+            Assert.IsTrue(detached.GetLayout(out var detachedLayout));
+            Assert.AreEqual(30f, firstLayout.Size.Width);
+            Assert.AreEqual(20f, firstLayout.Size.Height);
+            Assert.AreEqual(30f, secondLayout.Size.Width);
+            Assert.AreEqual(20f, secondLayout.Location.Y);
+            Assert.AreEqual(5f, detachedLayout.Size.Width);
+            Assert.AreEqual(40f, detachedLayout.Location.Y);
+        }
+
+        [TestMethod]
+        public void TestInterpolateLayouts()
+        {
+            using var tree = new TaffyTree();
+
+            var removed = tree.NewLeaf(new Style() { Size = new(100f, 50f) });
+            var kept = tree.NewLeaf(new Style() { Size = new(50f, 50f) });
+            var root = tree.NewWithChildren(new Style() { Size = new(300f, 50f) }, removed, kept);
+            Assert.IsTrue(root.ComputeLayout(new(300f, 50f)));
+            using var snapshot = tree.SnapshotLayouts(root);
+            Assert.IsNotNull(snapshot);
+
+            Assert.IsTrue(tree.Remove(removed));
+            var added = tree.NewLeaf(new Style() { Size = new(80f, 50f) });
+            Assert.IsTrue(tree.AddChild(root, added));
+            Assert.IsTrue(root.ComputeLayout(new(300f, 50f)));
+
+            // the current nodes in order, then the ones that disappeared
+            var rects = tree.InterpolateLayouts(root, snapshot, 0.25f);
+            CollectionAssert.AreEqual(new[] { root.Id, kept.Id, added.Id, removed.Id }, rects.Select(rect => rect.Node.Id).ToArray());
+            CollectionAssert.AreEqual(new[] { LayoutChange.Moved, LayoutChange.Moved, LayoutChange.Appeared, LayoutChange.Disappeared }, rects.Select(rect => rect.Change).ToArray());
+
+            Assert.AreEqual(75f, rects[1].Location.X);
+            Assert.AreEqual(1f, rects[1].Presence);
+
+            // appearing and disappearing nodes stay at their own box and fade
+            Assert.AreEqual(50f, rects[2].Location.X);
+            Assert.AreEqual(80f, rects[2].Size.Width);
+            Assert.AreEqual(0.25f, rects[2].Presence);
+            Assert.AreEqual(0f, rects[3].Location.X);
+            Assert.AreEqual(100f, rects[3].Size.Width);
+            Assert.AreEqual(0.75f, rects[3].Presence);
+
+            // past the end boxes extrapolate but presence stays within 0 and 1
+            rects = tree.InterpolateLayouts(root, snapshot, 1.5f);
+            Assert.AreEqual(-50f, rects[1].Location.X);
+            Assert.AreEqual(1f, rects[2].Presence);
+            Assert.AreEqual(0f, rects[3].Presence);
+        }
+
+        [TestMethod]
+        public void TestComputeLayoutFit()
+        {
+            using var tree = new TaffyTree();
+
+            var content = tree.NewLeaf(new Style() { Size = new(120f, 30f) });
+            var popup = tree.NewWithChildren(new Style(), content);
+
+            Assert.IsTrue(tree.ComputeLayoutFit(popup, new(0f, 0f), new(500f, 500f), out var size));
+            Assert.AreEqual(120f, size.Width);
+            Assert.AreEqual(30f, size.Height);
+
+            // the content is smaller than the min bounds
+            Assert.IsTrue(tree.ComputeLayoutFit(popup, new(200f, 50f), new(500f, 500f), out size));
+            Assert.AreEqual(200f, size.Width);
+            Assert.AreEqual(50f, size.Height);
+            Assert.IsTrue(popup.GetLayout(out var popupLayout));
+            Assert.AreEqual(200f, popupLayout.Size.Width);
+            Assert.AreEqual(50f, popupLayout.Size.Height);
+
+            // and larger than the max width, with no limit on the height
+            Assert.IsTrue(tree.ComputeLayoutFit(popup, new(0f, 0f), new(100f, float.PositiveInfinity), out size));
+            Assert.AreEqual(100f, size.Width);
+            Assert.AreEqual(30f, size.Height);
+            Assert.IsTrue(content.GetLayout(out var contentLayout));
+            Assert.AreEqual(100f, contentLayout.Size.Width);
+
+            Assert.IsFalse(tree.ComputeLayoutFit(popup, new(200f, 0f), new(100f, 100f), out _));
+        }
+
+        [TestMethod]
+        public void TestComputeLayoutsAfterOtherPasses()
+        {
+            using var tree = new TaffyTree();
+
+            var content = tree.NewLeaf(new Style() { Size = new(50f, 20f) });
+            var root = tree.NewWithChildren(new Style(), content);
+            var roots = new[] { root };
+            var spaces = new AvailableSpace[] { new(200f, 100f) };
+
+            Assert.IsTrue(root.ComputeLayout(new(200f, 100f)));
+            CollectionAssert.AreEqual(new[] { LayoutResult.Skipped }, tree.ComputeLayouts(roots, spaces));
+
+            // a fit layout replaces the one computed in that available space
+            Assert.IsTrue(tree.ComputeLayoutFit(root, new(0f, 0f), new(500f, 500f), out _));
+            CollectionAssert.AreEqual(new[] { LayoutResult.Computed }, tree.ComputeLayouts(roots, spaces));
+
+            // and so does attaching the root under another node
+            var parent = tree.NewLeaf(new Style());
+            Assert.IsTrue(tree.AddChild(parent, root));
+            CollectionAssert.AreEqual(new[] { LayoutResult.Computed }, tree.ComputeLayouts(roots, spaces));
+        }
+
+        [TestMethod]
+        public void TestNativeAbiRejectsMismatch()
+        {
+            NativeAbi.EnsureCompatible();
+
+            var sizes = new c_StructSizes();
+            Assert.AreEqual(0, NativeMethods.taffy_ffi_struct_sizes(&sizes, (nuint)sizeof(c_StructSizes)));
+            NativeAbi.Verify(NativeAbi.Version, sizes);
+
+            var mismatched = sizes;
+            mismatched.style.size += 4;
+            var error = Assert.ThrowsException<InvalidOperationException>(() => NativeAbi.Verify(NativeAbi.Version, mismatched));
+            StringAssert.Contains(error.Message, nameof(c_Style));
+            Assert.ThrowsException<InvalidOperationException>(() => NativeAbi.Verify(NativeAbi.Version + 1, sizes));
+
+            // only as many bytes as the caller has room for are written
+            var partial = new c_StructSizes();
+            Assert.AreEqual(0, NativeMethods.taffy_ffi_struct_sizes(&partial, (nuint)sizeof(c_TypeLayout)));
+            Assert.AreEqual(sizes.length.size, partial.length.size);
+            Assert.AreEqual(0u, partial.size.size);
+        }
     }
 }
@@ -0,0 +1,43 @@
+﻿namespace TaffySharp
+{
+    /// <summary>
+    /// The box of a node during an animated layout change, in the coordinates of <see cref="TaffyTree.GetAbsoluteLocation"/>.
+    /// See <see cref="TaffyTree.InterpolateLayouts"/>.
+    /// </summary>
+    public readonly struct AnimatedRect
+    {
+        /// <summary>
+        /// The node, which may no longer be in the tree if it <see cref="LayoutChange.Disappeared"/>
+        /// </summary>
+        public Node Node { get; }
+
+        /// <summary>
+        /// The top-left corner of the border box
+        /// </summary>
+        public Point<float> Location { get; }
+
+        /// <summary>
+        /// The size of the border box
+        /// </summary>
+        public Size<float> Size { get; }
+
+        /// <summary>
+        /// How the node changed since the snapshot
+        /// </summary>
+        public LayoutChange Change { get; }
+
+        /// <summary>
+        /// From 0 to 1, how far the node has appeared or how much of it is left while it disappears. Always 1 for moved nodes.
+        /// </summary>
+        public float Presence { get; }
+
+        internal AnimatedRect(Node node, Point<float> location, Size<float> size, LayoutChange change, float presence)
+        {
+            Node = node;
+            Location = location;
+            Size = size;
+            Change = change;
+            Presence = presence;
+        }
+    }
+}
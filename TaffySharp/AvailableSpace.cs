@@ -67,6 +67,10 @@ namespace TaffySharp
         /// <param name="height"></param>
         public AvailableSpace(float width, float height) => (Width, Height) = (AvailableSpaceLength.Definitive(width), AvailableSpaceLength.Definitive(height));
 
+        internal AvailableSpace(c_AvailableSpace space) => (Width, Height) = (
+            new AvailableSpaceLength((AvailableSpaceLength.AvailableSpaceType)space.width.dim, space.width.value),
+            new AvailableSpaceLength((AvailableSpaceLength.AvailableSpaceType)space.height.dim, space.height.value));
+
         /// <summary>
         /// Convert to a C struct
         /// </summary>
@@ -0,0 +1,36 @@
+﻿namespace TaffySharp
+{
+    /// <summary>
+    /// The area a node is clipped to by the overflow of its ancestors, in the coordinates of <see cref="TaffyTree.GetAbsoluteLocation"/>.
+    /// An axis no ancestor clips spans from negative to positive infinity.
+    /// </summary>
+    public readonly struct ClipRect
+    {
+        /// <summary>
+        /// The clipped node
+        /// </summary>
+        public Node Node { get; }
+
+        /// <summary>
+        /// The top-left corner
+        /// </summary>
+        public Point<float> Min { get; }
+
+        /// <summary>
+        /// The bottom-right corner
+        /// </summary>
+        public Point<float> Max { get; }
+
+        /// <summary>
+        /// Whether the node is clipped away entirely
+        /// </summary>
+        public bool IsEmpty => Max.X <= Min.X || Max.Y <= Min.Y;
+
+        internal ClipRect(Node node, Point<float> min, Point<float> max)
+        {
+            Node = node;
+            Min = min;
+            Max = max;
+        }
+    }
+}
@@ -0,0 +1,160 @@
+﻿using System;
+using TaffySharp.Lib;
+
+namespace TaffySharp
+{
+    /// <summary>
+    /// Lays out a node flagged with <see cref="TaffyTree.SetCustomLayout"/>. When <see cref="LayoutInput.RunMode"/> is
+    /// <see cref="RunMode.PerformLayout"/> it must also place every child with <see cref="LayoutContext.SetChildLayout"/>.
+    /// </summary>
+    /// <returns>The size of the node</returns>
+    public delegate LayoutOutput CustomLayoutFunction(LayoutContext context, Node node, LayoutInput input);
+
+    /// <summary>
+    /// The inputs of a layout pass over one node
+    /// </summary>
+    public class LayoutInput
+    {
+        public RunMode RunMode { get; set; }
+
+        public SizingMode SizingMode { get; set; }
+
+        public RequestedAxis Axis { get; set; }
+
+        /// <summary>
+        /// Sizes the node must have, set by its parent
+        /// </summary>
+        public Size<float?> KnownDimensions { get; set; } = new Size<float?>(null, null);
+
+        /// <summary>
+        /// The size of the parent, used to resolve percentages
+        /// </summary>
+        public Size<float?> ParentSize { get; set; } = new Size<float?>(null, null);
+
+        public AvailableSpace AvailableSpace { get; set; } = AvailableSpace.MaxContent;
+
+        /// <summary>
+        /// Create new layout inputs
+        /// </summary>
+        public LayoutInput()
+        {
+        }
+
+        internal unsafe LayoutInput(c_LayoutInput input)
+        {
+            RunMode = (RunMode)input.run_mode;
+            SizingMode = (SizingMode)input.sizing_mode;
+            Axis = (RequestedAxis)input.axis;
+            KnownDimensions = new Size<float?>(FromNaN(input.known_dimensions[0]), FromNaN(input.known_dimensions[1]));
+            ParentSize = new Size<float?>(FromNaN(input.parent_size[0]), FromNaN(input.parent_size[1]));
+            AvailableSpace = new AvailableSpace(input.available_space);
+        }
+
+        internal unsafe c_LayoutInput ToCStruct()
+        {
+            var input = new c_LayoutInput
+            {
+                run_mode = (int)RunMode,
+                sizing_mode = (int)SizingMode,
+                axis = (int)Axis,
+                available_space = AvailableSpace.ToCStruct(),
+            };
+            input.known_dimensions[0] = KnownDimensions.Width ?? float.NaN;
+            input.known_dimensions[1] = KnownDimensions.Height ?? float.NaN;
+            input.parent_size[0] = ParentSize.Width ?? float.NaN;
+            input.parent_size[1] = ParentSize.Height ?? float.NaN;
+            return input;
+        }
+
+        private static float? FromNaN(float value) => float.IsNaN(value) ? null : value;
+    }
+
+    /// <summary>
+    /// The result of a layout pass over one node
+    /// </summary>
+    public struct LayoutOutput
+    {
+        public float Width { get; set; }
+
+        public float Height { get; set; }
+
+        public float ContentWidth { get; set; }
+
+        public float ContentHeight { get; set; }
+
+        /// <summary>
+        /// The distance from the top of the node to its first baseline, if it has one
+        /// </summary>
+        public float? FirstBaseline { get; set; }
+
+        internal unsafe LayoutOutput(c_LayoutOutput output)
+        {
+            Width = output.size[0];
+            Height = output.size[1];
+            ContentWidth = output.content_size[0];
+            ContentHeight = output.content_size[1];
+            FirstBaseline = float.IsNaN(output.first_baselines[1]) ? null : output.first_baselines[1];
+        }
+
+        internal unsafe c_LayoutOutput ToCStruct()
+        {
+            var output = new c_LayoutOutput();
+            output.size[0] = Width;
+            output.size[1] = Height;
+            output.content_size[0] = ContentWidth;
+            output.content_size[1] = ContentHeight;
+            output.first_baselines[0] = float.NaN;
+            output.first_baselines[1] = FirstBaseline ?? float.NaN;
+            return output;
+        }
+    }
+
+    /// <summary>
+    /// Access to the tree from inside a <see cref="CustomLayoutFunction"/>. Only valid during the call.
+    /// </summary>
+    public readonly unsafe struct LayoutContext
+    {
+        private readonly TaffyTree _tree;
+        private readonly UIntPtr _context;
+
+        internal LayoutContext(TaffyTree tree, UIntPtr context)
+        {
+            _tree = tree;
+            _context = context;
+        }
+
+        public int ChildCount(Node node) => (int)NativeMethods.taffy_layoutcontext_child_count(_context, node.Id);
+
+        public Node? ChildAt(Node node, int index)
+        {
+            var id = NativeMethods.taffy_layoutcontext_child_at(_context, node.Id, (nuint)index);
+            return id == 0 ? null : new Node(_tree, id);
+        }
+
+        /// <summary>
+        /// Runs the layout of <paramref name="child"/>, with flexbox, grid, block or custom layout as its style says
+        /// </summary>
+        /// <returns>The size of the child, or null if it is not a child of the node being laid out</returns>
+        public LayoutOutput? ComputeChild(Node child, LayoutInput input)
+        {
+            var c_input = input.ToCStruct();
+            var c_output = new c_LayoutOutput();
+            if (NativeMethods.taffy_layoutcontext_compute_child(_context, child.Id, &c_input, &c_output) != 0)
+                return null;
+            return new LayoutOutput(c_output);
+        }
+
+        /// <summary>
+        /// Places <paramref name="child"/>, relative to the border box of its parent. Fails if it is not a child of the node being laid out.
+        /// </summary>
+        public bool SetChildLayout(Node child, Point<float> location, Size<float> size, int order = 0)
+        {
+            var layout = new c_Layout { order = order };
+            layout.location[0] = location.X;
+            layout.location[1] = location.Y;
+            layout.size[0] = size.Width;
+            layout.size[1] = size.Height;
+            return NativeMethods.taffy_layoutcontext_set_child_layout(_context, child.Id, &layout) == 0;
+        }
+    }
+}
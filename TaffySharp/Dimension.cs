@@ -43,6 +43,59 @@ namespace TaffySharp
         /// <returns></returns>
         public static Dimension FromPercentage(float percentage) => new(DimensionType.Percent, percentage);
 
+        /// <summary>
+        /// A length relative to the viewport width, where 100 is the full width (CSS <c>vw</c>)
+        /// </summary>
+        /// <param name="value"></param>
+        /// <returns></returns>
+        public static Dimension FromViewportWidth(float value) => new(DimensionType.ViewportWidth, value);
+
+        /// <summary>
+        /// A length relative to the viewport height, where 100 is the full height (CSS <c>vh</c>)
+        /// </summary>
+        /// <param name="value"></param>
+        /// <returns></returns>
+        public static Dimension FromViewportHeight(float value) => new(DimensionType.ViewportHeight, value);
+
+        /// <summary>
+        /// A length relative to the smaller side of the viewport (CSS <c>vmin</c>)
+        /// </summary>
+        /// <param name="value"></param>
+        /// <returns></returns>
+        public static Dimension FromViewportMin(float value) => new(DimensionType.ViewportMin, value);
+
+        /// <summary>
+        /// A length relative to the larger side of the viewport (CSS <c>vmax</c>)
+        /// </summary>
+        /// <param name="value"></param>
+        /// <returns></returns>
+        public static Dimension FromViewportMax(float value) => new(DimensionType.ViewportMax, value);
+
+        /// <summary>
+        /// A length relative to the font size of the node (CSS <c>em</c>)
+        /// </summary>
+        /// <param name="value"></param>
+        /// <returns></returns>
+        public static Dimension FromEm(float value) => new(DimensionType.Em, value);
+
+        /// <summary>
+        /// A length relative to the font size of the tree (CSS <c>rem</c>)
+        /// </summary>
+        /// <param name="value"></param>
+        /// <returns></returns>
+        public static Dimension FromRem(float value) => new(DimensionType.Rem, value);
+
+        /// <summary>
+        /// A <c>calc()</c>, <c>min()</c>, <c>max()</c> or <c>clamp()</c> expression of lengths in px and percentages,
+        /// such as <c>calc(100% - 32px)</c>. Relative units such as <c>vw</c> and <c>em</c> are not accepted in them.
+        /// Expressions are kept for the lifetime of the process and at most 2^24 distinct ones can be created, so reuse
+        /// them rather than creating one per value.
+        /// </summary>
+        /// <param name="expression"></param>
+        /// <returns></returns>
+        /// <exception cref="ArgumentException">The expression is invalid</exception>
+        public static Dimension FromCalc(string expression) => new(DimensionType.Calc, ParseCalc(expression));
+
         /// <inheritdoc />
         public override DimensionType Type
         {
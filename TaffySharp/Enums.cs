@@ -25,7 +25,43 @@ namespace TaffySharp
         /// <summary>
         /// A percentage of the parent element. Should be specified as 0.0 - 1.0.
         /// </summary>
-        Percent = 2
+        Percent = 2,
+
+        /// <summary>
+        /// A percentage of the tree's viewport width, see <see cref="TaffyTree.SetViewport"/>. Should be specified as 0 - 100.
+        /// </summary>
+        ViewportWidth = 8,
+
+        /// <summary>
+        /// A percentage of the tree's viewport height. Should be specified as 0 - 100.
+        /// </summary>
+        ViewportHeight = 9,
+
+        /// <summary>
+        /// A percentage of the smaller side of the tree's viewport. Should be specified as 0 - 100.
+        /// </summary>
+        ViewportMin = 10,
+
+        /// <summary>
+        /// A percentage of the larger side of the tree's viewport. Should be specified as 0 - 100.
+        /// </summary>
+        ViewportMax = 11,
+
+        /// <summary>
+        /// A multiple of the node's font size, see <see cref="TaffyTree.SetFontSize"/>.
+        /// </summary>
+        Em = 12,
+
+        /// <summary>
+        /// A multiple of the tree's font size, see <see cref="TaffyTree.SetRootFontSize"/>.
+        /// </summary>
+        Rem = 13,
+
+        /// <summary>
+        /// A <c>calc()</c>, <c>min()</c>, <c>max()</c> or <c>clamp()</c> expression of lengths and percentages, such as
+        /// <c>calc(100% - 32px)</c>. The value identifies the parsed expression, create these with <c>FromCalc</c>.
+        /// </summary>
+        Calc = 14
     }
 
     /// <summary>
@@ -457,4 +493,147 @@ namespace TaffySharp
         /// </summary>
         ColumnDense,
     }
+
+    /// <summary>
+    /// What a layout pass over a node should produce, see <see cref="LayoutInput"/>
+    /// </summary>
+    public enum RunMode
+    {
+        /// <summary>
+        /// Compute the node's size and place its children
+        /// </summary>
+        PerformLayout,
+
+        /// <summary>
+        /// Only compute the node's size
+        /// </summary>
+        ComputeSize,
+
+        /// <summary>
+        /// The node is inside a <see cref="Display.None"/> subtree and gets a zero-sized layout
+        /// </summary>
+        PerformHiddenLayout,
+    }
+
+    /// <summary>
+    /// Whether the node's own size styles should be applied during a layout pass
+    /// </summary>
+    public enum SizingMode
+    {
+        /// <summary>
+        /// Ignore the node's size styles and size it from its content
+        /// </summary>
+        ContentSize,
+
+        /// <summary>
+        /// Apply the node's size styles
+        /// </summary>
+        InherentSize,
+    }
+
+    /// <summary>
+    /// The axes a layout pass needs a size for
+    /// </summary>
+    public enum RequestedAxis
+    {
+        Horizontal,
+        Vertical,
+        Both,
+    }
+
+    /// <summary>
+    /// The severity of a line passed to a <see cref="LogFunction"/>
+    /// </summary>
+    public enum LogSeverity
+    {
+        /// <summary>
+        /// The inputs a node is about to be laid out with
+        /// </summary>
+        Trace,
+
+        /// <summary>
+        /// A summary of a node that was laid out
+        /// </summary>
+        Info,
+
+        /// <summary>
+        /// Something that is likely to be a mistake in the styles, such as a node with a size collapsed to 0 px
+        /// </summary>
+        Warning,
+    }
+
+    /// <summary>
+    /// The cargo features the native library was built with, see <see cref="TaffyTree.SupportedFeatures"/>
+    /// </summary>
+    [Flags]
+    public enum TaffyFeatures
+    {
+        None = 0,
+
+        /// <summary>
+        /// <see cref="Display.Flex"/> and the flexbox properties
+        /// </summary>
+        Flexbox = 1 << 0,
+
+        /// <summary>
+        /// <see cref="Display.Grid"/> and the grid properties
+        /// </summary>
+        Grid = 1 << 1,
+
+        /// <summary>
+        /// <see cref="Display.Block"/> and <see cref="TextAlign"/>
+        /// </summary>
+        BlockLayout = 1 << 2,
+
+        Calc = 1 << 3,
+
+        Serde = 1 << 4,
+
+        /// <summary>
+        /// Logging with <see cref="TaffyTree.SetLogger"/>
+        /// </summary>
+        Debug = 1 << 5,
+    }
+
+    /// <summary>
+    /// How a node changed between a <see cref="LayoutSnapshot"/> and the current layout
+    /// </summary>
+    public enum LayoutChange
+    {
+        /// <summary>
+        /// The node is in both layouts, and moves from its old box to its new one
+        /// </summary>
+        Moved = 0,
+
+        /// <summary>
+        /// The node is new, and stays at its new box while it appears
+        /// </summary>
+        Appeared = 1,
+
+        /// <summary>
+        /// The node is gone, and stays at its old box while it disappears
+        /// </summary>
+        Disappeared = 2,
+    }
+
+    /// <summary>
+    /// What <see cref="TaffyTree.ComputeLayouts"/> did with a root
+    /// </summary>
+    public enum LayoutResult
+    {
+        /// <summary>
+        /// The root is not a valid node
+        /// </summary>
+        Invalid = -1,
+
+        /// <summary>
+        /// The layout of the root was up to date
+        /// </summary>
+        Skipped = 0,
+
+        /// <summary>
+        /// The root was laid out
+        /// </summary>
+        Computed = 1,
+    }
 }
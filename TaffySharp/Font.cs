@@ -0,0 +1,52 @@
+﻿using System;
+using TaffySharp.Lib;
+
+namespace TaffySharp
+{
+    /// <summary>
+    /// A TrueType or OpenType font, used to measure text leaves natively. See <see cref="TaffyTree.SetText"/>.
+    /// </summary>
+    public unsafe class Font : IDisposable
+    {
+        private bool _disposed;
+
+        internal UIntPtr Handle { get; private set; }
+
+        /// <summary>
+        /// Loads a font from the contents of a .ttf or .otf file
+        /// </summary>
+        /// <param name="data"></param>
+        /// <exception cref="ArgumentException"><paramref name="data"/> is not a font</exception>
+        public Font(byte[] data)
+        {
+            fixed (byte* dataPtr = data)
+            {
+                Handle = NativeMethods.taffy_font_load(dataPtr, (nuint)data.Length);
+            }
+
+            if (Handle == UIntPtr.Zero)
+                throw new ArgumentException("Failed to load font", nameof(data));
+        }
+
+        /// <summary>
+        /// Releases the native font. Text leaves using it keep it alive until their text is replaced.
+        /// </summary>
+        public void Dispose()
+        {
+            if (!_disposed)
+            {
+                if (Handle != UIntPtr.Zero)
+                {
+                    NativeMethods.taffy_font_free(Handle);
+                    Handle = UIntPtr.Zero;
+                }
+                _disposed = true;
+            }
+        }
+
+        ~Font()
+        {
+            Dispose();
+        }
+    }
+}
@@ -0,0 +1,171 @@
+﻿using System;
+using System.Runtime.InteropServices;
+using TaffySharp.Lib;
+
+namespace TaffySharp
+{
+    /// <summary>
+    /// Lays out a widget hierarchy owned by the application, without mirroring it into a <see cref="TaffyTree"/>.
+    /// Nodes are the application's own non-zero handles. Only the layout caches are kept on the native side.
+    /// Viewport units in the styles of a host tree resolve to 0 and font units use a font size of 16.
+    /// </summary>
+    public abstract unsafe class HostTree : IDisposable
+    {
+        [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+        private delegate nuint ChildCountCallback(nuint userData, ulong node);
+
+        [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+        private delegate ulong ChildAtCallback(nuint userData, ulong node, nuint index);
+
+        [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+        private delegate ulong ParentCallback(nuint userData, ulong node);
+
+        [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+        private delegate nuint StyleCallback(nuint userData, ulong node);
+
+        [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+        private delegate void SetLayoutCallback(nuint userData, ulong node, c_Layout* layout);
+
+        [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+        private delegate void MeasureCallback(nuint userData, ulong node, float knownWidth, float knownHeight, c_AvailableSpace availableSpace, c_MeasureOutput* output);
+
+        // Kept alive for as long as the native side can call them
+        private readonly ChildCountCallback _childCount;
+        private readonly ChildAtCallback _childAt;
+        private readonly ParentCallback _parent;
+        private readonly StyleCallback _style;
+        private readonly SetLayoutCallback _setLayout;
+        private readonly MeasureCallback? _measure;
+
+        private UIntPtr _handle;
+        private bool _disposed;
+
+        /// <summary>
+        /// Creates the native side of the host tree, with leaves sized from their styles only
+        /// </summary>
+        /// <exception cref="InvalidOperationException"></exception>
+        protected HostTree() : this(false)
+        {
+        }
+
+        /// <summary>
+        /// Creates the native side of the host tree
+        /// </summary>
+        /// <param name="measureLeaves">Whether the content of nodes without children is sized by <see cref="Measure"/></param>
+        /// <exception cref="InvalidOperationException"></exception>
+        protected HostTree(bool measureLeaves)
+        {
+            NativeAbi.EnsureCompatible();
+            _childCount = (_, node) => (nuint)GetChildCount(node);
+            _childAt = (_, node, index) => GetChildAt(node, (int)index);
+            _parent = (_, node) => GetParent(node);
+            _style = (_, node) => GetStyle(node)?.Handle ?? UIntPtr.Zero;
+            _setLayout = (_, node, layout) => SetLayout(node, new Layout(*layout));
+            if (measureLeaves)
+                _measure = (_, node, knownWidth, knownHeight, availableSpace, output) =>
+                    *output = Measure(node, TaffyTree.KnownDimensions(knownWidth, knownHeight), new AvailableSpace(availableSpace)).ToCStruct();
+
+            var callbacks = new c_HostTreeCallbacks
+            {
+                child_count = (delegate* unmanaged[Cdecl]<nuint, ulong, nuint>)Marshal.GetFunctionPointerForDelegate(_childCount),
+                child_at = (delegate* unmanaged[Cdecl]<nuint, ulong, nuint, ulong>)Marshal.GetFunctionPointerForDelegate(_childAt),
+                parent = (delegate* unmanaged[Cdecl]<nuint, ulong, ulong>)Marshal.GetFunctionPointerForDelegate(_parent),
+                style = (delegate* unmanaged[Cdecl]<nuint, ulong, nuint>)Marshal.GetFunctionPointerForDelegate(_style),
+                set_layout = (delegate* unmanaged[Cdecl]<nuint, ulong, c_Layout*, void>)Marshal.GetFunctionPointerForDelegate(_setLayout),
+                measure = _measure == null ? null : (delegate* unmanaged[Cdecl]<nuint, ulong, float, float, c_AvailableSpace, c_MeasureOutput*, void>)Marshal.GetFunctionPointerForDelegate(_measure),
+            };
+            _handle = NativeMethods.taffy_hosttree_new(callbacks);
+
+            if (_handle == UIntPtr.Zero)
+                throw new InvalidOperationException("Failed to create HostTree");
+        }
+
+        /// <summary>
+        /// The number of children of <paramref name="node"/>
+        /// </summary>
+        protected abstract int GetChildCount(ulong node);
+
+        /// <summary>
+        /// The child of <paramref name="node"/> at <paramref name="index"/>
+        /// </summary>
+        protected abstract ulong GetChildAt(ulong node, int index);
+
+        /// <summary>
+        /// The parent of <paramref name="node"/>, or 0 for a root. Used by <see cref="MarkDirty"/> to reach ancestors.
+        /// </summary>
+        protected virtual ulong GetParent(ulong node) => 0;
+
+        /// <summary>
+        /// The style of <paramref name="node"/>, which must stay alive during <see cref="ComputeLayout"/>, or null for the default style
+        /// </summary>
+        protected abstract SharedStyle? GetStyle(ulong node);
+
+        /// <summary>
+        /// Receives the computed layout of <paramref name="node"/>
+        /// </summary>
+        protected abstract void SetLayout(ulong node, Layout layout);
+
+        /// <summary>
+        /// Sizes the content of <paramref name="node"/>, which has no children. Only called if the tree was created with <c>measureLeaves</c>.
+        /// </summary>
+        /// <param name="node"></param>
+        /// <param name="knownDimensions">Sizes the content box must have, if already decided</param>
+        /// <param name="availableSpace">The space available to the content box</param>
+        protected virtual MeasureOutput Measure(ulong node, Size<float?> knownDimensions, AvailableSpace availableSpace) => default;
+
+        /// <summary>
+        /// Lays out <paramref name="root"/> and its descendants, calling <see cref="SetLayout"/> for each of them
+        /// </summary>
+        public bool ComputeLayout(ulong root, AvailableSpace availableSpace)
+        {
+            return NativeMethods.taffy_hosttree_compute_layout(_handle, root, availableSpace.ToCStruct()) == 0;
+        }
+
+        /// <summary>
+        /// Discards the cached layout of <paramref name="node"/> and its ancestors. Call this when its style or children change.
+        /// </summary>
+        public bool MarkDirty(ulong node)
+        {
+            return NativeMethods.taffy_hosttree_mark_dirty(_handle, node) == 0;
+        }
+
+        /// <summary>
+        /// Releases the cached layout of a node that no longer exists
+        /// </summary>
+        public void RemoveNode(ulong node)
+        {
+            NativeMethods.taffy_hosttree_remove_node(_handle, node);
+        }
+
+        public void EnableRounding()
+        {
+            NativeMethods.taffy_hosttree_enable_rounding(_handle);
+        }
+
+        public void DisableRounding()
+        {
+            NativeMethods.taffy_hosttree_disable_rounding(_handle);
+        }
+
+        /// <summary>
+        /// Releases the native side of the host tree
+        /// </summary>
+        public void Dispose()
+        {
+            if (!_disposed)
+            {
+                if (_handle != UIntPtr.Zero)
+                {
+                    NativeMethods.taffy_hosttree_free(_handle);
+                    _handle = UIntPtr.Zero;
+                }
+                _disposed = true;
+            }
+        }
+
+        ~HostTree()
+        {
+            Dispose();
+        }
+    }
+}
@@ -0,0 +1,42 @@
+﻿using System;
+using TaffySharp.Lib;
+
+namespace TaffySharp
+{
+    /// <summary>
+    /// The boxes of a subtree recorded with <see cref="TaffyTree.SnapshotLayouts"/>, to animate a layout change from
+    /// them with <see cref="TaffyTree.InterpolateLayouts"/>
+    /// </summary>
+    public unsafe class LayoutSnapshot : IDisposable
+    {
+        private bool _disposed;
+
+        internal UIntPtr Handle { get; private set; }
+
+        internal LayoutSnapshot(UIntPtr handle)
+        {
+            Handle = handle;
+        }
+
+        /// <summary>
+        /// Releases the native snapshot
+        /// </summary>
+        public void Dispose()
+        {
+            if (!_disposed)
+            {
+                if (Handle != UIntPtr.Zero)
+                {
+                    NativeMethods.taffy_layout_snapshot_free(Handle);
+                    Handle = UIntPtr.Zero;
+                }
+                _disposed = true;
+            }
+        }
+
+        ~LayoutSnapshot()
+        {
+            Dispose();
+        }
+    }
+}
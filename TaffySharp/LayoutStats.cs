@@ -0,0 +1,97 @@
+﻿using System;
+using TaffySharp.Lib;
+
+namespace TaffySharp
+{
+    /// <summary>
+    /// Counters from the last <see cref="TaffyTree.ComputeLayout"/>
+    /// </summary>
+    public readonly struct LayoutStats
+    {
+        /// <summary>
+        /// The number of distinct nodes laid out
+        /// </summary>
+        public ulong NodesVisited { get; }
+
+        /// <summary>
+        /// The number of times the layout of a node was asked for, across all nodes
+        /// </summary>
+        public ulong LayoutCalls { get; }
+
+        public ulong CacheHits { get; }
+
+        public ulong CacheMisses { get; }
+
+        /// <summary>
+        /// Calls into the <see cref="MeasureFunction"/> and <see cref="BatchMeasureFunction"/>. A batch counts once.
+        /// </summary>
+        public ulong MeasureCalls { get; }
+
+        /// <summary>
+        /// Measurements of leaves flagged with <see cref="TaffyTree.SetMeasured"/>, predicted or not
+        /// </summary>
+        public ulong Measurements { get; }
+
+        public TimeSpan Total { get; }
+
+        /// <summary>
+        /// The time spent in flexbox layout, not counting the time spent laying out children. The same goes for the other algorithms.
+        /// </summary>
+        public TimeSpan Flexbox { get; }
+
+        public TimeSpan Grid { get; }
+
+        public TimeSpan Block { get; }
+
+        public TimeSpan Leaf { get; }
+
+        public TimeSpan Custom { get; }
+
+        internal LayoutStats(c_LayoutStats stats)
+        {
+            NodesVisited = stats.nodes_visited;
+            LayoutCalls = stats.layout_calls;
+            CacheHits = stats.cache_hits;
+            CacheMisses = stats.cache_misses;
+            MeasureCalls = stats.measure_calls;
+            Measurements = stats.measurements;
+            Total = FromMilliseconds(stats.total_ms);
+            Flexbox = FromMilliseconds(stats.flexbox_ms);
+            Grid = FromMilliseconds(stats.grid_ms);
+            Block = FromMilliseconds(stats.block_ms);
+            Leaf = FromMilliseconds(stats.leaf_ms);
+            Custom = FromMilliseconds(stats.custom_ms);
+        }
+
+        // TimeSpan.FromMilliseconds rounds to whole milliseconds on .NET Framework
+        internal static TimeSpan FromMilliseconds(double ms) => TimeSpan.FromTicks((long)(ms * TimeSpan.TicksPerMillisecond));
+    }
+
+    /// <summary>
+    /// Counters for one node from the last <see cref="TaffyTree.ComputeLayout"/>
+    /// </summary>
+    public readonly struct NodeStats
+    {
+        public ulong LayoutCalls { get; }
+
+        public ulong CacheHits { get; }
+
+        public ulong CacheMisses { get; }
+
+        public ulong Measurements { get; }
+
+        /// <summary>
+        /// The time spent laying out the node, including its subtree
+        /// </summary>
+        public TimeSpan Total { get; }
+
+        internal NodeStats(c_NodeStats stats)
+        {
+            LayoutCalls = stats.layout_calls;
+            CacheHits = stats.cache_hits;
+            CacheMisses = stats.cache_misses;
+            Measurements = stats.measurements;
+            Total = LayoutStats.FromMilliseconds(stats.total_ms);
+        }
+    }
+}
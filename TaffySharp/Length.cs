@@ -1,4 +1,6 @@
-﻿using TaffySharp.Lib;
+﻿using System;
+using System.Text;
+using TaffySharp.Lib;
 
 namespace TaffySharp
 {
@@ -22,5 +24,25 @@ namespace TaffySharp
         /// </summary>
         /// <returns></returns>
         internal c_Length ToCStruct() => new() { dim = (int)Type, value = Value };
+
+        /// <summary>
+        /// Parses a calc expression into the value of a <see cref="DimensionType.Calc"/> length
+        /// </summary>
+        /// <param name="expression"></param>
+        /// <returns></returns>
+        /// <exception cref="ArgumentException">The expression is invalid, or the native library was built without calc</exception>
+        internal static unsafe float ParseCalc(string expression)
+        {
+            var bytes = Encoding.UTF8.GetBytes(expression);
+            uint id;
+            fixed (byte* expressionPtr = bytes)
+            {
+                id = NativeMethods.taffy_calc_parse(expressionPtr, (nuint)bytes.Length);
+            }
+
+            if (id == 0)
+                throw new ArgumentException("Failed to parse calc expression", nameof(expression));
+            return id;
+        }
     }
 }
\ No newline at end of file
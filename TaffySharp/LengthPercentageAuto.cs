@@ -42,6 +42,59 @@ namespace TaffySharp
         /// <returns></returns>
         public static LengthPercentageAuto FromPercentage(float percentage) => new(DimensionType.Percent, percentage);
 
+        /// <summary>
+        /// A length relative to the viewport width, where 100 is the full width (CSS <c>vw</c>)
+        /// </summary>
+        /// <param name="value"></param>
+        /// <returns></returns>
+        public static LengthPercentageAuto FromViewportWidth(float value) => new(DimensionType.ViewportWidth, value);
+
+        /// <summary>
+        /// A length relative to the viewport height, where 100 is the full height (CSS <c>vh</c>)
+        /// </summary>
+        /// <param name="value"></param>
+        /// <returns></returns>
+        public static LengthPercentageAuto FromViewportHeight(float value) => new(DimensionType.ViewportHeight, value);
+
+        /// <summary>
+        /// A length relative to the smaller side of the viewport (CSS <c>vmin</c>)
+        /// </summary>
+        /// <param name="value"></param>
+        /// <returns></returns>
+        public static LengthPercentageAuto FromViewportMin(float value) => new(DimensionType.ViewportMin, value);
+
+        /// <summary>
+        /// A length relative to the larger side of the viewport (CSS <c>vmax</c>)
+        /// </summary>
+        /// <param name="value"></param>
+        /// <returns></returns>
+        public static LengthPercentageAuto FromViewportMax(float value) => new(DimensionType.ViewportMax, value);
+
+        /// <summary>
+        /// A length relative to the font size of the node (CSS <c>em</c>)
+        /// </summary>
+        /// <param name="value"></param>
+        /// <returns></returns>
+        public static LengthPercentageAuto FromEm(float value) => new(DimensionType.Em, value);
+
+        /// <summary>
+        /// A length relative to the font size of the tree (CSS <c>rem</c>)
+        /// </summary>
+        /// <param name="value"></param>
+        /// <returns></returns>
+        public static LengthPercentageAuto FromRem(float value) => new(DimensionType.Rem, value);
+
+        /// <summary>
+        /// A <c>calc()</c>, <c>min()</c>, <c>max()</c> or <c>clamp()</c> expression of lengths in px and percentages,
+        /// such as <c>calc(100% - 32px)</c>. Relative units such as <c>vw</c> and <c>em</c> are not accepted in them.
+        /// Expressions are kept for the lifetime of the process and at most 2^24 distinct ones can be created, so reuse
+        /// them rather than creating one per value.
+        /// </summary>
+        /// <param name="expression"></param>
+        /// <returns></returns>
+        /// <exception cref="ArgumentException">The expression is invalid</exception>
+        public static LengthPercentageAuto FromCalc(string expression) => new(DimensionType.Calc, ParseCalc(expression));
+
         /// <inheritdoc />
         public override DimensionType Type
         {
@@ -53,9 +106,16 @@ namespace TaffySharp
                     case DimensionType.Auto:
                     case DimensionType.Length:
                     case DimensionType.Percent:
+                    case DimensionType.ViewportWidth:
+                    case DimensionType.ViewportHeight:
+                    case DimensionType.ViewportMin:
+                    case DimensionType.ViewportMax:
+                    case DimensionType.Em:
+                    case DimensionType.Rem:
+                    case DimensionType.Calc:
                         break;
                     default:
-                        throw new ArgumentException($"Invalid dimension type: {value}. Should be one of: Auto, Length, Percent.");
+                        throw new ArgumentException($"Invalid dimension type: {value}. Should be one of: Auto, Length, Percent, ViewportWidth, ViewportHeight, ViewportMin, ViewportMax, Em, Rem, Calc.");
                 }
 
                 _type = value;
@@ -34,12 +34,104 @@ namespace TaffySharp.Lib
         [DllImport(__DllName, EntryPoint = "taffytree_disable_rounding", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
         internal static extern void taffytree_disable_rounding(nuint tree);
 
+        /// <summary>
+        ///  Starts a batch: until it is committed or rolled back, the first failing operation restores the
+        ///  tree to its current state and every later mutation is refused. Returns 1 if a batch is already open.
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffytree_begin_batch", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern int taffytree_begin_batch(nuint tree);
+
+        /// <summary>
+        ///  Closes the open batch, keeping its changes. Returns 1 if there was no open batch or if an
+        ///  operation in it failed, in which case the tree is back at the state it had before the batch.
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffytree_commit_batch", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern int taffytree_commit_batch(nuint tree);
+
+        /// <summary>
+        ///  Closes the open batch and restores the tree to the state it had before the batch.
+        ///  Returns 1 if there was no open batch.
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffytree_rollback_batch", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern int taffytree_rollback_batch(nuint tree);
+
+        /// <summary>
+        ///  Converts `style` once into a handle that can be applied to any number of nodes with
+        ///  `taffytree_set_style_handle`. Release it with `taffy_style_free`. Returns 0 if `style` is null
+        ///  or uses a display mode or calc length this build does not support.
+        ///
+        ///  Relative lengths are resolved against the tree the handle is applied to. Host trees have no
+        ///  viewport or font sizes, so viewport units are 0 there and font units use 16.
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffy_style_create", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern nuint taffy_style_create(c_Style* style);
+
+        /// <summary>
+        ///  Releases a handle created by `taffy_style_create`. Nodes the style was applied to keep it.
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffy_style_free", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern void taffy_style_free(nuint style);
+
+        /// <summary>
+        ///  Parses a stylesheet (see `stylesheet.rs` for the supported CSS) from `len` bytes of UTF-8.
+        ///  Returns 0 if the text is not valid UTF-8, has a syntax error or uses an unsupported property.
+        ///  The handle can be set on any number of trees and must be released with `taffy_stylesheet_free`.
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffy_stylesheet_parse", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern nuint taffy_stylesheet_parse(byte* css, nuint len);
+
+        /// <summary>
+        ///  Releases a stylesheet handle. Trees it is set on keep using it until it is replaced.
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffy_stylesheet_free", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern void taffy_stylesheet_free(nuint sheet);
+
+        /// <summary>
+        ///  Sets the stylesheet applied to every node with a tag, an id or classes, and re-resolves their
+        ///  styles. Passing 0 removes the stylesheet, leaving each node with the style the host set on it.
+        ///
+        ///  The rules override the style the host set, which only provides the properties no matching rule
+        ///  declares. This is the reverse of CSS, where an inline style wins over the rules.
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffytree_set_stylesheet", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern int taffytree_set_stylesheet(nuint tree, nuint sheet);
+
+        /// <summary>
+        ///  Sets the tag that type selectors match against `node`
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffytree_set_tag", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern int taffytree_set_tag(nuint tree, ulong node, byte* tag, nuint len);
+
+        /// <summary>
+        ///  Sets the id that `#id` selectors match against `node`
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffytree_set_element_id", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern int taffytree_set_element_id(nuint tree, ulong node, byte* id, nuint len);
+
+        /// <summary>
+        ///  Replaces the classes of `node` with the whitespace separated class names in `classes`
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffytree_set_classes", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern int taffytree_set_classes(nuint tree, ulong node, byte* classes, nuint len);
+
+        [DllImport(__DllName, EntryPoint = "taffytree_add_class", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern int taffytree_add_class(nuint tree, ulong node, byte* @class, nuint len);
+
+        [DllImport(__DllName, EntryPoint = "taffytree_remove_class", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern int taffytree_remove_class(nuint tree, ulong node, byte* @class, nuint len);
+
         [DllImport(__DllName, EntryPoint = "taffytree_new_leaf", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
         internal static extern ulong taffytree_new_leaf(nuint tree, c_Style* style);
 
+        [DllImport(__DllName, EntryPoint = "taffytree_new_leaf_with_style_handle", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern ulong taffytree_new_leaf_with_style_handle(nuint tree, nuint style);
+
         [DllImport(__DllName, EntryPoint = "taffytree_add_child", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
         internal static extern int taffytree_add_child(nuint tree, ulong parent, ulong child);
 
+        [DllImport(__DllName, EntryPoint = "taffytree_insert_child_at_index", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern int taffytree_insert_child_at_index(nuint tree, ulong parent, nuint index, ulong child);
+
         [DllImport(__DllName, EntryPoint = "taffytree_remove", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
         internal static extern int taffytree_remove(nuint tree, ulong node);
 
@@ -64,12 +156,389 @@ namespace TaffySharp.Lib
         [DllImport(__DllName, EntryPoint = "taffytree_set_style", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
         internal static extern int taffytree_set_style(nuint tree, ulong node, c_Style* style);
 
+        [DllImport(__DllName, EntryPoint = "taffytree_set_style_handle", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern int taffytree_set_style_handle(nuint tree, ulong node, nuint style);
+
+        /// <summary>
+        ///  Replaces the style of a handle from `taffy_style_create` and restyles every node of `tree` whose
+        ///  style was last set from it. Returns 1 if `style` is null or unsupported, leaving the handle as it was.
+        ///
+        ///  Nodes of other trees keep the previous style until the handle is set on them again or they are
+        ///  restyled by updating the handle with those trees. Rolling back a batch restores the styles of the
+        ///  nodes, but not the content of the handle.
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffytree_update_style_handle", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern int taffytree_update_style_handle(nuint tree, nuint handle, c_Style* style);
+
+        /// <summary>
+        ///  Applies a buffer of encoded commands (see `commands.rs` for the format) in a single call.
+        ///  The ids of the nodes created by the buffer are written to `out_results` in creation order.
+        ///
+        ///  The buffer is applied atomically. It is decoded whole first, so a malformed buffer changes nothing,
+        ///  and if a command then fails on the nodes it refers to, the tree is restored to its previous state.
+        ///  When called inside an open batch, a failure fails that batch instead.
+        ///
+        ///  Returns the number of nodes created, -1 if the header or version is not recognized, or
+        ///  `-2 - n` if command `n` (counting from 0) is malformed, fails, or has no result slot left.
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffytree_apply_commands", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern int taffytree_apply_commands(nuint tree, byte* buf, nuint len, ulong* out_results, nuint results_capacity);
+
         [DllImport(__DllName, EntryPoint = "taffytree_compute_layout", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
         internal static extern int taffytree_compute_layout(nuint tree, ulong node, c_AvailableSpace available_space);
 
         [DllImport(__DllName, EntryPoint = "taffytree_layout", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
         internal static extern int taffytree_layout(nuint tree, ulong node, c_Layout* layout);
 
+        /// <summary>
+        ///  Scrolls the content of `node` to (`x`, `y`), clamped between zero and the extent reported by
+        ///  `taffytree_scroll_extent` for the last computed layout.
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffytree_set_scroll_offset", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern int taffytree_set_scroll_offset(nuint tree, ulong node, float x, float y);
+
+        /// <summary>
+        ///  Gets the scroll offset of `node`, clamped to the extent of the last computed layout
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffytree_scroll_offset", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern int taffytree_scroll_offset(nuint tree, ulong node, float* out_x, float* out_y);
+
+        /// <summary>
+        ///  Gets the largest scroll offset of `node`: how far its content size extends past its padding box
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffytree_scroll_extent", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern int taffytree_scroll_extent(nuint tree, ulong node, float* out_x, float* out_y);
+
+        /// <summary>
+        ///  Gets the location of `node` relative to the root of its tree, taking the scroll offsets of its ancestors into account
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffytree_absolute_location", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern int taffytree_absolute_location(nuint tree, ulong node, float* out_x, float* out_y);
+
+        /// <summary>
+        ///  Finds the topmost node in the subtree of `root` at (`x`, `y`), in the same coordinates as
+        ///  `taffytree_absolute_location`. Returns 0 if no node is hit.
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffytree_hit_test", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern ulong taffytree_hit_test(nuint tree, ulong root, float x, float y);
+
+        /// <summary>
+        ///  Computes the clip rect of every node in the subtree of `root`, including `root`, from the padding boxes
+        ///  of its ancestors that don't have visible overflow. Up to `capacity` rects are written to `out_rects`, parents
+        ///  before children. Returns the number of nodes in the subtree, or -1 if `root` is not a valid node.
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffytree_clip_rects", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern int taffytree_clip_rects(nuint tree, ulong root, c_ClipRect* out_rects, nuint capacity);
+
+        /// <summary>
+        ///  Renders the computed layout of the subtree of `root` as an SVG image for debugging: the margin, border,
+        ///  padding and content box of every node, coloured by display mode and labelled with the node's tag, id and
+        ///  classes, or its node id. Writes up to `cap` bytes of UTF-8 to `buf` and returns the full length of the
+        ///  SVG, or -1 if `root` is not a valid node.
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffytree_render_svg", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern int taffytree_render_svg(nuint tree, ulong root, byte* buf, nuint cap);
+
+        /// <summary>
+        ///  Rasterizes the computed layout of the subtree of `root` into a character grid, for text-based snapshot
+        ///  tests. Each node's border box is drawn with box-drawing characters at `scale` cells per layout unit and
+        ///  labelled like `taffytree_render_svg`, trailing spaces are trimmed and every line ends with `\n`.
+        ///  Writes up to `cap` bytes of UTF-8 to `buf` and returns the full length of the text, or -1 if `root` is
+        ///  not a valid node, `scale` is not positive, or the grid would exceed 4096 cells in either direction.
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffytree_render_ascii", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern int taffytree_render_ascii(nuint tree, ulong root, float scale, byte* buf, nuint cap);
+
+        /// <summary>
+        ///  Creates a handle that runs flexbox, grid and block layout over a tree owned by the host, keeping
+        ///  only the layout caches on the native side. Returns 0 if a required callback is missing.
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffy_hosttree_new", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern nuint taffy_hosttree_new(c_HostTreeCallbacks callbacks);
+
+        [DllImport(__DllName, EntryPoint = "taffy_hosttree_free", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern void taffy_hosttree_free(nuint host);
+
+        [DllImport(__DllName, EntryPoint = "taffy_hosttree_enable_rounding", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern void taffy_hosttree_enable_rounding(nuint host);
+
+        [DllImport(__DllName, EntryPoint = "taffy_hosttree_disable_rounding", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern void taffy_hosttree_disable_rounding(nuint host);
+
+        /// <summary>
+        ///  Lays out the host tree below `root` and passes every node's layout to the `set_layout` callback
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffy_hosttree_compute_layout", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern int taffy_hosttree_compute_layout(nuint host, ulong root, c_AvailableSpace available_space);
+
+        /// <summary>
+        ///  Discards the cached layout of `node`, and of its ancestors when the `parent` callback is set.
+        ///  Call this whenever the style or the children of a host node change.
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffy_hosttree_mark_dirty", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern int taffy_hosttree_mark_dirty(nuint host, ulong node);
+
+        /// <summary>
+        ///  Releases the cached layout kept for a host node that no longer exists
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffy_hosttree_remove_node", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern void taffy_hosttree_remove_node(nuint host, ulong node);
+
+        /// <summary>
+        ///  Registers the function that lays out nodes flagged with `taffytree_set_custom_layout`. It is called
+        ///  with `user_data`, a context handle for the `taffy_layoutcontext_*` helpers, the node and the layout
+        ///  inputs, and writes the node's size to `output`. When `inputs.run_mode` is 0 it must also place every
+        ///  child with `taffy_layoutcontext_set_child_layout`. Passing no callback lays flagged nodes out as leaves.
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffytree_set_custom_layout_callback", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern void taffytree_set_custom_layout_callback(nuint tree, delegate* unmanaged[Cdecl]<nuint, nuint, ulong, c_LayoutInput*, c_LayoutOutput*, void> callback, nuint user_data);
+
+        /// <summary>
+        ///  Sets whether `node` is laid out by the custom layout callback instead of by its display mode
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffytree_set_custom_layout", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern int taffytree_set_custom_layout(nuint tree, ulong node, int enabled);
+
+        [DllImport(__DllName, EntryPoint = "taffy_layoutcontext_child_count", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern nuint taffy_layoutcontext_child_count(nuint context, ulong node);
+
+        /// <summary>
+        ///  Returns the child of `node` at `index`, or 0 if there is none
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffy_layoutcontext_child_at", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern ulong taffy_layoutcontext_child_at(nuint context, ulong node, nuint index);
+
+        /// <summary>
+        ///  Runs the layout of `child` with `inputs` from inside the custom layout callback, writing its size to `output`.
+        ///  Fails if `child` is not a child of the node being laid out.
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffy_layoutcontext_compute_child", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern int taffy_layoutcontext_compute_child(nuint context, ulong child, c_LayoutInput* inputs, c_LayoutOutput* output);
+
+        /// <summary>
+        ///  Places `child` from inside the custom layout callback. The location is relative to the border box of its parent.
+        ///  Fails if `child` is not a child of the node being laid out.
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffy_layoutcontext_set_child_layout", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern int taffy_layoutcontext_set_child_layout(nuint context, ulong child, c_Layout* layout);
+
+        /// <summary>
+        ///  Registers the function that sizes the content of leaves flagged with `taffytree_set_measured`. It is called
+        ///  with `user_data`, the node, the known width and height (NaN if unknown) and the space available to the
+        ///  content box, and writes the content size and first baseline to `output`. Passing no callback sizes flagged
+        ///  leaves from their styles only.
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffytree_set_measure_callback", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern void taffytree_set_measure_callback(nuint tree, delegate* unmanaged[Cdecl]<nuint, ulong, float, float, c_AvailableSpace, c_MeasureOutput*, void> callback, nuint user_data);
+
+        /// <summary>
+        ///  Registers a function that answers many measurements at once, to save a call into the host per leaf.
+        ///  Before each layout pass taffy_ffi predicts the measurements it will need for the dirty leaves flagged with
+        ///  `taffytree_set_measured` and calls it once with all of them, writing one output per query. Measurements
+        ///  it did not predict are asked for one at a time, through the single measure callback if there is one and
+        ///  otherwise through this function with a `count` of 1.
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffytree_set_batch_measure_callback", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern void taffytree_set_batch_measure_callback(nuint tree, delegate* unmanaged[Cdecl]<nuint, c_MeasureQuery*, c_MeasureOutput*, nuint, void> callback, nuint user_data);
+
+        /// <summary>
+        ///  Sets whether the content of `node` is sized by the measure callbacks. Only used while `node` has no children.
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffytree_set_measured", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern int taffytree_set_measured(nuint tree, ulong node, int enabled);
+
+        /// <summary>
+        ///  Loads a TrueType or OpenType font from `len` bytes, which are copied. Returns 0 if the data is not a font.
+        ///  The handle can be used for any number of text leaves and must be released with `taffy_font_free`.
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffy_font_load", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern nuint taffy_font_load(byte* data, nuint len);
+
+        /// <summary>
+        ///  Releases a font handle. Text leaves using it keep it alive until their text is replaced.
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffy_font_free", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern void taffy_font_free(nuint font);
+
+        /// <summary>
+        ///  Makes `node` a text leaf showing `len` bytes of UTF-8 in `font` at `font_size` pixels. Its content is
+        ///  wrapped at word boundaries to fit the available width during layout. `line_height` is in pixels, 0 uses
+        ///  the metrics of the font. Passing a `font` of 0 removes the text. Only used while `node` has no children.
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffytree_set_text", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern int taffytree_set_text(nuint tree, ulong node, nuint font, byte* text, nuint len, float font_size, float line_height);
+
+        /// <summary>
+        ///  Writes the counters from the last `taffytree_compute_layout` to `out`
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffytree_get_stats", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern int taffytree_get_stats(nuint tree, c_LayoutStats* @out);
+
+        /// <summary>
+        ///  Writes the counters for `node` from the last `taffytree_compute_layout` to `out`, all zero if it was not laid out
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffytree_get_node_stats", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern int taffytree_get_node_stats(nuint tree, ulong node, c_NodeStats* @out);
+
+        /// <summary>
+        ///  Zeroes the counters of the tree and of every node until the next `taffytree_compute_layout`
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffytree_reset_stats", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern int taffytree_reset_stats(nuint tree);
+
+        /// <summary>
+        ///  Registers a function that receives a log of each node laid out by `taffytree_compute_layout`. It is called
+        ///  once the pass is over, for every line in order, with `user_data`, a severity (0 for the inputs a node is
+        ///  about to be laid out with, 1 for how it was laid out, 2 for likely mistakes), the node and `len` bytes of UTF-8.
+        ///  Returns 1 if taffy_ffi was built without the `debug` feature, in which case nothing is logged.
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffytree_set_logger", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern int taffytree_set_logger(nuint tree, delegate* unmanaged[Cdecl]<nuint, int, ulong, byte*, nuint, void> callback, nuint user_data);
+
+        /// <summary>
+        ///  Writes an estimate of the memory held by `tree` to `out`. taffy's storage is estimated from the number of
+        ///  slots it has grown to, which includes those of removed nodes, taffy_ffi's own storage from its capacities.
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffytree_memory_usage", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern int taffytree_memory_usage(nuint tree, c_MemoryUsage* @out);
+
+        /// <summary>
+        ///  Releases the spare capacity of the vectors and maps `tree` stores its nodes in. Node ids stay valid, so the
+        ///  slots taffy keeps for removed nodes are not released: they are only reused by new nodes.
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffytree_shrink_to_fit", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern void taffytree_shrink_to_fit(nuint tree);
+
+        /// <summary>
+        ///  Returns `TAFFY_FFI_ABI_VERSION`, so that bindings can refuse to use a library they were not generated for
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffy_ffi_abi_version", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern uint taffy_ffi_abi_version();
+
+        /// <summary>
+        ///  Writes the size and alignment of every `#[repr(C)]` type to `out`, so that bindings can check their
+        ///  mirrored definitions against the library before using it. `len` is the size of `c_StructSizes` the bindings
+        ///  were built with. Only the first `len` bytes are written, so bindings with fewer types are not overrun, and
+        ///  the types they know past what this library writes are left as they were.
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffy_ffi_struct_sizes", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern int taffy_ffi_struct_sizes(c_StructSizes* @out, nuint len);
+
+        /// <summary>
+        ///  Returns the table of every export. It lives as long as the library is loaded.
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffy_ffi_get_api", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern c_Api* taffy_ffi_get_api();
+
+        /// <summary>
+        ///  Returns the `TAFFY_FFI_FEATURE_*` flags of the cargo features this library was built with. Styles that use
+        ///  a display mode of a missing algorithm are rejected, and so are stylesheet and command buffer properties
+        ///  that only apply to it.
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffy_ffi_supported_features", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern uint taffy_ffi_supported_features();
+
+        /// <summary>
+        ///  Sets the size that viewport units (see `units.rs`) are relative to, initially 0 x 0. Nodes whose style
+        ///  uses them are resolved again and marked dirty if their style changed.
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffytree_set_viewport", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern int taffytree_set_viewport(nuint tree, float width, float height);
+
+        /// <summary>
+        ///  Sets the font size that `rem` units are relative to, and `em` units of nodes that do not inherit
+        ///  one from an ancestor, initially 16. Fails if it is not a positive finite number.
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffytree_set_root_font_size", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern int taffytree_set_root_font_size(nuint tree, float font_size);
+
+        /// <summary>
+        ///  Sets the font size that `em` units of `node` and of its descendants without their own are relative
+        ///  to. NaN removes it so that the node inherits one again. Nodes of the subtree using font units are
+        ///  resolved again and marked dirty if their style changed.
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffytree_set_font_size", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern int taffytree_set_font_size(nuint tree, ulong node, float font_size);
+
+        /// <summary>
+        ///  Gets the font size `em` units of `node` are relative to: its own, inherited or the tree's
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffytree_font_size", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern int taffytree_font_size(nuint tree, ulong node, float* @out);
+
+        /// <summary>
+        ///  Parses a `calc()`, `min()`, `max()` or `clamp()` expression (see `calc.rs`) from `len` bytes of UTF-8.
+        ///  Returns its id, to be used as the value of a length with dim 14, or 0 if the expression is invalid
+        ///  or this build does not support calc (see `taffy_ffi_supported_features`). Lengths in an expression are
+        ///  in `px` or `%`, so relative units such as `vw` and `em` cannot be combined with calc. Stylesheet
+        ///  declarations accept the same expressions. Parsing the same expression again returns the same id. Parsed
+        ///  expressions are leaked for the lifetime of the process, and once 2^24 distinct ones have been parsed,
+        ///  new ones return 0, so expressions should not be generated from values that keep changing.
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffy_calc_parse", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern uint taffy_calc_parse(byte* text, nuint len);
+
+        /// <summary>
+        ///  Writes the style at `t` of a transition from `a` to `b` (see `lerp.rs`) to `out`: `a` at 0 and `b` at 1,
+        ///  extrapolating outside of that. The grid track arrays of `out` are allocated by this library, release
+        ///  them with `taffy_style_lerp_free`. Returns 1 if a style is null or uses a display mode or calc length
+        ///  this build does not support, or if `t` is NaN.
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffy_style_lerp", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern int taffy_style_lerp(c_Style* a, c_Style* b, float t, c_Style* @out);
+
+        /// <summary>
+        ///  Releases the grid track arrays of a style written by `taffy_style_lerp`, setting them to null.
+        ///  The style can still be used afterwards, without grid tracks.
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffy_style_lerp_free", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern void taffy_style_lerp_free(c_Style* style);
+
+        /// <summary>
+        ///  Records the boxes of `root` and its descendants from the last `taffytree_compute_layout`, to animate
+        ///  from them with `taffytree_interpolate_layouts` once the layout changed. Release the handle with
+        ///  `taffy_layout_snapshot_free`. Returns 0 if `root` is not a valid node.
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffytree_snapshot_layouts", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern nuint taffytree_snapshot_layouts(nuint tree, ulong root);
+
+        /// <summary>
+        ///  Releases a snapshot created by `taffytree_snapshot_layouts`
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffy_layout_snapshot_free", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern void taffy_layout_snapshot_free(nuint snapshot);
+
+        /// <summary>
+        ///  Interpolates the boxes of `snapshot` towards the current boxes of `root` and its descendants at `t`,
+        ///  from 0 (the snapshot) to 1 (the current layout). The nodes of the subtree come first, parents before
+        ///  children, followed by the nodes of the snapshot that are no longer in it. Up to `capacity` rects are
+        ///  written to `out_rects`. Returns the number of rects, or -1 if `root` or `snapshot` is not valid.
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffytree_interpolate_layouts", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern int taffytree_interpolate_layouts(nuint tree, ulong root, nuint snapshot, float t, c_AnimatedRect* out_rects, nuint capacity);
+
+        /// <summary>
+        ///  Computes the layout of `root` sized to its content, as a popup or tooltip would be: its fit-content
+        ///  size within the max bounds, clamped between the min and max bounds. Max bounds can be infinite, and
+        ///  percentages of the root resolve against the finite ones. The chosen size, before rounding, is
+        ///  written to the optional `out_width` and `out_height`. Returns 1 if `root` is not a valid node or
+        ///  if the bounds are negative, NaN or a min bound is greater than its max bound.
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffytree_compute_layout_fit", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern int taffytree_compute_layout_fit(nuint tree, ulong root, float min_width, float min_height, float max_width, float max_height, float* out_width, float* out_height);
+
+        /// <summary>
+        ///  Computes the layouts of `count` roots, each in its own available space, as `taffytree_compute_layout`
+        ///  would, for hosts with one root per window. Roots whose layout is up to date are skipped: those that
+        ///  are not dirty and were last laid out as a root in the same available space, with the same rounding,
+        ///  viewport and root font size as the tree has now. For each root, writes to
+        ///  the optional `out_results` 1 if it was laid out, 0 if it was skipped or -1 if it is not a valid node.
+        ///  `taffytree_get_stats` then reports the last root laid out. Returns the number of roots laid out, or -1
+        ///  if `roots` or `available_spaces` is null.
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "taffytree_compute_layouts", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern int taffytree_compute_layouts(nuint tree, ulong* roots, c_AvailableSpace* available_spaces, nuint count, int* out_results);
+
 
     }
 
@@ -198,6 +667,232 @@ namespace TaffySharp.Lib
         public fixed float margin[4];
     }
 
+    [StructLayout(LayoutKind.Sequential)]
+    internal unsafe partial struct c_LayoutInput
+    {
+        public int run_mode;
+        public int sizing_mode;
+        public int axis;
+        public fixed float known_dimensions[2];
+        public fixed float parent_size[2];
+        public c_AvailableSpace available_space;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal unsafe partial struct c_LayoutOutput
+    {
+        public fixed float size[2];
+        public fixed float content_size[2];
+        public fixed float first_baselines[2];
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal unsafe partial struct c_ClipRect
+    {
+        public ulong node;
+        public fixed float min[2];
+        public fixed float max[2];
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal unsafe partial struct c_HostTreeCallbacks
+    {
+        public nuint user_data;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, nuint> child_count;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, nuint, ulong> child_at;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, ulong> parent;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, nuint> style;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, c_Layout*, void> set_layout;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, float, float, c_AvailableSpace, c_MeasureOutput*, void> measure;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal unsafe partial struct c_MeasureOutput
+    {
+        public float width;
+        public float height;
+        public float first_baseline;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal unsafe partial struct c_MeasureQuery
+    {
+        public ulong node;
+        public float known_width;
+        public float known_height;
+        public c_AvailableSpace available_space;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal unsafe partial struct c_LayoutStats
+    {
+        public ulong nodes_visited;
+        public ulong layout_calls;
+        public ulong cache_hits;
+        public ulong cache_misses;
+        public ulong measure_calls;
+        public ulong measurements;
+        public double total_ms;
+        public double flexbox_ms;
+        public double grid_ms;
+        public double block_ms;
+        public double leaf_ms;
+        public double custom_ms;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal unsafe partial struct c_NodeStats
+    {
+        public ulong layout_calls;
+        public ulong cache_hits;
+        public ulong cache_misses;
+        public ulong measurements;
+        public double total_ms;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal unsafe partial struct c_MemoryUsage
+    {
+        public ulong nodes;
+        public ulong styles;
+        public ulong caches;
+        public ulong context;
+        public ulong total;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal unsafe partial struct c_TypeLayout
+    {
+        public uint size;
+        public uint align;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal unsafe partial struct c_StructSizes
+    {
+        public c_TypeLayout length;
+        public c_TypeLayout size;
+        public c_TypeLayout rect;
+        public c_TypeLayout grid_index;
+        public c_TypeLayout grid_placement;
+        public c_TypeLayout grid_track_size;
+        public c_TypeLayout grid_track_sizing;
+        public c_TypeLayout style;
+        public c_TypeLayout available_space;
+        public c_TypeLayout layout;
+        public c_TypeLayout layout_input;
+        public c_TypeLayout layout_output;
+        public c_TypeLayout clip_rect;
+        public c_TypeLayout host_tree_callbacks;
+        public c_TypeLayout measure_output;
+        public c_TypeLayout measure_query;
+        public c_TypeLayout layout_stats;
+        public c_TypeLayout node_stats;
+        public c_TypeLayout memory_usage;
+        public c_TypeLayout api;
+        public c_TypeLayout animated_rect;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal unsafe partial struct c_Api
+    {
+        public uint abi_version;
+        public delegate* unmanaged[Cdecl]<nuint> taffytree_new;
+        public delegate* unmanaged[Cdecl]<nuint, nuint> taffytree_with_capacity;
+        public delegate* unmanaged[Cdecl]<nuint, c_Style*, ulong*, nuint, ulong> taffytree_new_with_children;
+        public delegate* unmanaged[Cdecl]<nuint, void> taffytree_free;
+        public delegate* unmanaged[Cdecl]<nuint, void> taffytree_enable_rounding;
+        public delegate* unmanaged[Cdecl]<nuint, void> taffytree_disable_rounding;
+        public delegate* unmanaged[Cdecl]<nuint, int> taffytree_begin_batch;
+        public delegate* unmanaged[Cdecl]<nuint, int> taffytree_commit_batch;
+        public delegate* unmanaged[Cdecl]<nuint, int> taffytree_rollback_batch;
+        public delegate* unmanaged[Cdecl]<c_Style*, nuint> taffy_style_create;
+        public delegate* unmanaged[Cdecl]<nuint, void> taffy_style_free;
+        public delegate* unmanaged[Cdecl]<byte*, nuint, nuint> taffy_stylesheet_parse;
+        public delegate* unmanaged[Cdecl]<nuint, void> taffy_stylesheet_free;
+        public delegate* unmanaged[Cdecl]<nuint, nuint, int> taffytree_set_stylesheet;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, byte*, nuint, int> taffytree_set_tag;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, byte*, nuint, int> taffytree_set_element_id;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, byte*, nuint, int> taffytree_set_classes;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, byte*, nuint, int> taffytree_add_class;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, byte*, nuint, int> taffytree_remove_class;
+        public delegate* unmanaged[Cdecl]<nuint, c_Style*, ulong> taffytree_new_leaf;
+        public delegate* unmanaged[Cdecl]<nuint, nuint, ulong> taffytree_new_leaf_with_style_handle;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, ulong, int> taffytree_add_child;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, nuint, ulong, int> taffytree_insert_child_at_index;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, int> taffytree_remove;
+        public delegate* unmanaged[Cdecl]<nuint, void> taffytree_clear;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, nuint, ulong, int> taffytree_replace_child_at_index;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, ulong, int> taffytree_remove_child;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, nuint, int> taffytree_remove_child_at_index;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, int> taffytree_dirty;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, int> taffytree_mark_dirty;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, c_Style*, int> taffytree_set_style;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, nuint, int> taffytree_set_style_handle;
+        public delegate* unmanaged[Cdecl]<nuint, nuint, c_Style*, int> taffytree_update_style_handle;
+        public delegate* unmanaged[Cdecl]<nuint, byte*, nuint, ulong*, nuint, int> taffytree_apply_commands;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, c_AvailableSpace, int> taffytree_compute_layout;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, c_Layout*, int> taffytree_layout;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, float, float, int> taffytree_set_scroll_offset;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, float*, float*, int> taffytree_scroll_offset;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, float*, float*, int> taffytree_scroll_extent;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, float*, float*, int> taffytree_absolute_location;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, float, float, ulong> taffytree_hit_test;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, c_ClipRect*, nuint, int> taffytree_clip_rects;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, byte*, nuint, int> taffytree_render_svg;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, float, byte*, nuint, int> taffytree_render_ascii;
+        public delegate* unmanaged[Cdecl]<c_HostTreeCallbacks, nuint> taffy_hosttree_new;
+        public delegate* unmanaged[Cdecl]<nuint, void> taffy_hosttree_free;
+        public delegate* unmanaged[Cdecl]<nuint, void> taffy_hosttree_enable_rounding;
+        public delegate* unmanaged[Cdecl]<nuint, void> taffy_hosttree_disable_rounding;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, c_AvailableSpace, int> taffy_hosttree_compute_layout;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, int> taffy_hosttree_mark_dirty;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, void> taffy_hosttree_remove_node;
+        public delegate* unmanaged[Cdecl]<nuint, delegate* unmanaged[Cdecl]<nuint, nuint, ulong, c_LayoutInput*, c_LayoutOutput*, void>, nuint, void> taffytree_set_custom_layout_callback;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, int, int> taffytree_set_custom_layout;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, nuint> taffy_layoutcontext_child_count;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, nuint, ulong> taffy_layoutcontext_child_at;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, c_LayoutInput*, c_LayoutOutput*, int> taffy_layoutcontext_compute_child;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, c_Layout*, int> taffy_layoutcontext_set_child_layout;
+        public delegate* unmanaged[Cdecl]<nuint, delegate* unmanaged[Cdecl]<nuint, ulong, float, float, c_AvailableSpace, c_MeasureOutput*, void>, nuint, void> taffytree_set_measure_callback;
+        public delegate* unmanaged[Cdecl]<nuint, delegate* unmanaged[Cdecl]<nuint, c_MeasureQuery*, c_MeasureOutput*, nuint, void>, nuint, void> taffytree_set_batch_measure_callback;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, int, int> taffytree_set_measured;
+        public delegate* unmanaged[Cdecl]<byte*, nuint, nuint> taffy_font_load;
+        public delegate* unmanaged[Cdecl]<nuint, void> taffy_font_free;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, nuint, byte*, nuint, float, float, int> taffytree_set_text;
+        public delegate* unmanaged[Cdecl]<nuint, c_LayoutStats*, int> taffytree_get_stats;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, c_NodeStats*, int> taffytree_get_node_stats;
+        public delegate* unmanaged[Cdecl]<nuint, int> taffytree_reset_stats;
+        public delegate* unmanaged[Cdecl]<nuint, delegate* unmanaged[Cdecl]<nuint, int, ulong, byte*, nuint, void>, nuint, int> taffytree_set_logger;
+        public delegate* unmanaged[Cdecl]<nuint, c_MemoryUsage*, int> taffytree_memory_usage;
+        public delegate* unmanaged[Cdecl]<nuint, void> taffytree_shrink_to_fit;
+        public delegate* unmanaged[Cdecl]<uint> taffy_ffi_abi_version;
+        public delegate* unmanaged[Cdecl]<c_StructSizes*, nuint, int> taffy_ffi_struct_sizes;
+        public delegate* unmanaged[Cdecl]<uint> taffy_ffi_supported_features;
+        public delegate* unmanaged[Cdecl]<nuint, float, float, int> taffytree_set_viewport;
+        public delegate* unmanaged[Cdecl]<nuint, float, int> taffytree_set_root_font_size;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, float, int> taffytree_set_font_size;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, float*, int> taffytree_font_size;
+        public delegate* unmanaged[Cdecl]<byte*, nuint, uint> taffy_calc_parse;
+        public delegate* unmanaged[Cdecl]<c_Style*, c_Style*, float, c_Style*, int> taffy_style_lerp;
+        public delegate* unmanaged[Cdecl]<c_Style*, void> taffy_style_lerp_free;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, nuint> taffytree_snapshot_layouts;
+        public delegate* unmanaged[Cdecl]<nuint, void> taffy_layout_snapshot_free;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, nuint, float, c_AnimatedRect*, nuint, int> taffytree_interpolate_layouts;
+        public delegate* unmanaged[Cdecl]<nuint, ulong, float, float, float, float, float*, float*, int> taffytree_compute_layout_fit;
+        public delegate* unmanaged[Cdecl]<nuint, ulong*, c_AvailableSpace*, nuint, int*, int> taffytree_compute_layouts;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal unsafe partial struct c_AnimatedRect
+    {
+        public ulong node;
+        public fixed float location[2];
+        public fixed float size[2];
+        public int change;
+        public float presence;
+    }
+
 
 
 }
@@ -0,0 +1,10 @@
+﻿namespace TaffySharp
+{
+    /// <summary>
+    /// Receives a line of the layout log. See <see cref="TaffyTree.SetLogger"/>.
+    /// </summary>
+    /// <param name="severity"></param>
+    /// <param name="node">The node the line is about</param>
+    /// <param name="message"></param>
+    public delegate void LogFunction(LogSeverity severity, Node? node, string message);
+}
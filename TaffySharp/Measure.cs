@@ -0,0 +1,61 @@
+﻿using TaffySharp.Lib;
+
+namespace TaffySharp
+{
+    /// <summary>
+    /// Sizes the content of a leaf flagged with <see cref="TaffyTree.SetMeasured"/>, such as a text label or an image
+    /// </summary>
+    /// <param name="node">The leaf being measured</param>
+    /// <param name="knownDimensions">Sizes the content box must have, if already decided</param>
+    /// <param name="availableSpace">The space available to the content box</param>
+    public delegate MeasureOutput MeasureFunction(Node node, Size<float?> knownDimensions, AvailableSpace availableSpace);
+
+    /// <summary>
+    /// Answers many measurements at once, writing <c>outputs[i]</c> for <c>queries[i]</c>. See <see cref="TaffyTree.SetBatchMeasureFunction"/>.
+    /// </summary>
+    public delegate void BatchMeasureFunction(MeasureQuery[] queries, MeasureOutput[] outputs);
+
+    /// <summary>
+    /// A measurement of a leaf's content, with the same arguments as a <see cref="MeasureFunction"/>
+    /// </summary>
+    public readonly struct MeasureQuery
+    {
+        public Node Node { get; }
+
+        public Size<float?> KnownDimensions { get; }
+
+        public AvailableSpace AvailableSpace { get; }
+
+        internal MeasureQuery(Node node, Size<float?> knownDimensions, AvailableSpace availableSpace)
+        {
+            Node = node;
+            KnownDimensions = knownDimensions;
+            AvailableSpace = availableSpace;
+        }
+    }
+
+    /// <summary>
+    /// The size of a leaf's content
+    /// </summary>
+    public struct MeasureOutput
+    {
+        public float Width { get; set; }
+
+        public float Height { get; set; }
+
+        /// <summary>
+        /// The distance from the top of the content to its first baseline, if it has one.
+        /// Used to line up the leaf with its siblings when its parent uses <see cref="AlignItems.Baseline"/>.
+        /// </summary>
+        public float? FirstBaseline { get; set; }
+
+        public MeasureOutput(float width, float height, float? firstBaseline = null)
+        {
+            Width = width;
+            Height = height;
+            FirstBaseline = firstBaseline;
+        }
+
+        internal c_MeasureOutput ToCStruct() => new() { width = Width, height = Height, first_baseline = FirstBaseline ?? float.NaN };
+    }
+}
@@ -0,0 +1,41 @@
+﻿using TaffySharp.Lib;
+
+namespace TaffySharp
+{
+    /// <summary>
+    /// An estimate of the memory held by a <see cref="TaffyTree"/>, in bytes
+    /// </summary>
+    public readonly struct MemoryUsage
+    {
+        /// <summary>
+        /// The nodes, with their layouts and their parent and child links, and the vacant slots left by removed nodes
+        /// </summary>
+        public ulong Nodes { get; }
+
+        /// <summary>
+        /// Styles, including their grid track lists
+        /// </summary>
+        public ulong Styles { get; }
+
+        /// <summary>
+        /// The layout cache of each node
+        /// </summary>
+        public ulong Caches { get; }
+
+        /// <summary>
+        /// Element data, text, counters and the undo log of an open batch
+        /// </summary>
+        public ulong Context { get; }
+
+        public ulong Total { get; }
+
+        internal MemoryUsage(c_MemoryUsage usage)
+        {
+            Nodes = usage.nodes;
+            Styles = usage.styles;
+            Caches = usage.caches;
+            Context = usage.context;
+            Total = usage.total;
+        }
+    }
+}
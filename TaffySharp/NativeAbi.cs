@@ -0,0 +1,74 @@
+﻿using System;
+using TaffySharp.Lib;
+
+namespace TaffySharp
+{
+    /// <summary>
+    /// Checks that the loaded taffy_ffi library matches the definitions in <see cref="NativeMethods"/>
+    /// </summary>
+    internal static unsafe class NativeAbi
+    {
+        /// <summary>
+        /// The ABI version <see cref="NativeMethods"/> was generated for. Keep in step with TAFFY_FFI_ABI_VERSION in taffy_ffi/src/lib.rs.
+        /// </summary>
+        public const uint Version = 2;
+
+        private static volatile bool _verified;
+
+        /// <summary>
+        /// Throws if the native library has a different ABI version or a <c>#[repr(C)]</c> type of a different size
+        /// </summary>
+        /// <exception cref="InvalidOperationException"></exception>
+        public static void EnsureCompatible()
+        {
+            if (_verified)
+                return;
+
+            var version = NativeMethods.taffy_ffi_abi_version();
+            var sizes = new c_StructSizes();
+            // other versions may take other arguments
+            if (version == Version)
+                NativeMethods.taffy_ffi_struct_sizes(&sizes, (nuint)sizeof(c_StructSizes));
+            Verify(version, sizes);
+            _verified = true;
+        }
+
+        /// <summary>
+        /// Throws if <paramref name="version"/> or any of <paramref name="sizes"/>, as reported by the native library, differs from <see cref="NativeMethods"/>
+        /// </summary>
+        /// <exception cref="InvalidOperationException"></exception>
+        internal static void Verify(uint version, c_StructSizes sizes)
+        {
+            if (version != Version)
+                throw new InvalidOperationException($"taffy_ffi has ABI version {version}, but TaffySharp was built for version {Version}");
+
+            Check(sizes.length, sizeof(c_Length), nameof(c_Length));
+            Check(sizes.size, sizeof(c_Size), nameof(c_Size));
+            Check(sizes.rect, sizeof(c_Rect), nameof(c_Rect));
+            Check(sizes.grid_index, sizeof(c_GridIndex), nameof(c_GridIndex));
+            Check(sizes.grid_placement, sizeof(c_GridPlacement), nameof(c_GridPlacement));
+            Check(sizes.grid_track_size, sizeof(c_GridTrackSize), nameof(c_GridTrackSize));
+            Check(sizes.grid_track_sizing, sizeof(c_GridTrackSizing), nameof(c_GridTrackSizing));
+            Check(sizes.style, sizeof(c_Style), nameof(c_Style));
+            Check(sizes.available_space, sizeof(c_AvailableSpace), nameof(c_AvailableSpace));
+            Check(sizes.layout, sizeof(c_Layout), nameof(c_Layout));
+            Check(sizes.layout_input, sizeof(c_LayoutInput), nameof(c_LayoutInput));
+            Check(sizes.layout_output, sizeof(c_LayoutOutput), nameof(c_LayoutOutput));
+            Check(sizes.clip_rect, sizeof(c_ClipRect), nameof(c_ClipRect));
+            Check(sizes.host_tree_callbacks, sizeof(c_HostTreeCallbacks), nameof(c_HostTreeCallbacks));
+            Check(sizes.measure_output, sizeof(c_MeasureOutput), nameof(c_MeasureOutput));
+            Check(sizes.measure_query, sizeof(c_MeasureQuery), nameof(c_MeasureQuery));
+            Check(sizes.layout_stats, sizeof(c_LayoutStats), nameof(c_LayoutStats));
+            Check(sizes.node_stats, sizeof(c_NodeStats), nameof(c_NodeStats));
+            Check(sizes.memory_usage, sizeof(c_MemoryUsage), nameof(c_MemoryUsage));
+            Check(sizes.api, sizeof(c_Api), nameof(c_Api));
+            Check(sizes.animated_rect, sizeof(c_AnimatedRect), nameof(c_AnimatedRect));
+        }
+
+        private static void Check(c_TypeLayout native, int managedSize, string name)
+        {
+            if (native.size != managedSize)
+                throw new InvalidOperationException($"{name} is {native.size} bytes in taffy_ffi but {managedSize} bytes in TaffySharp");
+        }
+    }
+}
@@ -59,6 +59,57 @@ namespace TaffySharp
         /// <param name="style"></param>
         public bool SetStyle(Style style) => Tree.SetStyle(this, style);
 
+        /// <summary>
+        /// Sets a shared style for this node
+        /// </summary>
+        /// <param name="style"></param>
+        public bool SetStyle(SharedStyle style) => Tree.SetStyle(this, style);
+
+        /// <summary>
+        /// Sets the tag that stylesheet type selectors match
+        /// </summary>
+        /// <param name="tag"></param>
+        public bool SetTag(string tag) => Tree.SetTag(this, tag);
+
+        /// <summary>
+        /// Sets the id that stylesheet <c>#id</c> selectors match
+        /// </summary>
+        /// <param name="id"></param>
+        public bool SetElementId(string id) => Tree.SetElementId(this, id);
+
+        /// <summary>
+        /// Replaces the classes that stylesheet <c>.class</c> selectors match
+        /// </summary>
+        /// <param name="classes"></param>
+        public bool SetClasses(params string[] classes) => Tree.SetClasses(this, classes);
+
+        /// <summary>
+        /// Adds a class to this node
+        /// </summary>
+        /// <param name="class"></param>
+        public bool AddClass(string @class) => Tree.AddClass(this, @class);
+
+        /// <summary>
+        /// Removes a class from this node
+        /// </summary>
+        /// <param name="class"></param>
+        public bool RemoveClass(string @class) => Tree.RemoveClass(this, @class);
+
+        /// <summary>
+        /// Sets whether the content of this node is sized by the tree's <see cref="MeasureFunction"/>
+        /// </summary>
+        /// <param name="enabled"></param>
+        public bool SetMeasured(bool enabled = true) => Tree.SetMeasured(this, enabled);
+
+        /// <summary>
+        /// Makes this node a text leaf measured with <paramref name="font"/>
+        /// </summary>
+        /// <param name="font"></param>
+        /// <param name="text"></param>
+        /// <param name="fontSize"></param>
+        /// <param name="lineHeight"></param>
+        public bool SetText(Font? font, string text, float fontSize, float lineHeight = 0) => Tree.SetText(this, font, text, fontSize, lineHeight);
+
         /// <summary>
         /// Adds a child to this node
         /// </summary>
@@ -0,0 +1,85 @@
+﻿using System;
+using TaffySharp.Lib;
+
+namespace TaffySharp
+{
+    /// <summary>
+    /// A <see cref="Style"/> converted once on the native side, that can be applied to many nodes
+    /// without converting it (and pinning its grid tracks) again for every node.
+    /// </summary>
+    public unsafe class SharedStyle : IDisposable
+    {
+        private bool _disposed;
+
+        internal UIntPtr Handle { get; private set; }
+
+        /// <summary>
+        /// Converts <paramref name="style"/> into a shared style
+        /// </summary>
+        /// <param name="style"></param>
+        /// <exception cref="InvalidOperationException"></exception>
+        public SharedStyle(Style style)
+        {
+            using (var c_Style = style.ToCStruct())
+            {
+                Handle = NativeMethods.taffy_style_create((c_Style*)c_Style.NativePtr);
+            }
+
+            if (Handle == UIntPtr.Zero)
+                throw new InvalidOperationException("Failed to create SharedStyle");
+        }
+
+        private SharedStyle(UIntPtr handle)
+        {
+            Handle = handle;
+        }
+
+        /// <summary>
+        /// The style at <paramref name="t"/> of a transition from <paramref name="from"/> (at 0) to <paramref name="to"/> (at 1).
+        /// Lengths of the same unit, numbers and grid tracks of the same shape interpolate, everything else switches at 0.5.
+        /// </summary>
+        /// <param name="from"></param>
+        /// <param name="to"></param>
+        /// <param name="t"></param>
+        /// <returns></returns>
+        /// <exception cref="InvalidOperationException"></exception>
+        public static SharedStyle Lerp(Style from, Style to, float t)
+        {
+            UIntPtr handle;
+            using (var a = from.ToCStruct())
+            using (var b = to.ToCStruct())
+            {
+                c_Style lerped;
+                if (NativeMethods.taffy_style_lerp((c_Style*)a.NativePtr, (c_Style*)b.NativePtr, t, &lerped) != 0)
+                    throw new InvalidOperationException("Failed to interpolate styles");
+                handle = NativeMethods.taffy_style_create(&lerped);
+                NativeMethods.taffy_style_lerp_free(&lerped);
+            }
+
+            if (handle == UIntPtr.Zero)
+                throw new InvalidOperationException("Failed to create SharedStyle");
+            return new SharedStyle(handle);
+        }
+
+        /// <summary>
+        /// Releases the native style. Nodes it was applied to keep their style.
+        /// </summary>
+        public void Dispose()
+        {
+            if (!_disposed)
+            {
+                if (Handle != UIntPtr.Zero)
+                {
+                    NativeMethods.taffy_style_free(Handle);
+                    Handle = UIntPtr.Zero;
+                }
+                _disposed = true;
+            }
+        }
+
+        ~SharedStyle()
+        {
+            Dispose();
+        }
+    }
+}
@@ -229,8 +229,19 @@ namespace TaffySharp
         /// Converts this style to a C struct
         /// Stores allocated pointers in _allocatedPointers for cleanup in Dispose
         /// </summary>
+        /// <exception cref="NotSupportedException">The native library was built without the layout algorithm of <see cref="Display"/></exception>
         internal unsafe CStyleDisposable ToCStruct()
         {
+            var required = Display switch
+            {
+                Display.Flex => TaffyFeatures.Flexbox,
+                Display.Grid => TaffyFeatures.Grid,
+                Display.Block => TaffyFeatures.BlockLayout,
+                _ => TaffyFeatures.None,
+            };
+            if ((TaffyTree.SupportedFeatures & required) != required)
+                throw new NotSupportedException($"Display.{Display} needs the native library to be built with the {required} feature");
+
             List<IntPtr> allocatedPointers = new List<IntPtr>();
 
             // Allocate memory for c_Style
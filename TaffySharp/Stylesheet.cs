@@ -0,0 +1,54 @@
+﻿using System;
+using System.Text;
+using TaffySharp.Lib;
+
+namespace TaffySharp
+{
+    /// <summary>
+    /// A parsed stylesheet, matched against the tags, ids and classes of nodes once it is set on a <see cref="TaffyTree"/>
+    /// </summary>
+    public unsafe class Stylesheet : IDisposable
+    {
+        private bool _disposed;
+
+        internal UIntPtr Handle { get; private set; }
+
+        /// <summary>
+        /// Parses <paramref name="css"/> (see taffy_ffi/src/stylesheet.rs for the supported subset)
+        /// </summary>
+        /// <param name="css"></param>
+        /// <exception cref="ArgumentException">The stylesheet has a syntax error or an unsupported property</exception>
+        public Stylesheet(string css)
+        {
+            var bytes = Encoding.UTF8.GetBytes(css);
+            fixed (byte* cssPtr = bytes)
+            {
+                Handle = NativeMethods.taffy_stylesheet_parse(cssPtr, (nuint)bytes.Length);
+            }
+
+            if (Handle == UIntPtr.Zero)
+                throw new ArgumentException("Failed to parse stylesheet", nameof(css));
+        }
+
+        /// <summary>
+        /// Releases the native stylesheet. Trees it was set on keep using it until it is replaced.
+        /// </summary>
+        public void Dispose()
+        {
+            if (!_disposed)
+            {
+                if (Handle != UIntPtr.Zero)
+                {
+                    NativeMethods.taffy_stylesheet_free(Handle);
+                    Handle = UIntPtr.Zero;
+                }
+                _disposed = true;
+            }
+        }
+
+        ~Stylesheet()
+        {
+            Dispose();
+        }
+    }
+}
@@ -1,4 +1,5 @@
 ﻿using System.Runtime.InteropServices;
+using System.Text;
 using TaffySharp.Lib;
 
 namespace TaffySharp;
@@ -11,6 +12,37 @@ public unsafe class TaffyTree : IDisposable
     private UIntPtr _tree;
     private bool _disposed;
 
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    private delegate void CustomLayoutCallback(nuint userData, nuint context, ulong node, c_LayoutInput* input, c_LayoutOutput* output);
+
+    // Kept alive while it is registered with the native side
+    private CustomLayoutCallback? _customLayout;
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    private delegate void MeasureCallback(nuint userData, ulong node, float knownWidth, float knownHeight, c_AvailableSpace availableSpace, c_MeasureOutput* output);
+
+    // Kept alive while it is registered with the native side
+    private MeasureCallback? _measure;
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    private delegate void BatchMeasureCallback(nuint userData, c_MeasureQuery* queries, c_MeasureOutput* outputs, nuint count);
+
+    // Kept alive while it is registered with the native side
+    private BatchMeasureCallback? _batchMeasure;
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    private delegate void LoggerCallback(nuint userData, int severity, ulong node, byte* message, nuint length);
+
+    // Kept alive while it is registered with the native side
+    private LoggerCallback? _logger;
+
+    private static TaffyFeatures? _supportedFeatures;
+
+    /// <summary>
+    /// The features the native library was built with. Styles that use the <see cref="Display"/> of a missing layout algorithm are rejected.
+    /// </summary>
+    public static TaffyFeatures SupportedFeatures => _supportedFeatures ??= (TaffyFeatures)NativeMethods.taffy_ffi_supported_features();
+
     /// <summary>
     /// Creates a new <see cref="TaffyTree"/> that can store <paramref name="initializeCapacity"/> nodes before reallocation
     /// </summary>
@@ -18,6 +50,7 @@ public unsafe class TaffyTree : IDisposable
     /// <exception cref="InvalidOperationException"></exception>
     public TaffyTree(uint initializeCapacity = 16)
     {
+        NativeAbi.EnsureCompatible();
         _tree = NativeMethods.taffytree_with_capacity((nuint)initializeCapacity);
 
         if (_tree == UIntPtr.Zero)
@@ -40,6 +73,34 @@ public unsafe class TaffyTree : IDisposable
         NativeMethods.taffytree_disable_rounding(_tree);
     }
 
+    /// <summary>
+    /// Starts a batch. If any operation fails before the batch is committed, the tree is restored to
+    /// the state it had when the batch began and later operations are refused until the batch is closed.
+    /// </summary>
+    /// <returns>false if a batch is already open</returns>
+    public bool BeginBatch()
+    {
+        return NativeMethods.taffytree_begin_batch(_tree) == 0;
+    }
+
+    /// <summary>
+    /// Closes the open batch, keeping its changes
+    /// </summary>
+    /// <returns>false if there was no open batch, or if an operation in it failed and the tree was restored</returns>
+    public bool CommitBatch()
+    {
+        return NativeMethods.taffytree_commit_batch(_tree) == 0;
+    }
+
+    /// <summary>
+    /// Closes the open batch and restores the tree to the state it had before the batch
+    /// </summary>
+    /// <returns>false if there was no open batch</returns>
+    public bool RollbackBatch()
+    {
+        return NativeMethods.taffytree_rollback_batch(_tree) == 0;
+    }
+
 
     /// <summary>
     /// Creates and adds a new unattached leaf node to the tree, and returns the node of the new node
@@ -56,6 +117,17 @@ public unsafe class TaffyTree : IDisposable
         }
     }
 
+    /// <summary>
+    /// Creates and adds a new unattached leaf node with a shared style, and returns the new node
+    /// </summary>
+    /// <param name="style"></param>
+    /// <returns></returns>
+    public Node NewLeaf(SharedStyle style)
+    {
+        var nodeId = NativeMethods.taffytree_new_leaf_with_style_handle(_tree, style.Handle);
+        return new Node(this, nodeId);
+    }
+
     /// <summary>
     /// Creates and adds a new node, which may have any number of `children`
     /// </summary>
@@ -104,6 +176,27 @@ public unsafe class TaffyTree : IDisposable
         NativeMethods.taffytree_clear(_tree);
     }
 
+    /// <summary>
+    /// Applies an encoded command buffer (see taffy_ffi/src/commands.rs for the format) in a single call.
+    /// The buffer is applied atomically, the ids of the nodes it creates are written to <paramref name="results"/>.
+    /// </summary>
+    /// <param name="commands"></param>
+    /// <param name="results"></param>
+    /// <returns>The number of nodes created, -1 for an unrecognized header, or -2 - n if command n failed</returns>
+    public int ApplyCommands(byte[] commands, ulong[] results)
+    {
+        fixed (byte* commandsPtr = commands)
+        fixed (ulong* resultsPtr = results)
+        {
+            return NativeMethods.taffytree_apply_commands(_tree, commandsPtr, (nuint)commands.Length, resultsPtr, (nuint)results.Length);
+        }
+    }
+
+    public bool InsertChildAtIndex(Node parent, uint index, Node child)
+    {
+        return NativeMethods.taffytree_insert_child_at_index(_tree, parent.Id, index, child.Id) == 0;
+    }
+
     public bool ReplaceChildAtIndex(Node parent, uint index, Node child)
     {
         return NativeMethods.taffytree_replace_child_at_index(_tree, parent.Id, index, child.Id) == 0;
@@ -147,11 +240,186 @@ public unsafe class TaffyTree : IDisposable
         }
     }
 
+    /// <summary>
+    /// Applies a shared style to a node, without converting the style again
+    /// </summary>
+    /// <param name="node"></param>
+    /// <param name="style"></param>
+    /// <returns></returns>
+    public bool SetStyle(Node node, SharedStyle style)
+    {
+        return NativeMethods.taffytree_set_style_handle(_tree, node.Id, style.Handle) == 0;
+    }
+
+    /// <summary>
+    /// Replaces the style of a shared style and restyles every node of this tree it was last applied to.
+    /// Nodes of other trees keep the previous style until they are updated the same way.
+    /// </summary>
+    /// <param name="shared"></param>
+    /// <param name="style"></param>
+    /// <returns></returns>
+    public bool UpdateSharedStyle(SharedStyle shared, Style style)
+    {
+        using (var c_Style = style.ToCStruct())
+        {
+            return NativeMethods.taffytree_update_style_handle(_tree, shared.Handle, (c_Style*)c_Style.NativePtr) == 0;
+        }
+    }
+
+    /// <summary>
+    /// Sets the stylesheet resolved into the style of every node with a tag, an id or classes.
+    /// Pass null to remove it, the nodes then keep the style set with <see cref="SetStyle(Node, Style)"/>.
+    /// Unlike a CSS inline style, that style does not win over the rules: it only provides the properties no matching rule declares.
+    /// </summary>
+    /// <param name="stylesheet"></param>
+    /// <returns></returns>
+    public bool SetStylesheet(Stylesheet? stylesheet)
+    {
+        return NativeMethods.taffytree_set_stylesheet(_tree, stylesheet?.Handle ?? UIntPtr.Zero) == 0;
+    }
+
+    /// <summary>
+    /// Sets the size that viewport units (<see cref="DimensionType.ViewportWidth"/> etc.) are relative to, initially 0 x 0.
+    /// Nodes using them are marked dirty if their size changes with it.
+    /// </summary>
+    /// <param name="viewport"></param>
+    /// <returns></returns>
+    public bool SetViewport(Size<float> viewport)
+    {
+        return NativeMethods.taffytree_set_viewport(_tree, viewport.Width, viewport.Height) == 0;
+    }
+
+    /// <summary>
+    /// Sets the font size <see cref="DimensionType.Rem"/> units are relative to, and <see cref="DimensionType.Em"/> units
+    /// of nodes that do not inherit one, initially 16. Nodes using them are marked dirty if their size changes with it.
+    /// </summary>
+    /// <param name="fontSize">A positive size</param>
+    /// <returns></returns>
+    public bool SetRootFontSize(float fontSize)
+    {
+        return NativeMethods.taffytree_set_root_font_size(_tree, fontSize) == 0;
+    }
+
+    /// <summary>
+    /// Sets the font size <see cref="DimensionType.Em"/> units of the node and of its descendants without their own are
+    /// relative to. Pass null to inherit it again.
+    /// </summary>
+    /// <param name="node"></param>
+    /// <param name="fontSize">A positive size, or null</param>
+    /// <returns></returns>
+    public bool SetFontSize(Node node, float? fontSize)
+    {
+        return NativeMethods.taffytree_set_font_size(_tree, node.Id, fontSize ?? float.NaN) == 0;
+    }
+
+    /// <summary>
+    /// Gets the font size <see cref="DimensionType.Em"/> units of the node are relative to: its own, inherited or the tree's
+    /// </summary>
+    /// <param name="node"></param>
+    /// <param name="fontSize"></param>
+    /// <returns></returns>
+    public bool GetFontSize(Node node, out float fontSize)
+    {
+        float size;
+        var ret = NativeMethods.taffytree_font_size(_tree, node.Id, &size);
+        fontSize = size;
+        return ret == 0;
+    }
+
+    public bool SetTag(Node node, string tag)
+    {
+        fixed (byte* tagPtr = Encoding.UTF8.GetBytes(tag))
+        {
+            return NativeMethods.taffytree_set_tag(_tree, node.Id, tagPtr, (nuint)Encoding.UTF8.GetByteCount(tag)) == 0;
+        }
+    }
+
+    public bool SetElementId(Node node, string id)
+    {
+        fixed (byte* idPtr = Encoding.UTF8.GetBytes(id))
+        {
+            return NativeMethods.taffytree_set_element_id(_tree, node.Id, idPtr, (nuint)Encoding.UTF8.GetByteCount(id)) == 0;
+        }
+    }
+
+    /// <summary>
+    /// Replaces the classes of <paramref name="node"/>
+    /// </summary>
+    /// <param name="node"></param>
+    /// <param name="classes"></param>
+    /// <returns></returns>
+    public bool SetClasses(Node node, params string[] classes)
+    {
+        var joined = string.Join(" ", classes);
+        fixed (byte* classesPtr = Encoding.UTF8.GetBytes(joined))
+        {
+            return NativeMethods.taffytree_set_classes(_tree, node.Id, classesPtr, (nuint)Encoding.UTF8.GetByteCount(joined)) == 0;
+        }
+    }
+
+    public bool AddClass(Node node, string @class)
+    {
+        fixed (byte* classPtr = Encoding.UTF8.GetBytes(@class))
+        {
+            return NativeMethods.taffytree_add_class(_tree, node.Id, classPtr, (nuint)Encoding.UTF8.GetByteCount(@class)) == 0;
+        }
+    }
+
+    public bool RemoveClass(Node node, string @class)
+    {
+        fixed (byte* classPtr = Encoding.UTF8.GetBytes(@class))
+        {
+            return NativeMethods.taffytree_remove_class(_tree, node.Id, classPtr, (nuint)Encoding.UTF8.GetByteCount(@class)) == 0;
+        }
+    }
+
     public bool ComputeLayout(Node node, AvailableSpace availableSpace)
     {
         return NativeMethods.taffytree_compute_layout(_tree, node.Id, availableSpace.ToCStruct()) == 0;
     }
 
+    /// <summary>
+    /// Computes the layout of <paramref name="root"/> sized to its content, as for a popup or tooltip: its fit-content
+    /// size within <paramref name="maxSize"/>, clamped between <paramref name="minSize"/> and <paramref name="maxSize"/>.
+    /// </summary>
+    /// <param name="root"></param>
+    /// <param name="minSize"></param>
+    /// <param name="maxSize">Bounds that can be <see cref="float.PositiveInfinity"/>, percentages of the root resolve against the finite ones</param>
+    /// <param name="size">The size the root was laid out at, before rounding</param>
+    /// <returns></returns>
+    public bool ComputeLayoutFit(Node root, Size<float> minSize, Size<float> maxSize, out Size<float> size)
+    {
+        float width, height;
+        var ret = NativeMethods.taffytree_compute_layout_fit(_tree, root.Id, minSize.Width, minSize.Height, maxSize.Width, maxSize.Height, &width, &height);
+        size = new Size<float>(width, height);
+        return ret == 0;
+    }
+
+    /// <summary>
+    /// Computes the layouts of several roots, each in its own available space, skipping those whose layout is up to date:
+    /// roots that are not dirty and were last laid out as a root in the same available space, with the same rounding,
+    /// viewport and root font size as the tree has now
+    /// </summary>
+    /// <param name="roots"></param>
+    /// <param name="availableSpaces">The available space of each root</param>
+    /// <returns>What was done with each root</returns>
+    public LayoutResult[] ComputeLayouts(Node[] roots, AvailableSpace[] availableSpaces)
+    {
+        if (roots.Length != availableSpaces.Length)
+            throw new ArgumentException("Every root needs an available space", nameof(availableSpaces));
+
+        var rootIds = roots.Select(x => x.Id).ToArray();
+        var spaces = availableSpaces.Select(x => x.ToCStruct()).ToArray();
+        var results = new int[roots.Length];
+        fixed (ulong* rootsPtr = rootIds)
+        fixed (c_AvailableSpace* spacesPtr = spaces)
+        fixed (int* resultsPtr = results)
+        {
+            NativeMethods.taffytree_compute_layouts(_tree, rootsPtr, spacesPtr, (nuint)roots.Length, resultsPtr);
+        }
+        return results.Select(x => (LayoutResult)x).ToArray();
+    }
+
     public bool GetLayout(Node node, out Layout layout)
     {
         IntPtr layoutPtr = Marshal.AllocHGlobal(Marshal.SizeOf<c_Layout>());
@@ -173,6 +441,372 @@ public unsafe class TaffyTree : IDisposable
         }
     }
 
+    /// <summary>
+    /// Sets the function that lays out nodes flagged with <see cref="SetCustomLayout"/>.
+    /// Pass null to lay those nodes out as leaves.
+    /// </summary>
+    /// <param name="function"></param>
+    public void SetCustomLayoutFunction(CustomLayoutFunction? function)
+    {
+        if (function == null)
+        {
+            NativeMethods.taffytree_set_custom_layout_callback(_tree, null, 0);
+            _customLayout = null;
+            return;
+        }
+
+        _customLayout = (_, context, node, input, output) =>
+            *output = function(new LayoutContext(this, context), new Node(this, node), new LayoutInput(*input)).ToCStruct();
+        var callback = (delegate* unmanaged[Cdecl]<nuint, nuint, ulong, c_LayoutInput*, c_LayoutOutput*, void>)Marshal.GetFunctionPointerForDelegate(_customLayout);
+        NativeMethods.taffytree_set_custom_layout_callback(_tree, callback, 0);
+    }
+
+    /// <summary>
+    /// Sets whether <paramref name="node"/> is laid out by the <see cref="CustomLayoutFunction"/> instead of by its display mode
+    /// </summary>
+    /// <param name="node"></param>
+    /// <param name="enabled"></param>
+    /// <returns></returns>
+    public bool SetCustomLayout(Node node, bool enabled)
+    {
+        return NativeMethods.taffytree_set_custom_layout(_tree, node.Id, enabled ? 1 : 0) == 0;
+    }
+
+    /// <summary>
+    /// Sets the function that sizes the content of leaves flagged with <see cref="SetMeasured"/>.
+    /// Pass null to size those leaves from their styles only.
+    /// </summary>
+    /// <param name="function"></param>
+    public void SetMeasureFunction(MeasureFunction? function)
+    {
+        if (function == null)
+        {
+            NativeMethods.taffytree_set_measure_callback(_tree, null, 0);
+            _measure = null;
+            return;
+        }
+
+        _measure = (_, node, knownWidth, knownHeight, availableSpace, output) =>
+            *output = function(new Node(this, node), KnownDimensions(knownWidth, knownHeight), new AvailableSpace(availableSpace)).ToCStruct();
+        var callback = (delegate* unmanaged[Cdecl]<nuint, ulong, float, float, c_AvailableSpace, c_MeasureOutput*, void>)Marshal.GetFunctionPointerForDelegate(_measure);
+        NativeMethods.taffytree_set_measure_callback(_tree, callback, 0);
+    }
+
+    /// <summary>
+    /// Sets a function that answers many measurements at once, to save a call into managed code per leaf. Before each
+    /// layout pass, the measurements expected for the dirty leaves flagged with <see cref="SetMeasured"/> are passed to
+    /// it in one call. Other measurements use the <see cref="MeasureFunction"/> if one is set, or this function with a
+    /// single query otherwise. Pass null to remove it.
+    /// </summary>
+    /// <param name="function"></param>
+    public void SetBatchMeasureFunction(BatchMeasureFunction? function)
+    {
+        if (function == null)
+        {
+            NativeMethods.taffytree_set_batch_measure_callback(_tree, null, 0);
+            _batchMeasure = null;
+            return;
+        }
+
+        _batchMeasure = (_, queries, outputs, count) =>
+        {
+            var managedQueries = new MeasureQuery[(int)count];
+            for (var i = 0; i < managedQueries.Length; i++)
+            {
+                var query = queries[i];
+                managedQueries[i] = new MeasureQuery(new Node(this, query.node), KnownDimensions(query.known_width, query.known_height), new AvailableSpace(query.available_space));
+            }
+
+            var managedOutputs = new MeasureOutput[managedQueries.Length];
+            function(managedQueries, managedOutputs);
+            for (var i = 0; i < managedOutputs.Length; i++)
+                outputs[i] = managedOutputs[i].ToCStruct();
+        };
+        var callback = (delegate* unmanaged[Cdecl]<nuint, c_MeasureQuery*, c_MeasureOutput*, nuint, void>)Marshal.GetFunctionPointerForDelegate(_batchMeasure);
+        NativeMethods.taffytree_set_batch_measure_callback(_tree, callback, 0);
+    }
+
+    internal static Size<float?> KnownDimensions(float width, float height) =>
+        new(float.IsNaN(width) ? null : width, float.IsNaN(height) ? null : height);
+
+    /// <summary>
+    /// Sets whether the content of <paramref name="node"/> is sized by the <see cref="MeasureFunction"/> or <see cref="BatchMeasureFunction"/>. Only used while it has no children.
+    /// </summary>
+    /// <param name="node"></param>
+    /// <param name="enabled"></param>
+    /// <returns></returns>
+    public bool SetMeasured(Node node, bool enabled)
+    {
+        return NativeMethods.taffytree_set_measured(_tree, node.Id, enabled ? 1 : 0) == 0;
+    }
+
+    /// <summary>
+    /// Makes <paramref name="node"/> a text leaf measured with <paramref name="font"/>, wrapping at word boundaries
+    /// to fit the available width. Pass a null font to remove the text.
+    /// </summary>
+    /// <param name="node"></param>
+    /// <param name="font"></param>
+    /// <param name="text"></param>
+    /// <param name="fontSize">The font size in pixels</param>
+    /// <param name="lineHeight">The line height in pixels, or 0 to use the metrics of the font</param>
+    /// <returns></returns>
+    public bool SetText(Node node, Font? font, string text, float fontSize, float lineHeight = 0)
+    {
+        fixed (byte* textPtr = Encoding.UTF8.GetBytes(text))
+        {
+            return NativeMethods.taffytree_set_text(_tree, node.Id, font?.Handle ?? UIntPtr.Zero, textPtr, (nuint)Encoding.UTF8.GetByteCount(text), fontSize, lineHeight) == 0;
+        }
+    }
+
+    /// <summary>
+    /// Scrolls the content of <paramref name="node"/>, clamped to <see cref="GetScrollExtent"/>
+    /// </summary>
+    /// <param name="node"></param>
+    /// <param name="offset"></param>
+    /// <returns></returns>
+    public bool SetScrollOffset(Node node, Point<float> offset)
+    {
+        return NativeMethods.taffytree_set_scroll_offset(_tree, node.Id, offset.X, offset.Y) == 0;
+    }
+
+    public bool GetScrollOffset(Node node, out Point<float> offset)
+    {
+        float x, y;
+        var ret = NativeMethods.taffytree_scroll_offset(_tree, node.Id, &x, &y);
+        offset = new Point<float>(x, y);
+        return ret == 0;
+    }
+
+    /// <summary>
+    /// Gets how far the content of <paramref name="node"/> can be scrolled, based on the last computed layout
+    /// </summary>
+    /// <param name="node"></param>
+    /// <param name="extent"></param>
+    /// <returns></returns>
+    public bool GetScrollExtent(Node node, out Point<float> extent)
+    {
+        float x, y;
+        var ret = NativeMethods.taffytree_scroll_extent(_tree, node.Id, &x, &y);
+        extent = new Point<float>(x, y);
+        return ret == 0;
+    }
+
+    /// <summary>
+    /// Gets the location of <paramref name="node"/> relative to its root, after the scroll offsets of its ancestors
+    /// </summary>
+    /// <param name="node"></param>
+    /// <param name="location"></param>
+    /// <returns></returns>
+    public bool GetAbsoluteLocation(Node node, out Point<float> location)
+    {
+        float x, y;
+        var ret = NativeMethods.taffytree_absolute_location(_tree, node.Id, &x, &y);
+        location = new Point<float>(x, y);
+        return ret == 0;
+    }
+
+    /// <summary>
+    /// Finds the topmost node under <paramref name="point"/> in the subtree of <paramref name="root"/>,
+    /// in the same coordinates as <see cref="GetAbsoluteLocation"/>
+    /// </summary>
+    /// <param name="root"></param>
+    /// <param name="point"></param>
+    /// <returns>The node that was hit, or null</returns>
+    public Node? HitTest(Node root, Point<float> point)
+    {
+        var nodeId = NativeMethods.taffytree_hit_test(_tree, root.Id, point.X, point.Y);
+        return nodeId == 0 ? null : new Node(this, nodeId);
+    }
+
+    /// <summary>
+    /// Sets the function that receives a log of each node laid out by <see cref="ComputeLayout"/>. It is called once the layout
+    /// is done, for every line in order. Pass null to stop logging.
+    /// </summary>
+    /// <param name="function"></param>
+    /// <returns>False if the native library was built without the <c>debug</c> feature</returns>
+    public bool SetLogger(LogFunction? function)
+    {
+        if (function == null)
+        {
+            var result = NativeMethods.taffytree_set_logger(_tree, null, 0) == 0;
+            _logger = null;
+            return result;
+        }
+
+        _logger = (_, severity, node, message, length) =>
+        {
+            var text = Encoding.UTF8.GetString(message, (int)length);
+            function((LogSeverity)severity, node == 0 ? null : new Node(this, node), text);
+        };
+        var callback = (delegate* unmanaged[Cdecl]<nuint, int, ulong, byte*, nuint, void>)Marshal.GetFunctionPointerForDelegate(_logger);
+        if (NativeMethods.taffytree_set_logger(_tree, callback, 0) != 0)
+        {
+            _logger = null;
+            return false;
+        }
+        return true;
+    }
+
+    /// <summary>
+    /// Returns the counters from the last <see cref="ComputeLayout"/>
+    /// </summary>
+    /// <returns></returns>
+    public LayoutStats GetStats()
+    {
+        var stats = new c_LayoutStats();
+        NativeMethods.taffytree_get_stats(_tree, &stats);
+        return new LayoutStats(stats);
+    }
+
+    /// <summary>
+    /// Returns the counters for <paramref name="node"/> from the last <see cref="ComputeLayout"/>, all zero if it was not laid out
+    /// </summary>
+    /// <param name="node"></param>
+    /// <returns></returns>
+    public NodeStats GetNodeStats(Node node)
+    {
+        var stats = new c_NodeStats();
+        NativeMethods.taffytree_get_node_stats(_tree, node.Id, &stats);
+        return new NodeStats(stats);
+    }
+
+    /// <summary>
+    /// Zeroes the counters of the tree and of every node until the next <see cref="ComputeLayout"/>
+    /// </summary>
+    public void ResetStats()
+    {
+        NativeMethods.taffytree_reset_stats(_tree);
+    }
+
+    /// <summary>
+    /// Returns an estimate of the memory held by this tree
+    /// </summary>
+    /// <returns></returns>
+    public MemoryUsage GetMemoryUsage()
+    {
+        var usage = new c_MemoryUsage();
+        NativeMethods.taffytree_memory_usage(_tree, &usage);
+        return new MemoryUsage(usage);
+    }
+
+    /// <summary>
+    /// Releases the spare capacity of the storage of this tree. Nodes stay valid, so the slots of removed nodes are
+    /// kept and only reused by new nodes: removing a large subtree does not make the tree smaller.
+    /// </summary>
+    public void ShrinkToFit()
+    {
+        NativeMethods.taffytree_shrink_to_fit(_tree);
+    }
+
+    /// <summary>
+    /// Records the boxes of <paramref name="root"/> and its descendants from the last layout, to animate from them once
+    /// the layout changed
+    /// </summary>
+    /// <param name="root"></param>
+    /// <returns>The snapshot, or null if <paramref name="root"/> is not a valid node</returns>
+    public LayoutSnapshot? SnapshotLayouts(Node root)
+    {
+        var handle = NativeMethods.taffytree_snapshot_layouts(_tree, root.Id);
+        return handle == UIntPtr.Zero ? null : new LayoutSnapshot(handle);
+    }
+
+    /// <summary>
+    /// Interpolates the boxes of <paramref name="snapshot"/> towards the current boxes of <paramref name="root"/> and its
+    /// descendants. The nodes of the subtree come first, parents before children, followed by the nodes that disappeared.
+    /// </summary>
+    /// <param name="root"></param>
+    /// <param name="snapshot"></param>
+    /// <param name="t">The progress of the transition, from 0 (the snapshot) to 1 (the current layout)</param>
+    /// <returns>The boxes, or an empty array if <paramref name="root"/> is not a valid node</returns>
+    public AnimatedRect[] InterpolateLayouts(Node root, LayoutSnapshot snapshot, float t)
+    {
+        var count = NativeMethods.taffytree_interpolate_layouts(_tree, root.Id, snapshot.Handle, t, null, 0);
+        if (count <= 0)
+            return Array.Empty<AnimatedRect>();
+
+        var rects = new c_AnimatedRect[count];
+        fixed (c_AnimatedRect* rectsPtr = rects)
+        {
+            NativeMethods.taffytree_interpolate_layouts(_tree, root.Id, snapshot.Handle, t, rectsPtr, (nuint)rects.Length);
+        }
+
+        return rects.Select(rect => new AnimatedRect(
+            new Node(this, rect.node),
+            new Point<float>(rect.location[0], rect.location[1]),
+            new Size<float>(rect.size[0], rect.size[1]),
+            (LayoutChange)rect.change,
+            rect.presence)).ToArray();
+    }
+
+    /// <summary>
+    /// Computes the clip rect of every node in the subtree of <paramref name="root"/>, parents before children
+    /// </summary>
+    /// <param name="root"></param>
+    /// <returns>The clip rects, or an empty array if <paramref name="root"/> is not a valid node</returns>
+    public ClipRect[] GetClipRects(Node root)
+    {
+        var count = NativeMethods.taffytree_clip_rects(_tree, root.Id, null, 0);
+        if (count <= 0)
+            return Array.Empty<ClipRect>();
+
+        var rects = new c_ClipRect[count];
+        fixed (c_ClipRect* rectsPtr = rects)
+        {
+            NativeMethods.taffytree_clip_rects(_tree, root.Id, rectsPtr, (nuint)rects.Length);
+        }
+
+        return rects.Select(rect => new ClipRect(
+            new Node(this, rect.node),
+            new Point<float>(rect.min[0], rect.min[1]),
+            new Point<float>(rect.max[0], rect.max[1]))).ToArray();
+    }
+
+    /// <summary>
+    /// Renders the computed layout of <paramref name="root"/> and its descendants as an SVG image, for debugging
+    /// </summary>
+    /// <param name="root"></param>
+    /// <returns>The SVG, or null if <paramref name="root"/> is not a valid node</returns>
+    public string? RenderSvg(Node root)
+    {
+        return ReadBuffer((buf, cap) => NativeMethods.taffytree_render_svg(_tree, root.Id, buf, cap));
+    }
+
+    /// <summary>
+    /// Rasterizes the computed layout of <paramref name="root"/> and its descendants into a character grid,
+    /// drawing each border box with box-drawing characters. Useful as a snapshot in tests.
+    /// </summary>
+    /// <param name="root"></param>
+    /// <param name="scale">Character cells per layout unit</param>
+    /// <returns>The grid, or null if <paramref name="root"/> is not a valid node or the scale is out of range</returns>
+    public string? RenderAscii(Node root, float scale = 0.1f)
+    {
+        return ReadBuffer((buf, cap) => NativeMethods.taffytree_render_ascii(_tree, root.Id, scale, buf, cap));
+    }
+
+    private delegate int BufferWriter(byte* buf, nuint cap);
+
+    /// <summary>
+    /// Reads a UTF-8 string from an export that returns the length it needs, growing the buffer once if it is too small
+    /// </summary>
+    private static string? ReadBuffer(BufferWriter write)
+    {
+        var buffer = new byte[4096];
+        while (true)
+        {
+            int length;
+            fixed (byte* bufferPtr = buffer)
+            {
+                length = write(bufferPtr, (nuint)buffer.Length);
+            }
+
+            if (length < 0)
+                return null;
+            if (length <= buffer.Length)
+                return Encoding.UTF8.GetString(buffer, 0, length);
+            buffer = new byte[length];
+        }
+    }
+
     /// <summary>
     /// Disposes the <see cref="TaffyTree"/>, freeing its memory
     /// </summary>
@@ -0,0 +1,103 @@
+//! Layout snapshots, for animating a layout change from the old boxes of the nodes to their new ones
+//! (FLIP). The host snapshots a subtree before changing it, computes the new layout, then draws the
+//! interpolated boxes of `taffytree_interpolate_layouts` for each frame of the transition.
+//!
+//! Nodes in both layouts move and resize from their old box to their new one. A node that appeared since
+//! the snapshot stays at its new box and one that disappeared stays at its old box, while their presence
+//! goes from 0 to 1 or from 1 to 0, which the host can use to fade or scale them.
+
+use std::collections::{HashMap, HashSet};
+
+use taffy::prelude::*;
+
+use crate::{c_AnimatedRect, FfiTree};
+
+const MOVED: i32 = 0;
+const APPEARED: i32 = 1;
+const DISAPPEARED: i32 = 2;
+
+/// The border box of a node, in the coordinates of `taffytree_absolute_location`
+#[derive(Clone, Copy)]
+pub(crate) struct NodeBox {
+    node: NodeId,
+    location: taffy::Point<f32>,
+    size: Size<f32>,
+}
+
+pub(crate) struct LayoutSnapshot {
+    /// Parents before children
+    boxes: Vec<NodeBox>,
+    index: HashMap<NodeId, usize>,
+}
+
+impl FfiTree {
+    /// The boxes of `root` and its descendants, parents before children
+    pub(crate) fn absolute_boxes(&self, root: NodeId) -> Vec<NodeBox> {
+        let mut boxes = Vec::new();
+        let mut stack = vec![(root, self.absolute_location(root))];
+        while let Some((node, location)) = stack.pop() {
+            let size = self.layout(node).map_or(Size::ZERO, |layout| layout.size);
+            boxes.push(NodeBox { node, location, size });
+
+            let scroll = self.scroll_offset(node);
+            let children = self.taffy.children(node).unwrap_or_default();
+            // Reversed so that children pop off the stack in order
+            for child in children.into_iter().rev() {
+                let child_location = self.layout(child).map_or(taffy::Point::ZERO, |layout| layout.location);
+                let child_location = taffy::Point {
+                    x: location.x + child_location.x - scroll.x,
+                    y: location.y + child_location.y - scroll.y,
+                };
+                stack.push((child, child_location));
+            }
+        }
+        boxes
+    }
+}
+
+impl LayoutSnapshot {
+    pub(crate) fn new(boxes: Vec<NodeBox>) -> Self {
+        let index = boxes.iter().enumerate().map(|(i, node_box)| (node_box.node, i)).collect();
+        LayoutSnapshot { boxes, index }
+    }
+
+    /// The rects at `t` of the transition from the snapshot to `current`: the nodes of `current` in its
+    /// order, then the nodes that disappeared in the order of the snapshot
+    pub(crate) fn interpolate(&self, current: &[NodeBox], t: f32) -> Vec<c_AnimatedRect> {
+        fn lerp(a: f32, b: f32, t: f32) -> f32 {
+            a + (b - a) * t
+        }
+        let rect = |node_box: &NodeBox, change, presence: f32| c_AnimatedRect {
+            node: node_box.node.into(),
+            location: [node_box.location.x, node_box.location.y],
+            size: [node_box.size.width, node_box.size.height],
+            change,
+            presence: presence.clamp(0.0, 1.0),
+        };
+
+        let mut rects: Vec<c_AnimatedRect> = current
+            .iter()
+            .map(|new| match self.index.get(&new.node) {
+                Some(&i) => {
+                    let old = &self.boxes[i];
+                    let location = taffy::Point {
+                        x: lerp(old.location.x, new.location.x, t),
+                        y: lerp(old.location.y, new.location.y, t),
+                    };
+                    let size = Size { width: lerp(old.size.width, new.size.width, t), height: lerp(old.size.height, new.size.height, t) };
+                    rect(&NodeBox { node: new.node, location, size }, MOVED, 1.0)
+                }
+                None => rect(new, APPEARED, t),
+            })
+            .collect();
+
+        let remaining: HashSet<NodeId> = current.iter().map(|node_box| node_box.node).collect();
+        rects.extend(
+            self.boxes
+                .iter()
+                .filter(|old| !remaining.contains(&old.node))
+                .map(|old| rect(old, DISAPPEARED, 1.0 - t)),
+        );
+        rects
+    }
+}
@@ -0,0 +1,309 @@
+//! `calc()` lengths: `c_Length` dim 14, whose value is the id of an expression parsed by `taffy_calc_parse`.
+//!
+//! Expressions use the CSS syntax of `calc()`, `min()`, `max()` and `clamp()`, with lengths in `px` (or
+//! unitless) and `%`. The relative units of `units.rs` are not accepted, so an expression cannot combine
+//! `vw` or `em` with other lengths. Lengths can be added and subtracted, and multiplied or divided by
+//! plain numbers:
+//!
+//! ```text
+//! calc(100% - 32px)
+//! min(50%, 400px)
+//! clamp(200px, 30% + 16px, 600px)
+//! ```
+//!
+//! Host styles, command buffers (see `commands.rs`) and stylesheet declarations (see `stylesheet.rs`) all
+//! accept them. taffy resolves them during layout, with percentages relative to what a plain percentage
+//! of the same property is relative to.
+//!
+//! Parsed expressions are interned, so parsing the same expression again returns the same id and styles
+//! using equal expressions compare equal. They are leaked for the lifetime of the process, as taffy keeps
+//! pointers to them in styles, and at most `MAX_ID` (2^24) distinct expressions can be parsed. Parsing a
+//! new one fails after that.
+
+use std::collections::HashMap;
+use std::iter::Peekable;
+use std::str::Chars;
+use std::sync::{Mutex, OnceLock};
+
+use crate::c_Length;
+
+/// The `c_Length::dim` of calc lengths
+pub(crate) const DIM: i32 = 14;
+
+/// Ids are passed as the `f32` value of a length, which holds integers exactly up to this
+const MAX_ID: u32 = 1 << 24;
+
+#[derive(Debug)]
+#[cfg_attr(not(feature = "calc"), allow(dead_code))]
+enum Expr {
+    Px(f32),
+    /// A fraction of the basis, 0.5 for `50%`
+    Percent(f32),
+    Sum(Box<Expr>, Box<Expr>),
+    Scale(Box<Expr>, f32),
+    Min(Vec<Expr>),
+    Max(Vec<Expr>),
+    /// Minimum, preferred and maximum value
+    Clamp(Box<[Expr; 3]>),
+}
+
+#[cfg_attr(not(feature = "calc"), allow(dead_code))]
+impl Expr {
+    fn resolve(&self, basis: f32) -> f32 {
+        match self {
+            Expr::Px(value) => *value,
+            Expr::Percent(fraction) => fraction * basis,
+            Expr::Sum(a, b) => a.resolve(basis) + b.resolve(basis),
+            Expr::Scale(expr, factor) => expr.resolve(basis) * factor,
+            Expr::Min(exprs) => exprs.iter().map(|expr| expr.resolve(basis)).fold(f32::INFINITY, f32::min),
+            Expr::Max(exprs) => exprs.iter().map(|expr| expr.resolve(basis)).fold(f32::NEG_INFINITY, f32::max),
+            Expr::Clamp(exprs) => {
+                let [min, value, max] = exprs.as_ref();
+                value.resolve(basis).min(max.resolve(basis)).max(min.resolve(basis))
+            }
+        }
+    }
+}
+
+/// An interned expression. taffy keeps the pointer to it in the low bits of a length, which it uses as
+/// a tag, so it must be 8-byte aligned.
+#[repr(align(8))]
+#[cfg_attr(not(feature = "calc"), allow(dead_code))]
+struct Calc(Expr);
+
+#[derive(Default)]
+struct Interner {
+    ids: HashMap<String, u32>,
+    /// The expression of id `n` at index `n - 1`
+    calcs: Vec<&'static Calc>,
+}
+
+fn interner() -> &'static Mutex<Interner> {
+    static INTERNER: OnceLock<Mutex<Interner>> = OnceLock::new();
+    INTERNER.get_or_init(Default::default)
+}
+
+/// Parses and interns an expression, returning its id, or `None` if it is invalid
+pub(crate) fn parse(text: &str) -> Option<u32> {
+    let mut parser = Parser { chars: text.chars().peekable() };
+    let expr = parser.sum()?.into_length();
+    parser.skip_whitespace();
+    if parser.chars.next().is_some() {
+        return None;
+    }
+
+    let mut interner = interner().lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let key = format!("{expr:?}");
+    if let Some(&id) = interner.ids.get(&key) {
+        return Some(id);
+    }
+    let id = next_id(interner.calcs.len())?;
+    interner.calcs.push(Box::leak(Box::new(Calc(expr))));
+    interner.ids.insert(key, id);
+    Some(id)
+}
+
+/// The id of the expression interned after `count` others, if there is one left
+fn next_id(count: usize) -> Option<u32> {
+    u32::try_from(count + 1).ok().filter(|&id| id <= MAX_ID)
+}
+
+/// Whether `value` is the id of a parsed expression
+pub(crate) fn exists(value: f32) -> bool {
+    pointer(value).is_some()
+}
+
+/// Whether `length` is not a calc length, or one this build can lay out
+pub(crate) fn is_supported(length: c_Length) -> bool {
+    length.dim != DIM || cfg!(feature = "calc") && exists(length.value)
+}
+
+/// The pointer taffy stores for the expression with id `value`
+pub(crate) fn pointer(value: f32) -> Option<*const ()> {
+    if value.fract() != 0.0 || !(1.0..=MAX_ID as f32).contains(&value) {
+        return None;
+    }
+    let interner = interner().lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let calc: &'static Calc = interner.calcs.get(value as usize - 1)?;
+    Some(calc as *const Calc as *const ())
+}
+
+/// Resolves a calc value taffy found in a style, `ptr` being what `pointer` returned
+#[cfg_attr(not(feature = "calc"), allow(dead_code))]
+pub(crate) fn resolve(ptr: *const (), basis: f32) -> f32 {
+    let calc = unsafe { &*(ptr as *const Calc) };
+    calc.0.resolve(basis)
+}
+
+/// A parsed operand, which is either a length or a plain number until it is used as a length
+enum Value {
+    Number(f32),
+    Length(Expr),
+}
+
+impl Value {
+    /// Unitless numbers are lengths in pixels, as elsewhere
+    fn into_length(self) -> Expr {
+        match self {
+            Value::Number(value) => Expr::Px(value),
+            Value::Length(expr) => expr,
+        }
+    }
+}
+
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+}
+
+impl Parser<'_> {
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_whitespace();
+        self.chars.next_if_eq(&c).is_some()
+    }
+
+    /// Terms joined by `+` and `-`
+    fn sum(&mut self) -> Option<Value> {
+        let mut sum = self.product()?;
+        loop {
+            let sign = if self.eat('+') {
+                1.0
+            } else if self.eat('-') {
+                -1.0
+            } else {
+                return Some(sum);
+            };
+            let term = match self.product()? {
+                Value::Number(value) => Expr::Px(value * sign),
+                Value::Length(expr) if sign < 0.0 => Expr::Scale(Box::new(expr), -1.0),
+                Value::Length(expr) => expr,
+            };
+            sum = Value::Length(Expr::Sum(Box::new(sum.into_length()), Box::new(term)));
+        }
+    }
+
+    /// Operands joined by `*` and `/`, at most one of which can be a length
+    fn product(&mut self) -> Option<Value> {
+        let mut product = self.operand()?;
+        loop {
+            if self.eat('*') {
+                product = match (product, self.operand()?) {
+                    (Value::Number(a), Value::Number(b)) => Value::Number(a * b),
+                    (Value::Number(factor), Value::Length(expr)) | (Value::Length(expr), Value::Number(factor)) => {
+                        Value::Length(Expr::Scale(Box::new(expr), factor))
+                    }
+                    (Value::Length(_), Value::Length(_)) => return None,
+                };
+            } else if self.eat('/') {
+                let Value::Number(divisor) = self.operand()? else {
+                    return None;
+                };
+                if divisor == 0.0 {
+                    return None;
+                }
+                product = match product {
+                    Value::Number(value) => Value::Number(value / divisor),
+                    Value::Length(expr) => Value::Length(Expr::Scale(Box::new(expr), 1.0 / divisor)),
+                };
+            } else {
+                return Some(product);
+            }
+        }
+    }
+
+    /// A number, a length, a parenthesized sum or a function
+    fn operand(&mut self) -> Option<Value> {
+        self.skip_whitespace();
+        match self.chars.peek()? {
+            '(' => {
+                self.chars.next();
+                let sum = self.sum()?;
+                self.eat(')').then_some(sum)
+            }
+            c if c.is_ascii_alphabetic() => self.function(),
+            _ => self.number(),
+        }
+    }
+
+    fn function(&mut self) -> Option<Value> {
+        let mut name = String::new();
+        while let Some(c) = self.chars.next_if(|c| c.is_ascii_alphabetic()) {
+            name.push(c);
+        }
+        if self.chars.next() != Some('(') {
+            return None;
+        }
+        let mut args = vec![self.sum()?];
+        while self.eat(',') {
+            args.push(self.sum()?);
+        }
+        if !self.eat(')') {
+            return None;
+        }
+
+        if name == "calc" {
+            return match <[Value; 1]>::try_from(args) {
+                Ok([value]) => Some(value),
+                Err(_) => None,
+            };
+        }
+        let mut exprs: Vec<Expr> = args.into_iter().map(Value::into_length).collect();
+        Some(Value::Length(match name.as_str() {
+            "min" => Expr::Min(exprs),
+            "max" => Expr::Max(exprs),
+            "clamp" if exprs.len() == 3 => {
+                let max = exprs.pop()?;
+                let value = exprs.pop()?;
+                let min = exprs.pop()?;
+                Expr::Clamp(Box::new([min, value, max]))
+            }
+            _ => return None,
+        }))
+    }
+
+    /// A signed number, followed by `px`, `%` or nothing
+    fn number(&mut self) -> Option<Value> {
+        let mut text = String::new();
+        if let Some(sign) = self.chars.next_if(|&c| c == '-' || c == '+') {
+            text.push(sign);
+        }
+        while let Some(c) = self.chars.next_if(|c| c.is_ascii_digit() || *c == '.') {
+            text.push(c);
+        }
+        let value: f32 = text.parse().ok().filter(|value: &f32| value.is_finite())?;
+        if self.chars.next_if_eq(&'%').is_some() {
+            return Some(Value::Length(Expr::Percent(value / 100.0)));
+        }
+        if self.chars.next_if_eq(&'p').is_some() {
+            return self.chars.next_if_eq(&'x').map(|_| Value::Length(Expr::Px(value)));
+        }
+        Some(Value::Number(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ids_run_out_at_max_id() {
+        assert_eq!(next_id(0), Some(1));
+        assert_eq!(next_id(MAX_ID as usize - 1), Some(MAX_ID));
+        assert_eq!(next_id(MAX_ID as usize), None);
+        // the last id still survives the round trip through the `f32` value of a length
+        assert_eq!(MAX_ID as f32 as u32, MAX_ID);
+        assert_ne!((MAX_ID + 1) as f32 as u32, MAX_ID + 1);
+    }
+
+    #[test]
+    fn equal_expressions_share_an_id() {
+        let id = parse("calc(100% - 32px)").unwrap();
+        assert_eq!(parse("calc( 100%   -  32px )"), Some(id));
+        assert_ne!(parse("calc(100% - 16px)"), Some(id));
+        assert_eq!(parse("calc(100vw - 32px)"), None);
+        assert_eq!(parse("calc(2em + 1px)"), None);
+    }
+}
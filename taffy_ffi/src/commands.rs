@@ -0,0 +1,560 @@
+//! Decoder for the command buffers accepted by `taffytree_apply_commands`.
+//!
+//! All values are little-endian. A buffer starts with the magic bytes `TFCB` and a `u32` format
+//! version ([`VERSION`]), followed by commands until the end of the buffer. Each command is a `u8`
+//! opcode followed by its operands:
+//!
+//! | opcode | command      | operands                                                |
+//! |--------|--------------|---------------------------------------------------------|
+//! | 1      | create leaf  | style                                                   |
+//! | 2      | insert child | parent node, child node, `u32` index (`u32::MAX` appends) |
+//! | 3      | remove child | parent node, child node                                 |
+//! | 4      | remove node  | node                                                    |
+//! | 5      | set style    | node, style                                             |
+//! | 6      | set property | node, `u16` property id, value                          |
+//!
+//! A node operand is a `u8` tag followed by a `u64`. Tag 0 means the `u64` is an existing node id,
+//! tag 1 means it is the index of a node created earlier in the same buffer.
+//!
+//! A style holds every `c_Style` field in declaration order, using the same encodings: `i32` for
+//! enums and flags, `f32` for numbers, `i32` dim + `f32` value for lengths, `i8` kind + `i16` value
+//! for grid indices. Each pointer + count pair is replaced by a `u32` count followed by the
+//! elements inline. A track sizing is its `i32` repetition followed by one track size when the
+//! repetition is -2 (single track), or by a `u32` count and that many track sizes otherwise.
+//!
+//! The properties that can be set individually are:
+//!
+//! | id | property        | value                          |
+//! |----|-----------------|--------------------------------|
+//! | 0  | display         | `i32`                          |
+//! | 1  | position        | `i32`                          |
+//! | 2  | overflow_x      | `i32`                          |
+//! | 3  | overflow_y      | `i32`                          |
+//! | 4  | width           | length                         |
+//! | 5  | height          | length                         |
+//! | 6  | min_width       | length                         |
+//! | 7  | min_height      | length                         |
+//! | 8  | max_width       | length                         |
+//! | 9  | max_height      | length                         |
+//! | 10 | margin          | 4 lengths (left, right, top, bottom) |
+//! | 11 | padding         | 4 lengths                      |
+//! | 12 | border          | 4 lengths                      |
+//! | 13 | inset           | 4 lengths                      |
+//! | 14 | gap             | 2 lengths (width, height)      |
+//! | 15 | flex_direction  | `i32`                          |
+//! | 16 | flex_wrap       | `i32`                          |
+//! | 17 | flex_grow       | `f32`                          |
+//! | 18 | flex_shrink     | `f32`                          |
+//! | 19 | flex_basis      | length                         |
+//! | 20 | align_items     | `i32`, -1 for none             |
+//! | 21 | align_self      | `i32`, -1 for none             |
+//! | 22 | justify_items   | `i32`, -1 for none             |
+//! | 23 | justify_self    | `i32`, -1 for none             |
+//! | 24 | align_content   | `i32`, -1 for none             |
+//! | 25 | justify_content | `i32`, -1 for none             |
+//! | 26 | aspect_ratio    | `f32`, NaN for none            |
+//! | 27 | text_align      | `i32`                          |
+//! | 28 | box_sizing      | `i32`                          |
+//!
+//! Lengths may use the relative units of `units.rs` and the expressions of `calc.rs`. A style keeps
+//! relative units relative, while a property set on its own is resolved against the node's viewport
+//! and font size right away, and also fixes any other relative units of the node's style at their
+//! current values.
+//!
+//! A display mode or property of a layout algorithm the library was built without (see
+//! `taffy_ffi_supported_features`) fails the command, as does an enum index or a length dim that is out of
+//! range for its field.
+
+use std::ops::RangeInclusive;
+
+use taffy::prelude::*;
+use taffy::style::Style;
+use taffy::Overflow;
+#[cfg(feature = "block_layout")]
+use taffy::TextAlign;
+
+use crate::{
+    c_GridIndex, c_GridPlacement, c_GridTrackSize, c_Length, c_Rect, c_Size, c_Style, is_valid_length, supports_display,
+    FfiTree, FromIndex, OwnedStyle, OwnedTrackSizing, DIMENSION, LENGTH_PERCENTAGE, LENGTH_PERCENTAGE_AUTO, MAX_ALIGN_CONTENT,
+    MAX_ALIGN_ITEMS, MAX_BOX_SIZING, MAX_DISPLAY, MAX_FLEX_DIRECTION, MAX_FLEX_WRAP, MAX_GRID_AUTO_FLOW, MAX_OVERFLOW,
+    MAX_POSITION, MAX_TEXT_ALIGN, MAX_TRACK, MIN_TRACK,
+};
+#[cfg(any(feature = "flexbox", feature = "grid"))]
+use crate::FromIndexOptional;
+use crate::units::Units;
+
+const MAGIC: &[u8; 4] = b"TFCB";
+const VERSION: u32 = 1;
+
+const CREATE_LEAF: u8 = 1;
+const INSERT_CHILD: u8 = 2;
+const REMOVE_CHILD: u8 = 3;
+const REMOVE_NODE: u8 = 4;
+const SET_STYLE: u8 = 5;
+const SET_PROPERTY: u8 = 6;
+
+/// A decoded command, whose operands were all validated except for the nodes they refer to
+enum Command {
+    CreateLeaf(OwnedStyle),
+    InsertChild(NodeRef, NodeRef, u32),
+    RemoveChild(NodeRef, NodeRef),
+    RemoveNode(NodeRef),
+    SetStyle(NodeRef, OwnedStyle),
+    /// The property id and the position of its value, which is read again against the units of the
+    /// node when it is applied
+    SetProperty(NodeRef, u16, usize),
+}
+
+#[derive(Clone, Copy)]
+enum NodeRef {
+    Existing(u64),
+    /// The index of a node created earlier in the buffer
+    Created(usize),
+}
+
+/// Applies the encoded commands in `bytes` to the tree behind the `tree` handle.
+/// See `taffytree_apply_commands` for the return value.
+pub(crate) fn apply(tree: usize, bytes: &[u8], results: &mut [u64]) -> i32 {
+    let ffi_tree = unsafe { &mut *(tree as *mut FfiTree) };
+    let commands = match decode(bytes, results.len(), ffi_tree.units(None)) {
+        Ok(commands) => commands,
+        Err(code) => {
+            ffi_tree.fail();
+            return code;
+        }
+    };
+
+    // Commands can still fail on the nodes they refer to, which the batch rolls back. Inside the
+    // host's batch, that batch fails instead.
+    let own_batch = ffi_tree.begin_batch();
+    let mut created = Vec::new();
+    for (i, command) in commands.into_iter().enumerate() {
+        if apply_command(tree, bytes, command, &mut created).is_none() {
+            let ffi_tree = unsafe { &mut *(tree as *mut FfiTree) };
+            ffi_tree.fail();
+            if own_batch {
+                ffi_tree.commit_batch();
+            }
+            return -2 - i as i32;
+        }
+    }
+
+    let ffi_tree = unsafe { &mut *(tree as *mut FfiTree) };
+    if own_batch {
+        ffi_tree.commit_batch();
+    }
+    results[..created.len()].copy_from_slice(&created);
+    created.len() as i32
+}
+
+/// Decodes the whole buffer, failing with the return value of `taffytree_apply_commands` if the header
+/// is not recognized, if a command is malformed, or if more than `capacity` nodes are created. Properties
+/// are validated with their relative lengths resolved against `units`.
+fn decode(bytes: &[u8], capacity: usize, units: Units) -> Result<Vec<Command>, i32> {
+    let mut reader = Reader { bytes, pos: 0, units: None };
+    if reader.take::<4>() != Some(*MAGIC) || reader.u32() != Some(VERSION) {
+        return Err(-1);
+    }
+
+    let mut commands = Vec::new();
+    let mut created = 0;
+    while !reader.is_empty() {
+        let command = decode_command(&mut reader, created, units).ok_or(-2 - commands.len() as i32)?;
+        if let Command::CreateLeaf(_) = command {
+            if created == capacity {
+                return Err(-2 - commands.len() as i32);
+            }
+            created += 1;
+        }
+        commands.push(command);
+    }
+    Ok(commands)
+}
+
+fn decode_command(reader: &mut Reader, created: usize, units: Units) -> Option<Command> {
+    Some(match reader.u8()? {
+        CREATE_LEAF => Command::CreateLeaf(reader.style()?),
+        INSERT_CHILD => Command::InsertChild(reader.node(created)?, reader.node(created)?, reader.u32()?),
+        REMOVE_CHILD => Command::RemoveChild(reader.node(created)?, reader.node(created)?),
+        REMOVE_NODE => Command::RemoveNode(reader.node(created)?),
+        SET_STYLE => Command::SetStyle(reader.node(created)?, reader.style()?),
+        SET_PROPERTY => {
+            let node = reader.node(created)?;
+            let property = reader.u16()?;
+            let pos = reader.pos;
+            // Properties are converted as they are read, so relative lengths must be resolved against something
+            let mut value = Reader { bytes: reader.bytes, pos, units: Some(units) };
+            set_property(&mut Style::default(), property, &mut value)?;
+            reader.pos = value.pos;
+            Command::SetProperty(node, property, pos)
+        }
+        _ => return None,
+    })
+}
+
+fn apply_command(tree: usize, bytes: &[u8], command: Command, created: &mut Vec<u64>) -> Option<()> {
+    let node = |node: NodeRef| match node {
+        NodeRef::Existing(id) => id,
+        NodeRef::Created(index) => created[index],
+    };
+    let status = match command {
+        Command::CreateLeaf(style) => {
+            let node = style.with_raw(|raw| crate::taffytree_new_leaf(tree, raw));
+            if node == 0 {
+                return None;
+            }
+            created.push(node);
+            0
+        }
+        Command::InsertChild(parent, child, index) => {
+            let (parent, child) = (node(parent), node(child));
+            match index {
+                u32::MAX => crate::taffytree_add_child(tree, parent, child),
+                index => crate::taffytree_insert_child_at_index(tree, parent, index as usize, child),
+            }
+        }
+        Command::RemoveChild(parent, child) => crate::taffytree_remove_child(tree, node(parent), node(child)),
+        Command::RemoveNode(target) => crate::taffytree_remove(tree, node(target)),
+        Command::SetStyle(target, style) => {
+            let target = node(target);
+            style.with_raw(|raw| crate::taffytree_set_style(tree, target, raw))
+        }
+        Command::SetProperty(target, property, pos) => {
+            let target = NodeId::from(node(target));
+            let ffi_tree = unsafe { &mut *(tree as *mut FfiTree) };
+            if ffi_tree.poisoned() || !ffi_tree.contains(target) {
+                return None;
+            }
+            let mut style = ffi_tree.host_style(target).ok()?.clone();
+            let mut reader = Reader { bytes, pos, units: Some(ffi_tree.units(Some(target))) };
+            set_property(&mut style, property, &mut reader)?;
+            match ffi_tree.set_host_style(target, style) {
+                Ok(_) => 0,
+                Err(_) => 1,
+            }
+        }
+    };
+    (status == 0).then_some(())
+}
+
+fn set_property(style: &mut Style, property: u16, reader: &mut Reader) -> Option<()> {
+    match property {
+        0 => style.display = Display::from_index(reader.display()?),
+        1 => style.position = Position::from_index(reader.index(MAX_POSITION)?),
+        2 => style.overflow.x = Overflow::from_index(reader.index(MAX_OVERFLOW)?),
+        3 => style.overflow.y = Overflow::from_index(reader.index(MAX_OVERFLOW)?),
+        4 => style.size.width = Dimension::from(reader.length(DIMENSION)?),
+        5 => style.size.height = Dimension::from(reader.length(DIMENSION)?),
+        6 => style.min_size.width = Dimension::from(reader.length(DIMENSION)?),
+        7 => style.min_size.height = Dimension::from(reader.length(DIMENSION)?),
+        8 => style.max_size.width = Dimension::from(reader.length(DIMENSION)?),
+        9 => style.max_size.height = Dimension::from(reader.length(DIMENSION)?),
+        10 => style.margin = Rect::from(reader.rect(LENGTH_PERCENTAGE_AUTO)?),
+        11 => style.padding = Rect::from(reader.rect(LENGTH_PERCENTAGE)?),
+        12 => style.border = Rect::from(reader.rect(LENGTH_PERCENTAGE)?),
+        13 => style.inset = Rect::from(reader.rect(LENGTH_PERCENTAGE_AUTO)?),
+        #[cfg(any(feature = "flexbox", feature = "grid"))]
+        14 => style.gap = Size::from(reader.size(LENGTH_PERCENTAGE)?),
+        #[cfg(feature = "flexbox")]
+        15 => style.flex_direction = FlexDirection::from_index(reader.index(MAX_FLEX_DIRECTION)?),
+        #[cfg(feature = "flexbox")]
+        16 => style.flex_wrap = FlexWrap::from_index(reader.index(MAX_FLEX_WRAP)?),
+        #[cfg(feature = "flexbox")]
+        17 => style.flex_grow = reader.f32()?,
+        #[cfg(feature = "flexbox")]
+        18 => style.flex_shrink = reader.f32()?,
+        #[cfg(feature = "flexbox")]
+        19 => style.flex_basis = Dimension::from(reader.length(DIMENSION)?),
+        #[cfg(any(feature = "flexbox", feature = "grid"))]
+        20 => style.align_items = reader.optional_align_items()?,
+        #[cfg(any(feature = "flexbox", feature = "grid"))]
+        21 => style.align_self = reader.optional_align_items()?,
+        #[cfg(feature = "grid")]
+        22 => style.justify_items = reader.optional_align_items()?,
+        #[cfg(feature = "grid")]
+        23 => style.justify_self = reader.optional_align_items()?,
+        #[cfg(any(feature = "flexbox", feature = "grid"))]
+        24 => style.align_content = reader.optional_align_content()?,
+        #[cfg(any(feature = "flexbox", feature = "grid"))]
+        25 => style.justify_content = reader.optional_align_content()?,
+        26 => style.aspect_ratio = Some(reader.f32()?).filter(|ratio| !ratio.is_nan()),
+        #[cfg(feature = "block_layout")]
+        27 => style.text_align = TextAlign::from_index(reader.index(MAX_TEXT_ALIGN)?),
+        28 => style.box_sizing = BoxSizing::from_index(reader.index(MAX_BOX_SIZING)?),
+        _ => return None,
+    }
+    Some(())
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    /// What relative lengths are resolved against as they are read, if they are not kept relative
+    units: Option<Units>,
+}
+
+impl Reader<'_> {
+    fn is_empty(&self) -> bool {
+        self.pos >= self.bytes.len()
+    }
+
+    fn take<const N: usize>(&mut self) -> Option<[u8; N]> {
+        let bytes = self.bytes.get(self.pos..self.pos + N)?;
+        self.pos += N;
+        bytes.try_into().ok()
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take::<1>().map(|b| b[0])
+    }
+
+    fn i8(&mut self) -> Option<i8> {
+        self.take().map(i8::from_le_bytes)
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        self.take().map(u16::from_le_bytes)
+    }
+
+    fn i16(&mut self) -> Option<i16> {
+        self.take().map(i16::from_le_bytes)
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.take().map(u32::from_le_bytes)
+    }
+
+    fn i32(&mut self) -> Option<i32> {
+        self.take().map(i32::from_le_bytes)
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        self.take().map(u64::from_le_bytes)
+    }
+
+    fn f32(&mut self) -> Option<f32> {
+        self.take().map(f32::from_le_bytes)
+    }
+
+    /// Reads an element count, refusing counts that can't possibly fit in the rest of the buffer
+    fn count(&mut self, min_element_size: usize) -> Option<usize> {
+        let count = self.u32()? as usize;
+        let needed = count.checked_mul(min_element_size)?;
+        (needed <= self.bytes.len() - self.pos).then_some(count)
+    }
+
+    /// Reads an enum index in `0..=max`
+    fn index(&mut self, max: i32) -> Option<i32> {
+        self.i32().filter(|index| (0..=max).contains(index))
+    }
+
+    fn flag(&mut self) -> Option<i32> {
+        self.i32().filter(|flag| *flag == 0 || *flag == 1)
+    }
+
+    /// Reads a node operand, `created` being the number of nodes created by the buffer so far
+    fn node(&mut self, created: usize) -> Option<NodeRef> {
+        match self.u8()? {
+            0 => self.u64().map(NodeRef::Existing),
+            1 => usize::try_from(self.u64()?).ok().filter(|&index| index < created).map(NodeRef::Created),
+            _ => None,
+        }
+    }
+
+    fn length(&mut self, dims: RangeInclusive<i32>) -> Option<c_Length> {
+        let length = c_Length { dim: self.i32()?, value: self.f32()? };
+        if !is_valid_length(length, &dims) {
+            return None;
+        }
+        Some(match &self.units {
+            Some(units) => units.resolve(length),
+            None => length,
+        })
+    }
+
+    fn size(&mut self, dims: RangeInclusive<i32>) -> Option<c_Size> {
+        Some(c_Size {
+            width: self.length(dims.clone())?,
+            height: self.length(dims)?,
+        })
+    }
+
+    fn rect(&mut self, dims: RangeInclusive<i32>) -> Option<c_Rect> {
+        Some(c_Rect {
+            left: self.length(dims.clone())?,
+            right: self.length(dims.clone())?,
+            top: self.length(dims.clone())?,
+            bottom: self.length(dims)?,
+        })
+    }
+
+    fn grid_index(&mut self) -> Option<c_GridIndex> {
+        let kind = self.i8().filter(|kind| (0..=2).contains(kind))?;
+        let value = self.i16()?;
+        if kind == 2 && value < 0 {
+            return None;
+        }
+        Some(c_GridIndex { kind, value })
+    }
+
+    fn grid_placement(&mut self) -> Option<c_GridPlacement> {
+        Some(c_GridPlacement {
+            start: self.grid_index()?,
+            end: self.grid_index()?,
+        })
+    }
+
+    fn track_size(&mut self) -> Option<c_GridTrackSize> {
+        Some(c_GridTrackSize {
+            min_size: self.length(MIN_TRACK)?,
+            max_size: self.length(MAX_TRACK)?,
+        })
+    }
+
+    fn track_sizes(&mut self) -> Option<Vec<c_GridTrackSize>> {
+        let count = self.count(16)?;
+        (0..count).map(|_| self.track_size()).collect()
+    }
+
+    fn track_sizings(&mut self) -> Option<Vec<OwnedTrackSizing>> {
+        let count = self.count(4)?;
+        (0..count)
+            .map(|_| match self.i32()? {
+                -2 => Some(OwnedTrackSizing::Single(self.track_size()?)),
+                repetition if (-1..=u16::MAX as i32).contains(&repetition) => {
+                    Some(OwnedTrackSizing::Repeat(repetition, self.track_sizes()?))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[cfg(any(feature = "flexbox", feature = "grid"))]
+    /// Reads an optional alignment encoded as an index, or -1 for none
+    fn optional_align(&mut self, max: i32) -> Option<(i32, i32)> {
+        match self.i32()? {
+            -1 => Some((0, 0)),
+            index if (0..=max).contains(&index) => Some((index, 1)),
+            _ => None,
+        }
+    }
+
+    #[cfg(any(feature = "flexbox", feature = "grid"))]
+    fn optional_align_items(&mut self) -> Option<Option<AlignItems>> {
+        let (index, has) = self.optional_align(MAX_ALIGN_ITEMS)?;
+        Some(AlignItems::from_index(index, has))
+    }
+
+    #[cfg(any(feature = "flexbox", feature = "grid"))]
+    fn optional_align_content(&mut self) -> Option<Option<AlignContent>> {
+        let (index, has) = self.optional_align(MAX_ALIGN_CONTENT)?;
+        Some(AlignContent::from_index(index, has))
+    }
+
+    /// Reads a display index, rejecting display modes this build was compiled without
+    fn display(&mut self) -> Option<i32> {
+        self.index(MAX_DISPLAY).filter(|&display| supports_display(display))
+    }
+
+    fn style(&mut self) -> Option<OwnedStyle> {
+        let display = self.display()?;
+        let item_is_table = self.flag()?;
+        let item_is_replaced = self.flag()?;
+        let box_sizing = self.index(MAX_BOX_SIZING)?;
+        let overflow_x = self.index(MAX_OVERFLOW)?;
+        let overflow_y = self.index(MAX_OVERFLOW)?;
+        let scrollbar_width = self.f32()?;
+        let position = self.index(MAX_POSITION)?;
+        let inset = self.rect(LENGTH_PERCENTAGE_AUTO)?;
+        let gap = self.size(LENGTH_PERCENTAGE)?;
+        let text_align = self.index(MAX_TEXT_ALIGN)?;
+        let margin = self.rect(LENGTH_PERCENTAGE_AUTO)?;
+        let border = self.rect(LENGTH_PERCENTAGE)?;
+        let padding = self.rect(LENGTH_PERCENTAGE)?;
+        let size = self.size(DIMENSION)?;
+        let min_size = self.size(DIMENSION)?;
+        let max_size = self.size(DIMENSION)?;
+        let flex_wrap = self.index(MAX_FLEX_WRAP)?;
+        let flex_direction = self.index(MAX_FLEX_DIRECTION)?;
+        let flex_grow = self.f32()?;
+        let flex_shrink = self.f32()?;
+        let flex_basis = self.length(DIMENSION)?;
+        let grid_template_rows = self.track_sizings()?;
+        let grid_template_columns = self.track_sizings()?;
+        let grid_auto_rows = self.track_sizes()?;
+        let grid_auto_columns = self.track_sizes()?;
+        let grid_auto_flow = self.index(MAX_GRID_AUTO_FLOW)?;
+        let grid_row = self.grid_placement()?;
+        let grid_column = self.grid_placement()?;
+        let aspect_ratio = self.f32()?;
+        let has_aspect_ratio = self.flag()?;
+        let align_items = self.index(MAX_ALIGN_ITEMS)?;
+        let has_align_items = self.flag()?;
+        let justify_items = self.index(MAX_ALIGN_ITEMS)?;
+        let has_justify_items = self.flag()?;
+        let align_self = self.index(MAX_ALIGN_ITEMS)?;
+        let has_align_self = self.flag()?;
+        let justify_self = self.index(MAX_ALIGN_ITEMS)?;
+        let has_justify_self = self.flag()?;
+        let align_content = self.index(MAX_ALIGN_CONTENT)?;
+        let has_align_content = self.flag()?;
+        let justify_content = self.index(MAX_ALIGN_CONTENT)?;
+        let has_justify_content = self.flag()?;
+
+        Some(OwnedStyle {
+            raw: c_Style {
+                display,
+                item_is_table,
+                item_is_replaced,
+                box_sizing,
+                overflow_x,
+                overflow_y,
+                scrollbar_width,
+                position,
+                inset,
+                gap,
+                text_align,
+                margin,
+                border,
+                padding,
+                size,
+                min_size,
+                max_size,
+                flex_wrap,
+                flex_direction,
+                flex_grow,
+                flex_shrink,
+                flex_basis,
+                grid_template_rows: std::ptr::null(),
+                grid_template_rows_count: 0,
+                grid_template_columns: std::ptr::null(),
+                grid_template_columns_count: 0,
+                grid_auto_rows: std::ptr::null(),
+                grid_auto_rows_count: 0,
+                grid_auto_columns: std::ptr::null(),
+                grid_auto_columns_count: 0,
+                grid_auto_flow,
+                grid_row,
+                grid_column,
+                aspect_ratio,
+                has_aspect_ratio,
+                align_items,
+                has_align_items,
+                justify_items,
+                has_justify_items,
+                align_self,
+                has_align_self,
+                justify_self,
+                has_justify_self,
+                align_content,
+                has_align_content,
+                justify_content,
+                has_justify_content,
+            },
+            grid_template_rows,
+            grid_template_columns,
+            grid_auto_rows,
+            grid_auto_columns,
+        })
+    }
+}
@@ -0,0 +1,34 @@
+//! The log of a layout pass, passed to the logger callback of a tree.
+//!
+//! The layout driver logs each node it starts laying out and how it was laid out, and warns about likely
+//! mistakes in the styles. The lines are kept until the pass is over and then passed to the logger on the
+//! thread that ran it, so the logger is free to use the tree or lay out another one.
+
+use taffy::NodeId;
+
+use crate::Logger;
+
+/// The inputs a node is about to be laid out with
+pub(crate) const TRACE: i32 = 0;
+/// A summary of a node taffy_ffi laid out
+pub(crate) const INFO: i32 = 1;
+/// Something that is likely to be a mistake in the styles
+pub(crate) const WARNING: i32 = 2;
+
+#[derive(Default)]
+pub(crate) struct Log {
+    lines: Vec<(i32, NodeId, String)>,
+}
+
+impl Log {
+    pub(crate) fn message(&mut self, severity: i32, node: NodeId, args: std::fmt::Arguments) {
+        self.lines.push((severity, node, args.to_string()));
+    }
+
+    /// Passes every line to `logger`, in the order they were logged
+    pub(crate) fn finish(self, logger: Logger) {
+        for (severity, node, text) in self.lines {
+            (logger.callback)(logger.user_data, severity, node.into(), text.as_ptr(), text.len());
+        }
+    }
+}
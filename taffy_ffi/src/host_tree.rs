@@ -0,0 +1,319 @@
+//! Layout over a tree owned by the host, reached through the callbacks in `c_HostTreeCallbacks`.
+//!
+//! The host keeps its hierarchy, styles and final layouts, taffy_ffi only keeps what taffy needs between
+//! passes: the layout cache and the unrounded layout of each node.
+//!
+//! Styles are the handles the host returns from its `style` callback, which are trusted to be live handles
+//! from `taffy_style_create` without any check. They are used as converted by `taffy_style_create`, since
+//! a host tree has no viewport or font sizes: viewport units are 0 and font units use the default 16.
+
+use std::collections::HashMap;
+
+use taffy::prelude::*;
+use taffy::style::Style;
+use taffy::{
+    compute_cached_layout, compute_hidden_layout, compute_leaf_layout, compute_root_layout, round_layout, Cache,
+    CacheTree, LayoutInput, LayoutOutput, LayoutPartialTree, RoundTree, RunMode, TraversePartialTree, TraverseTree,
+};
+#[cfg(feature = "block_layout")]
+use taffy::{compute_block_layout, LayoutBlockContainer};
+#[cfg(feature = "flexbox")]
+use taffy::{compute_flexbox_layout, LayoutFlexboxContainer};
+#[cfg(feature = "grid")]
+use taffy::{compute_grid_layout, LayoutGridContainer};
+
+use crate::layout::{measured_baseline, measurement};
+use crate::{c_AvailableSpace, c_HostTreeCallbacks, c_Layout, c_MeasureOutput, StyleHandle};
+
+static EMPTY_LAYOUT: Layout = Layout::new();
+
+pub(crate) struct HostTree {
+    user_data: usize,
+    child_count: extern "C" fn(usize, u64) -> usize,
+    child_at: extern "C" fn(usize, u64, usize) -> u64,
+    parent: Option<extern "C" fn(usize, u64) -> u64>,
+    style: extern "C" fn(usize, u64) -> usize,
+    set_layout: extern "C" fn(usize, u64, *const c_Layout),
+    measure: Option<extern "C" fn(usize, u64, f32, f32, c_AvailableSpace, *mut c_MeasureOutput)>,
+    pub(crate) use_rounding: bool,
+    /// Used for nodes the host has no style handle for
+    default_style: Style,
+    nodes: HashMap<NodeId, HostNode>,
+}
+
+struct HostNode {
+    cache: Cache,
+    unrounded_layout: Layout,
+}
+
+impl HostTree {
+    pub(crate) fn new(callbacks: c_HostTreeCallbacks) -> Option<HostTree> {
+        Some(HostTree {
+            user_data: callbacks.user_data,
+            child_count: callbacks.child_count?,
+            child_at: callbacks.child_at?,
+            parent: callbacks.parent,
+            style: callbacks.style?,
+            set_layout: callbacks.set_layout?,
+            measure: callbacks.measure,
+            use_rounding: true,
+            default_style: Style::DEFAULT,
+            nodes: HashMap::new(),
+        })
+    }
+
+    fn node_mut(&mut self, node: NodeId) -> &mut HostNode {
+        self.nodes.entry(node).or_insert_with(|| HostNode { cache: Cache::new(), unrounded_layout: Layout::new() })
+    }
+
+    pub(crate) fn compute_layout(&mut self, root: NodeId, available_space: Size<AvailableSpace>) {
+        compute_root_layout(self, root, available_space);
+        if self.use_rounding {
+            round_layout(self, root);
+        } else {
+            let mut stack = vec![root];
+            while let Some(node) = stack.pop() {
+                let layout = c_Layout::from(*self.get_unrounded_layout(node));
+                (self.set_layout)(self.user_data, node.into(), &layout);
+                stack.extend(self.child_ids(node));
+            }
+        }
+    }
+
+    pub(crate) fn mark_dirty(&mut self, node: NodeId) {
+        let mut current = Some(node);
+        while let Some(node) = current {
+            if let Some(data) = self.nodes.get_mut(&node) {
+                data.cache.clear();
+            }
+            current = self
+                .parent
+                .map(|parent| parent(self.user_data, node.into()))
+                .filter(|&parent| parent != 0)
+                .map(NodeId::from);
+        }
+    }
+
+    pub(crate) fn remove_node(&mut self, node: NodeId) {
+        self.nodes.remove(&node);
+    }
+}
+
+pub(crate) struct HostChildIter<'a> {
+    tree: &'a HostTree,
+    parent: NodeId,
+    index: usize,
+    count: usize,
+}
+
+impl Iterator for HostChildIter<'_> {
+    type Item = NodeId;
+
+    fn next(&mut self) -> Option<NodeId> {
+        if self.index >= self.count {
+            return None;
+        }
+        self.index += 1;
+        Some(self.tree.get_child_id(self.parent, self.index - 1))
+    }
+}
+
+impl TraversePartialTree for HostTree {
+    type ChildIter<'a> = HostChildIter<'a>;
+
+    fn child_ids(&self, parent: NodeId) -> Self::ChildIter<'_> {
+        HostChildIter { tree: self, parent, index: 0, count: self.child_count(parent) }
+    }
+
+    fn child_count(&self, parent: NodeId) -> usize {
+        (self.child_count)(self.user_data, parent.into())
+    }
+
+    fn get_child_id(&self, parent: NodeId, index: usize) -> NodeId {
+        NodeId::from((self.child_at)(self.user_data, parent.into(), index))
+    }
+}
+
+impl TraverseTree for HostTree {}
+
+impl HostTree {
+    fn style(&self, node: NodeId) -> &Style {
+        match (self.style)(self.user_data, node.into()) {
+            0 => &self.default_style,
+            handle => unsafe { &(*(handle as *const StyleHandle)).style.style },
+        }
+    }
+}
+
+impl LayoutPartialTree for HostTree {
+    type CoreContainerStyle<'a>
+        = &'a Style
+    where
+        Self: 'a;
+
+    fn get_core_container_style(&self, node: NodeId) -> Self::CoreContainerStyle<'_> {
+        self.style(node)
+    }
+
+    #[cfg(feature = "calc")]
+    fn resolve_calc_value(&self, calc: *const (), basis: f32) -> f32 {
+        crate::calc::resolve(calc, basis)
+    }
+
+    fn set_unrounded_layout(&mut self, node: NodeId, layout: &Layout) {
+        self.node_mut(node).unrounded_layout = *layout;
+    }
+
+    fn compute_child_layout(&mut self, node: NodeId, inputs: LayoutInput) -> LayoutOutput {
+        if inputs.run_mode == RunMode::PerformHiddenLayout {
+            return compute_hidden_layout(self, node);
+        }
+
+        compute_cached_layout(self, node, inputs, |tree, node, inputs| {
+            let display = tree.style(node).display;
+            if display == Display::None {
+                return compute_hidden_layout(tree, node);
+            }
+            let has_children = tree.child_count(node) > 0;
+            match (display, has_children) {
+                #[cfg(feature = "block_layout")]
+                (Display::Block, true) => compute_block_layout(tree, node, inputs),
+                #[cfg(feature = "flexbox")]
+                (Display::Flex, true) => compute_flexbox_layout(tree, node, inputs),
+                #[cfg(feature = "grid")]
+                (Display::Grid, true) => compute_grid_layout(tree, node, inputs),
+                _ => tree.compute_leaf(node, inputs),
+            }
+        })
+    }
+}
+
+impl HostTree {
+    /// Lays out a leaf, sizing its content with the `measure` callback if the host has one
+    fn compute_leaf(&self, node: NodeId, inputs: LayoutInput) -> LayoutOutput {
+        let mut baseline = None;
+        let style = self.style(node);
+        let mut output = compute_leaf_layout(inputs, style, |_, _| 0.0, |known_dimensions, available_space| {
+            let Some(measure) = self.measure else {
+                return Size::ZERO;
+            };
+            let mut output = c_MeasureOutput { width: 0.0, height: 0.0, first_baseline: f32::NAN };
+            measure(
+                self.user_data,
+                node.into(),
+                known_dimensions.width.unwrap_or(f32::NAN),
+                known_dimensions.height.unwrap_or(f32::NAN),
+                c_AvailableSpace::from(available_space),
+                &mut output,
+            );
+            let (size, first_baseline) = measurement(output);
+            baseline = first_baseline;
+            size
+        });
+        measured_baseline(&mut output, style, &inputs, baseline);
+        output
+    }
+}
+
+impl CacheTree for HostTree {
+    fn cache_get(
+        &self,
+        node: NodeId,
+        known_dimensions: Size<Option<f32>>,
+        available_space: Size<AvailableSpace>,
+        run_mode: RunMode,
+    ) -> Option<LayoutOutput> {
+        self.nodes.get(&node)?.cache.get(known_dimensions, available_space, run_mode)
+    }
+
+    fn cache_store(
+        &mut self,
+        node: NodeId,
+        known_dimensions: Size<Option<f32>>,
+        available_space: Size<AvailableSpace>,
+        run_mode: RunMode,
+        layout_output: LayoutOutput,
+    ) {
+        self.node_mut(node).cache.store(known_dimensions, available_space, run_mode, layout_output);
+    }
+
+    fn cache_clear(&mut self, node: NodeId) {
+        if let Some(data) = self.nodes.get_mut(&node) {
+            data.cache.clear();
+        }
+    }
+}
+
+#[cfg(feature = "flexbox")]
+impl LayoutFlexboxContainer for HostTree {
+    type FlexboxContainerStyle<'a>
+        = &'a Style
+    where
+        Self: 'a;
+
+    type FlexboxItemStyle<'a>
+        = &'a Style
+    where
+        Self: 'a;
+
+    fn get_flexbox_container_style(&self, node: NodeId) -> Self::FlexboxContainerStyle<'_> {
+        self.style(node)
+    }
+
+    fn get_flexbox_child_style(&self, child: NodeId) -> Self::FlexboxItemStyle<'_> {
+        self.style(child)
+    }
+}
+
+#[cfg(feature = "grid")]
+impl LayoutGridContainer for HostTree {
+    type GridContainerStyle<'a>
+        = &'a Style
+    where
+        Self: 'a;
+
+    type GridItemStyle<'a>
+        = &'a Style
+    where
+        Self: 'a;
+
+    fn get_grid_container_style(&self, node: NodeId) -> Self::GridContainerStyle<'_> {
+        self.style(node)
+    }
+
+    fn get_grid_child_style(&self, child: NodeId) -> Self::GridItemStyle<'_> {
+        self.style(child)
+    }
+}
+
+#[cfg(feature = "block_layout")]
+impl LayoutBlockContainer for HostTree {
+    type BlockContainerStyle<'a>
+        = &'a Style
+    where
+        Self: 'a;
+
+    type BlockItemStyle<'a>
+        = &'a Style
+    where
+        Self: 'a;
+
+    fn get_block_container_style(&self, node: NodeId) -> Self::BlockContainerStyle<'_> {
+        self.style(node)
+    }
+
+    fn get_block_child_style(&self, child: NodeId) -> Self::BlockItemStyle<'_> {
+        self.style(child)
+    }
+}
+
+impl RoundTree for HostTree {
+    fn get_unrounded_layout(&self, node: NodeId) -> &Layout {
+        self.nodes.get(&node).map_or(&EMPTY_LAYOUT, |data| &data.unrounded_layout)
+    }
+
+    fn set_final_layout(&mut self, node: NodeId, layout: &Layout) {
+        let layout = c_Layout::from(*layout);
+        (self.set_layout)(self.user_data, node.into(), &layout);
+    }
+}
@@ -0,0 +1,778 @@
+//! The layout pass over an `FfiTree`.
+//!
+//! taffy's own `TaffyTree::compute_layout` can only run its built-in algorithms, so taffy_ffi drives
+//! the low-level API itself through `FfiView`. Caches stay in the `TaffyTree`, layouts are kept in
+//! the `NodeData` of each node.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use taffy::prelude::*;
+use taffy::style::{Overflow, Style};
+use taffy::{
+    compute_cached_layout, compute_hidden_layout, compute_leaf_layout, compute_root_layout, round_layout, CacheTree,
+    LayoutInput, LayoutOutput, LayoutPartialTree, RequestedAxis, ResolveOrZero, RoundTree, RunMode, SizingMode, TraversePartialTree,
+    TraverseTree,
+};
+#[cfg(feature = "block_layout")]
+use taffy::{compute_block_layout, LayoutBlockContainer};
+#[cfg(feature = "flexbox")]
+use taffy::{compute_flexbox_layout, LayoutFlexboxContainer};
+#[cfg(feature = "grid")]
+use taffy::{compute_grid_layout, LayoutGridContainer};
+
+use crate::{
+    c_AvailableSpace, c_LayoutInput, c_LayoutOutput, c_LayoutStats, c_MeasureOutput, c_MeasureQuery, c_NodeStats, CustomLayoutCallback,
+    FfiTree,
+};
+
+static EMPTY_LAYOUT: Layout = Layout::new();
+
+/// A measured content size and first baseline
+type Measurement = (Size<f32>, Option<f32>);
+
+pub(crate) struct FfiView<'a> {
+    tree: &'a mut FfiTree,
+    /// Answers to the measurements predicted before the pass
+    predicted: HashMap<MeasureKey, Measurement>,
+    /// The measurements asked for during the pass, by node
+    queries: HashMap<NodeId, Vec<c_MeasureQuery>>,
+    stats: c_LayoutStats,
+    node_stats: HashMap<NodeId, c_NodeStats>,
+    /// The time spent laying out the children of the node being laid out so far
+    child_time: Duration,
+    /// The algorithm that ran for the last cache miss, taken once the miss is counted
+    algorithm: Option<Algorithm>,
+    /// The node the custom layout callback is laying out, the only one whose children it may lay out and place
+    custom_node: Option<NodeId>,
+    /// The lines for the logger, if the tree has one
+    #[cfg(feature = "debug")]
+    log: Option<crate::debug::Log>,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Algorithm {
+    Hidden,
+    #[cfg(feature = "flexbox")]
+    Flexbox,
+    #[cfg(feature = "grid")]
+    Grid,
+    #[cfg(feature = "block_layout")]
+    Block,
+    Leaf,
+    Custom,
+}
+
+/// What the layout of a root depends on besides the styles of its subtree
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) struct RootPass {
+    available_space: Size<AvailableSpace>,
+    use_rounding: bool,
+    viewport: Size<f32>,
+    root_font_size: f32,
+}
+
+impl RootPass {
+    fn new(tree: &FfiTree, available_space: Size<AvailableSpace>) -> Self {
+        RootPass { available_space, use_rounding: tree.use_rounding, viewport: tree.viewport, root_font_size: tree.root_font_size }
+    }
+}
+
+pub(crate) fn compute_layout(tree: &mut FfiTree, root: NodeId, available_space: Size<AvailableSpace>) {
+    run_pass(tree, root, |view| compute_root_layout(view, root, available_space));
+    let pass = RootPass::new(tree, available_space);
+    if let Some(data) = tree.nodes.get_mut(&root) {
+        data.root_pass = Some(pass);
+    }
+}
+
+/// Whether `root` is dirty, or its layout is not from a pass over it in `available_space` with the current
+/// rounding, viewport and root font size of the tree
+pub(crate) fn needs_layout(tree: &FfiTree, root: NodeId, available_space: Size<AvailableSpace>) -> bool {
+    tree.taffy.dirty(root).unwrap_or(true)
+        || tree.nodes.get(&root).and_then(|data| data.root_pass) != Some(RootPass::new(tree, available_space))
+}
+
+/// Lays out `root` at its fit-content size within `max_size`, clamped between `min_size` and `max_size`
+/// (which can be infinite), and returns that size. Percentages of the root resolve against the finite
+/// axes of `max_size`.
+pub(crate) fn compute_layout_fit(tree: &mut FfiTree, root: NodeId, min_size: Size<f32>, max_size: Size<f32>) -> Size<f32> {
+    let size = run_pass(tree, root, |view| view.compute_fitted_root(root, min_size, max_size));
+    // The layout is no longer the one of a pass `taffytree_compute_layouts` can compare against
+    if let Some(data) = tree.nodes.get_mut(&root) {
+        data.root_pass = None;
+    }
+    size
+}
+
+/// Runs `root_layout` over the tree, with the measurement, stats and rounding of a layout pass
+fn run_pass<R>(tree: &mut FfiTree, root: NodeId, root_layout: impl FnOnce(&mut FfiView) -> R) -> R {
+    #[cfg(feature = "debug")]
+    let logger = tree.logger;
+
+    let start = Instant::now();
+    let mut stats = c_LayoutStats::default();
+    let predicted = predict_measurements(tree, root);
+    if !predicted.is_empty() {
+        stats.measure_calls += 1;
+    }
+    let mut view = FfiView {
+        tree,
+        predicted,
+        queries: HashMap::new(),
+        stats,
+        node_stats: HashMap::new(),
+        child_time: Duration::ZERO,
+        algorithm: None,
+        custom_node: None,
+        #[cfg(feature = "debug")]
+        log: logger.map(|_| crate::debug::Log::default()),
+    };
+    let result = root_layout(&mut view);
+    for (node, queries) in std::mem::take(&mut view.queries) {
+        if let Some(data) = view.tree.nodes.get_mut(&node) {
+            data.measure_queries = queries;
+        }
+    }
+    view.stats.nodes_visited = view.node_stats.len() as u64;
+    view.stats.total_ms = start.elapsed().as_secs_f64() * 1000.0;
+    view.tree.stats = view.stats;
+    view.tree.node_stats = std::mem::take(&mut view.node_stats);
+    if view.tree.use_rounding {
+        round_layout(&mut view, root);
+    } else {
+        let mut stack = vec![root];
+        while let Some(node) = stack.pop() {
+            if let Some(data) = view.tree.nodes.get_mut(&node) {
+                data.layout = data.unrounded_layout;
+            }
+            stack.extend(view.tree.taffy.child_ids(node));
+        }
+    }
+
+    #[cfg(feature = "debug")]
+    if let (Some(log), Some(logger)) = (view.log.take(), logger) {
+        log.finish(logger);
+    }
+    result
+}
+
+impl FfiView<'_> {
+    fn style(&self, node: NodeId) -> &Style {
+        self.tree.taffy.style(node).expect("nodes reached by layout are valid")
+    }
+
+    /// `compute_root_layout` at a size measured from the content: the width first, then the height at
+    /// that width, so that text wraps at the clamped width
+    fn compute_fitted_root(&mut self, root: NodeId, min_size: Size<f32>, max_size: Size<f32>) -> Size<f32> {
+        let parent_size = max_size.map(|max| max.is_finite().then_some(max));
+        let available_space = parent_size.map(|max| max.map_or(AvailableSpace::MaxContent, AvailableSpace::Definite));
+        let mut inputs = LayoutInput {
+            run_mode: RunMode::ComputeSize,
+            sizing_mode: SizingMode::InherentSize,
+            axis: RequestedAxis::Both,
+            known_dimensions: Size::NONE,
+            parent_size,
+            available_space,
+            vertical_margins_are_collapsible: Line::FALSE,
+        };
+        let width = self.compute_child_layout(root, inputs).size.width.clamp(min_size.width, max_size.width);
+        inputs.known_dimensions.width = Some(width);
+        let height = self.compute_child_layout(root, inputs).size.height.clamp(min_size.height, max_size.height);
+        let size = Size { width, height };
+
+        inputs.run_mode = RunMode::PerformLayout;
+        inputs.known_dimensions = size.map(Some);
+        let output = self.compute_child_layout(root, inputs);
+
+        let style = self.style(root);
+        let calc = |val, basis| self.resolve_calc_value(val, basis);
+        let scrollbar_size = Size {
+            width: if style.overflow.y == Overflow::Scroll { style.scrollbar_width } else { 0.0 },
+            height: if style.overflow.x == Overflow::Scroll { style.scrollbar_width } else { 0.0 },
+        };
+        let layout = Layout {
+            order: 0,
+            location: taffy::Point::ZERO,
+            size: output.size,
+            content_size: output.content_size,
+            scrollbar_size,
+            padding: style.padding.resolve_or_zero(parent_size.width, calc),
+            border: style.border.resolve_or_zero(parent_size.width, calc),
+            margin: style.margin.resolve_or_zero(parent_size.width, calc),
+        };
+        self.set_unrounded_layout(root, &layout);
+        size
+    }
+
+    fn run_custom_layout(&mut self, custom: CustomLayoutCallback, node: NodeId, inputs: LayoutInput) -> LayoutOutput {
+        let c_inputs = c_LayoutInput::from(inputs);
+        let mut output = c_LayoutOutput::from(LayoutOutput::HIDDEN);
+        let outer_custom_node = self.custom_node.replace(node);
+        (custom.callback)(custom.user_data, self as *mut FfiView as usize, node.into(), &c_inputs, &mut output);
+        self.custom_node = outer_custom_node;
+        LayoutOutput::from(output)
+    }
+
+    fn compute_leaf(&mut self, node: NodeId, inputs: LayoutInput) -> LayoutOutput {
+        let FfiView { tree, predicted, queries, stats, node_stats, .. } = self;
+        let tree: &FfiTree = tree;
+        let data = tree.nodes.get(&node);
+        let text = data.and_then(|data| data.text.as_deref());
+        let measured =
+            data.is_some_and(|data| data.measured) && (tree.measure_callback.is_some() || tree.batch_measure_callback.is_some());
+
+        let mut baseline = None;
+        let style = tree.taffy.style(node).expect("nodes reached by layout are valid");
+        let mut output = compute_leaf_layout(inputs, style, |_, _| 0.0, |known_dimensions, available_space| {
+            let (size, first_baseline) = match (text, measured) {
+                (Some(text), _) => text.measure(known_dimensions, available_space),
+                (None, true) => {
+                    let query = c_MeasureQuery {
+                        node: node.into(),
+                        known_width: known_dimensions.width.unwrap_or(f32::NAN),
+                        known_height: known_dimensions.height.unwrap_or(f32::NAN),
+                        available_space: c_AvailableSpace::from(available_space),
+                    };
+                    queries.entry(node).or_default().push(query);
+                    stats.measurements += 1;
+                    node_stats.entry(node).or_default().measurements += 1;
+                    match predicted.get(&MeasureKey::new(&query)) {
+                        Some(measurement) => *measurement,
+                        None => {
+                            stats.measure_calls += 1;
+                            measure_one(tree, query)
+                        }
+                    }
+                }
+                (None, false) => (Size::ZERO, None),
+            };
+            baseline = first_baseline;
+            size
+        });
+
+        measured_baseline(&mut output, style, &inputs, baseline);
+        output
+    }
+
+    /// Logs how `inputs` were laid out, and warns about an axis collapsed to nothing despite its style or content
+    #[cfg(feature = "debug")]
+    fn log_output(&mut self, node: NodeId, algorithm: Option<Algorithm>, inputs: &LayoutInput, output: &LayoutOutput) {
+        use crate::debug::{INFO, WARNING};
+        use taffy::MaybeResolve;
+
+        let Some(log) = &mut self.log else {
+            return;
+        };
+        let Some(algorithm) = algorithm else {
+            log.message(INFO, node, format_args!("cache hit for {:?}: {} x {}", inputs.run_mode, output.size.width, output.size.height));
+            return;
+        };
+        log.message(
+            INFO,
+            node,
+            format_args!(
+                "{:?} {:?} known {:?} available {:?}: {} x {}, content {} x {}",
+                algorithm,
+                inputs.run_mode,
+                inputs.known_dimensions,
+                inputs.available_space,
+                output.size.width,
+                output.size.height,
+                output.content_size.width,
+                output.content_size.height
+            ),
+        );
+        if inputs.run_mode == RunMode::PerformLayout {
+            let style = self.tree.taffy.style(node).expect("nodes reached by layout are valid");
+            let styled: Size<Option<f32>> = style.size.maybe_resolve(inputs.parent_size, |_, _| 0.0);
+            for (axis, size, styled, content) in [
+                ("wide", output.size.width, styled.width, output.content_size.width),
+                ("tall", output.size.height, styled.height, output.content_size.height),
+            ] {
+                if size != 0.0 {
+                    continue;
+                }
+                if let Some(styled) = styled.filter(|styled| *styled > 0.0) {
+                    log.message(WARNING, node, format_args!("laid out 0 px {axis} although its size is {styled} px"));
+                } else if content > 0.0 {
+                    log.message(WARNING, node, format_args!("laid out 0 px {axis} but its content needs {content} px"));
+                }
+            }
+        }
+    }
+
+    /// `child_count` for ids coming from the host, 0 if `node` is not valid
+    pub(crate) fn child_count_checked(&self, node: NodeId) -> usize {
+        if self.tree.contains(node) { self.child_count(node) } else { 0 }
+    }
+
+    pub(crate) fn child_at_checked(&self, node: NodeId, index: usize) -> Option<NodeId> {
+        (index < self.child_count_checked(node)).then(|| self.get_child_id(node, index))
+    }
+
+    /// Whether `child` is a child of the node being laid out by the custom layout callback
+    fn is_custom_child(&self, child: NodeId) -> bool {
+        self.tree.contains(child) && self.custom_node.is_some_and(|node| self.tree.taffy.parent(child) == Some(node))
+    }
+
+    pub(crate) fn compute_child_checked(&mut self, child: NodeId, inputs: LayoutInput) -> Option<LayoutOutput> {
+        self.is_custom_child(child).then(|| self.compute_child_layout(child, inputs))
+    }
+
+    pub(crate) fn set_layout_checked(&mut self, child: NodeId, layout: Layout) -> bool {
+        let valid = self.is_custom_child(child);
+        if valid {
+            self.set_unrounded_layout(child, &layout);
+        }
+        valid
+    }
+}
+
+/// Identifies a measurement by the bits of its query
+#[derive(PartialEq, Eq, Hash)]
+struct MeasureKey([u32; 8]);
+
+impl MeasureKey {
+    fn new(query: &c_MeasureQuery) -> Self {
+        let bits = |value: f32| if value.is_nan() { f32::NAN.to_bits() } else { value.to_bits() };
+        let node = query.node;
+        let available = query.available_space;
+        MeasureKey([
+            node as u32,
+            (node >> 32) as u32,
+            bits(query.known_width),
+            bits(query.known_height),
+            available.width.dim as u32,
+            bits(available.width.value),
+            available.height.dim as u32,
+            bits(available.height.value),
+        ])
+    }
+}
+
+/// Asks the host once for the measurements the pass over `root` is expected to need: for every dirty measured
+/// leaf, the ones it needed in its last pass, or its min-content and max-content sizes if it has not been laid out
+fn predict_measurements(tree: &FfiTree, root: NodeId) -> HashMap<MeasureKey, Measurement> {
+    let Some(batch) = tree.batch_measure_callback else {
+        return HashMap::new();
+    };
+
+    let mut queries = Vec::new();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        if tree.taffy.style(node).is_ok_and(|style| style.display == Display::None) {
+            continue;
+        }
+        let children = tree.taffy.children(node).unwrap_or_default();
+        if !children.is_empty() {
+            stack.extend(children.into_iter().rev());
+            continue;
+        }
+        let Some(data) = tree.nodes.get(&node).filter(|data| data.measured && data.text.is_none()) else {
+            continue;
+        };
+        if !tree.taffy.dirty(node).unwrap_or(false) {
+            continue;
+        }
+        if data.measure_queries.is_empty() {
+            for width in [AvailableSpace::MinContent, AvailableSpace::MaxContent] {
+                queries.push(c_MeasureQuery {
+                    node: node.into(),
+                    known_width: f32::NAN,
+                    known_height: f32::NAN,
+                    available_space: c_AvailableSpace::from(Size { width, height: AvailableSpace::MaxContent }),
+                });
+            }
+        } else {
+            queries.extend_from_slice(&data.measure_queries);
+        }
+    }
+    if queries.is_empty() {
+        return HashMap::new();
+    }
+
+    let mut outputs = vec![c_MeasureOutput { width: 0.0, height: 0.0, first_baseline: f32::NAN }; queries.len()];
+    (batch.callback)(batch.user_data, queries.as_ptr(), outputs.as_mut_ptr(), queries.len());
+    queries.iter().zip(outputs).map(|(query, output)| (MeasureKey::new(query), measurement(output))).collect()
+}
+
+/// Sets the first baseline of a measured leaf. Measured baselines are from the top of the content box,
+/// taffy wants them from the border box.
+pub(crate) fn measured_baseline(output: &mut LayoutOutput, style: &Style, inputs: &LayoutInput, baseline: Option<f32>) {
+    if let Some(baseline) = baseline {
+        let parent_width = inputs.parent_size.width;
+        let top = style.padding.top.resolve_or_zero(parent_width, |_, _| 0.0)
+            + style.border.top.resolve_or_zero(parent_width, |_, _| 0.0);
+        output.first_baselines.y = Some(top + baseline);
+    }
+}
+
+/// Asks the host for a measurement that was not predicted
+fn measure_one(tree: &FfiTree, query: c_MeasureQuery) -> Measurement {
+    let mut output = c_MeasureOutput { width: 0.0, height: 0.0, first_baseline: f32::NAN };
+    if let Some(measure) = tree.measure_callback {
+        (measure.callback)(measure.user_data, query.node, query.known_width, query.known_height, query.available_space, &mut output);
+    } else if let Some(batch) = tree.batch_measure_callback {
+        (batch.callback)(batch.user_data, &query, &mut output, 1);
+    }
+    measurement(output)
+}
+
+pub(crate) fn measurement(output: c_MeasureOutput) -> Measurement {
+    let baseline = (!output.first_baseline.is_nan()).then_some(output.first_baseline);
+    (Size { width: output.width, height: output.height }, baseline)
+}
+
+impl TraversePartialTree for FfiView<'_> {
+    type ChildIter<'a>
+        = <TaffyTree<()> as TraversePartialTree>::ChildIter<'a>
+    where
+        Self: 'a;
+
+    fn child_ids(&self, parent: NodeId) -> Self::ChildIter<'_> {
+        self.tree.taffy.child_ids(parent)
+    }
+
+    fn child_count(&self, parent: NodeId) -> usize {
+        self.tree.taffy.child_count(parent)
+    }
+
+    fn get_child_id(&self, parent: NodeId, index: usize) -> NodeId {
+        self.tree.taffy.get_child_id(parent, index)
+    }
+}
+
+impl TraverseTree for FfiView<'_> {}
+
+impl LayoutPartialTree for FfiView<'_> {
+    type CoreContainerStyle<'a>
+        = &'a Style
+    where
+        Self: 'a;
+
+    fn get_core_container_style(&self, node: NodeId) -> Self::CoreContainerStyle<'_> {
+        self.style(node)
+    }
+
+    #[cfg(feature = "calc")]
+    fn resolve_calc_value(&self, calc: *const (), basis: f32) -> f32 {
+        crate::calc::resolve(calc, basis)
+    }
+
+    fn set_unrounded_layout(&mut self, node: NodeId, layout: &Layout) {
+        if let Some(data) = self.tree.nodes.get_mut(&node) {
+            data.unrounded_layout = *layout;
+            data.root_pass = None;
+        }
+    }
+
+    fn compute_child_layout(&mut self, node: NodeId, inputs: LayoutInput) -> LayoutOutput {
+        if inputs.run_mode == RunMode::PerformHiddenLayout {
+            return compute_hidden_layout(self, node);
+        }
+
+        #[cfg(feature = "debug")]
+        if let Some(log) = &mut self.log {
+            log.message(
+                crate::debug::TRACE,
+                node,
+                format_args!("{:?} known {:?} available {:?}", inputs.run_mode, inputs.known_dimensions, inputs.available_space),
+            );
+        }
+
+        let start = Instant::now();
+        let outer_child_time = std::mem::take(&mut self.child_time);
+        let output = compute_cached_layout(self, node, inputs, |view, node, inputs| {
+            let display = view.style(node).display;
+            if display == Display::None {
+                view.algorithm = Some(Algorithm::Hidden);
+                return compute_hidden_layout(view, node);
+            }
+            let has_children = view.child_count(node) > 0;
+            let custom = view
+                .tree
+                .custom_layout_callback
+                .filter(|_| view.tree.nodes.get(&node).is_some_and(|data| data.custom_layout));
+            let (algorithm, output) = match (display, has_children, custom) {
+                (_, _, Some(callback)) => (Algorithm::Custom, view.run_custom_layout(callback, node, inputs)),
+                #[cfg(feature = "block_layout")]
+                (Display::Block, true, None) => (Algorithm::Block, compute_block_layout(view, node, inputs)),
+                #[cfg(feature = "flexbox")]
+                (Display::Flex, true, None) => (Algorithm::Flexbox, compute_flexbox_layout(view, node, inputs)),
+                #[cfg(feature = "grid")]
+                (Display::Grid, true, None) => (Algorithm::Grid, compute_grid_layout(view, node, inputs)),
+                _ => (Algorithm::Leaf, view.compute_leaf(node, inputs)),
+            };
+            view.algorithm = Some(algorithm);
+            output
+        });
+
+        let elapsed = start.elapsed();
+        let exclusive = elapsed.saturating_sub(self.child_time);
+        self.child_time = outer_child_time + elapsed;
+
+        let algorithm = self.algorithm.take();
+        #[cfg(feature = "debug")]
+        self.log_output(node, algorithm, &inputs, &output);
+
+        let ms = |duration: Duration| duration.as_secs_f64() * 1000.0;
+        let node_stats = self.node_stats.entry(node).or_default();
+        node_stats.layout_calls += 1;
+        node_stats.total_ms += ms(elapsed);
+        self.stats.layout_calls += 1;
+        match algorithm {
+            Some(algorithm) => {
+                node_stats.cache_misses += 1;
+                self.stats.cache_misses += 1;
+                let algorithm_ms = match algorithm {
+                    Algorithm::Hidden => None,
+                    #[cfg(feature = "flexbox")]
+                    Algorithm::Flexbox => Some(&mut self.stats.flexbox_ms),
+                    #[cfg(feature = "grid")]
+                    Algorithm::Grid => Some(&mut self.stats.grid_ms),
+                    #[cfg(feature = "block_layout")]
+                    Algorithm::Block => Some(&mut self.stats.block_ms),
+                    Algorithm::Leaf => Some(&mut self.stats.leaf_ms),
+                    Algorithm::Custom => Some(&mut self.stats.custom_ms),
+                };
+                if let Some(algorithm_ms) = algorithm_ms {
+                    *algorithm_ms += ms(exclusive);
+                }
+            }
+            None => {
+                node_stats.cache_hits += 1;
+                self.stats.cache_hits += 1;
+            }
+        }
+        output
+    }
+}
+
+impl CacheTree for FfiView<'_> {
+    fn cache_get(
+        &self,
+        node: NodeId,
+        known_dimensions: Size<Option<f32>>,
+        available_space: Size<AvailableSpace>,
+        run_mode: RunMode,
+    ) -> Option<LayoutOutput> {
+        self.tree.taffy.cache_get(node, known_dimensions, available_space, run_mode)
+    }
+
+    fn cache_store(
+        &mut self,
+        node: NodeId,
+        known_dimensions: Size<Option<f32>>,
+        available_space: Size<AvailableSpace>,
+        run_mode: RunMode,
+        layout_output: LayoutOutput,
+    ) {
+        self.tree.taffy.cache_store(node, known_dimensions, available_space, run_mode, layout_output)
+    }
+
+    fn cache_clear(&mut self, node: NodeId) {
+        self.tree.taffy.cache_clear(node)
+    }
+}
+
+#[cfg(feature = "flexbox")]
+impl LayoutFlexboxContainer for FfiView<'_> {
+    type FlexboxContainerStyle<'a>
+        = &'a Style
+    where
+        Self: 'a;
+
+    type FlexboxItemStyle<'a>
+        = &'a Style
+    where
+        Self: 'a;
+
+    fn get_flexbox_container_style(&self, node: NodeId) -> Self::FlexboxContainerStyle<'_> {
+        self.style(node)
+    }
+
+    fn get_flexbox_child_style(&self, child: NodeId) -> Self::FlexboxItemStyle<'_> {
+        self.style(child)
+    }
+}
+
+#[cfg(feature = "grid")]
+impl LayoutGridContainer for FfiView<'_> {
+    type GridContainerStyle<'a>
+        = &'a Style
+    where
+        Self: 'a;
+
+    type GridItemStyle<'a>
+        = &'a Style
+    where
+        Self: 'a;
+
+    fn get_grid_container_style(&self, node: NodeId) -> Self::GridContainerStyle<'_> {
+        self.style(node)
+    }
+
+    fn get_grid_child_style(&self, child: NodeId) -> Self::GridItemStyle<'_> {
+        self.style(child)
+    }
+}
+
+#[cfg(feature = "block_layout")]
+impl LayoutBlockContainer for FfiView<'_> {
+    type BlockContainerStyle<'a>
+        = &'a Style
+    where
+        Self: 'a;
+
+    type BlockItemStyle<'a>
+        = &'a Style
+    where
+        Self: 'a;
+
+    fn get_block_container_style(&self, node: NodeId) -> Self::BlockContainerStyle<'_> {
+        self.style(node)
+    }
+
+    fn get_block_child_style(&self, child: NodeId) -> Self::BlockItemStyle<'_> {
+        self.style(child)
+    }
+}
+
+impl RoundTree for FfiView<'_> {
+    fn get_unrounded_layout(&self, node: NodeId) -> &Layout {
+        self.tree.nodes.get(&node).map_or(&EMPTY_LAYOUT, |data| &data.unrounded_layout)
+    }
+
+    fn set_final_layout(&mut self, node: NodeId, layout: &Layout) {
+        if let Some(data) = self.tree.nodes.get_mut(&node) {
+            data.layout = *layout;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Adds a node to the tree the way the exports do
+    fn node(tree: &mut FfiTree, style: Style, children: &[NodeId]) -> NodeId {
+        let node = tree.taffy.new_with_children(style, children).unwrap();
+        tree.insert_node(node, None);
+        node
+    }
+
+    fn sized(tree: &mut FfiTree, width: f32, height: f32) -> NodeId {
+        node(tree, Style { size: Size { width: length(width), height: length(height) }, ..Style::default() }, &[])
+    }
+
+    /// Lays `root` out with `compute_layout` and with `TaffyTree::compute_layout`, with and without
+    /// rounding, and checks that every node gets the same layout from both
+    fn assert_parity(mut tree: FfiTree, root: NodeId, available_space: Size<AvailableSpace>) {
+        // Both passes start from a tree that has never been laid out, as caches are kept in the `TaffyTree`
+        let pristine = tree.taffy.clone();
+        for use_rounding in [true, false] {
+            let mut reference = pristine.clone();
+            tree.taffy = pristine.clone();
+            if use_rounding {
+                reference.enable_rounding();
+            } else {
+                reference.disable_rounding();
+            }
+            reference.compute_layout(root, available_space).unwrap();
+            tree.use_rounding = use_rounding;
+            compute_layout(&mut tree, root, available_space);
+
+            let mut stack = vec![root];
+            while let Some(node) = stack.pop() {
+                assert_eq!(tree.layout(node).unwrap(), reference.layout(node).unwrap(), "node {node:?}, rounding {use_rounding}");
+                stack.extend(reference.child_ids(node));
+            }
+        }
+    }
+
+    fn definite(width: f32, height: f32) -> Size<AvailableSpace> {
+        Size { width: AvailableSpace::Definite(width), height: AvailableSpace::Definite(height) }
+    }
+
+    #[cfg(feature = "flexbox")]
+    #[test]
+    fn flexbox_matches_taffy() {
+        let mut tree = FfiTree::new(TaffyTree::new());
+        let fixed = sized(&mut tree, 33.3, 20.0);
+        let grow = node(&mut tree, Style { flex_grow: 1.0, min_size: Size { width: auto(), height: length(10.0) }, ..Style::default() }, &[]);
+        let shrink = node(
+            &mut tree,
+            Style { flex_basis: length(80.0), flex_shrink: 2.0, margin: Rect { left: length(5.0), right: percent(0.1), top: auto(), bottom: zero() }, ..Style::default() },
+            &[],
+        );
+        let wrapped = sized(&mut tree, 70.0, 15.5);
+        let root = node(
+            &mut tree,
+            Style {
+                flex_wrap: FlexWrap::Wrap,
+                align_items: Some(AlignItems::Center),
+                justify_content: Some(JustifyContent::SpaceBetween),
+                padding: Rect { left: length(7.0), right: length(3.0), top: length(2.5), bottom: zero() },
+                gap: Size { width: length(4.0), height: length(6.0) },
+                size: Size { width: length(151.0), height: auto() },
+                ..Style::default()
+            },
+            &[fixed, grow, shrink, wrapped],
+        );
+        assert_parity(tree, root, definite(200.0, 200.0));
+    }
+
+    #[cfg(feature = "grid")]
+    #[test]
+    fn grid_matches_taffy() {
+        let mut tree = FfiTree::new(TaffyTree::new());
+        let first = sized(&mut tree, 20.0, 30.0);
+        let spanning = node(&mut tree, Style { grid_column: span(2), ..Style::default() }, &[]);
+        let placed = node(&mut tree, Style { grid_row: line(1), grid_column: line(3), ..Style::default() }, &[]);
+        let auto_placed = sized(&mut tree, 45.0, 12.0);
+        let root = node(
+            &mut tree,
+            Style {
+                display: Display::Grid,
+                grid_template_columns: vec![length(40.0), fr(1.0), minmax(length(10.0), fr(2.0))],
+                grid_template_rows: vec![auto(), length(25.0)],
+                gap: Size { width: length(3.0), height: length(5.0) },
+                padding: Rect::length(4.0),
+                ..Style::default()
+            },
+            &[first, spanning, placed, auto_placed],
+        );
+        assert_parity(tree, root, definite(190.0, 120.0));
+    }
+
+    #[cfg(feature = "block_layout")]
+    #[test]
+    fn block_matches_taffy() {
+        let mut tree = FfiTree::new(TaffyTree::new());
+        let first = node(
+            &mut tree,
+            Style { display: Display::Block, size: Size { width: auto(), height: length(20.0) }, margin: Rect { left: zero(), right: zero(), top: length(10.0), bottom: length(15.0) }, ..Style::default() },
+            &[],
+        );
+        let second = node(
+            &mut tree,
+            Style { display: Display::Block, size: Size { width: percent(0.5), height: length(12.5) }, margin: Rect { left: auto(), right: auto(), top: length(8.0), bottom: zero() }, ..Style::default() },
+            &[],
+        );
+        let inner = sized(&mut tree, 30.0, 9.0);
+        let nested = node(&mut tree, Style { display: Display::Block, padding: Rect::length(2.0), ..Style::default() }, &[inner]);
+        let root = node(&mut tree, Style { display: Display::Block, size: Size { width: length(120.0), height: auto() }, ..Style::default() }, &[first, second, nested]);
+        assert_parity(tree, root, definite(300.0, 300.0));
+    }
+
+    #[test]
+    fn display_none_matches_taffy() {
+        let mut tree = FfiTree::new(TaffyTree::new());
+        let hidden_child = sized(&mut tree, 10.0, 10.0);
+        let hidden = node(&mut tree, Style { display: Display::None, size: Size { width: length(50.0), height: length(50.0) }, ..Style::default() }, &[hidden_child]);
+        let visible = sized(&mut tree, 25.0, 35.0);
+        let root = node(&mut tree, Style { size: Size { width: length(100.0), height: length(100.0) }, ..Style::default() }, &[hidden, visible]);
+        assert_parity(tree, root, definite(100.0, 100.0));
+    }
+}
@@ -0,0 +1,187 @@
+//! Interpolation between two styles, for animated transitions. See `taffy_style_lerp`.
+//!
+//! As in CSS, a length interpolates when both ends use the same unit, numbers always interpolate, and
+//! everything else is discrete: it takes the value of the first style before t = 0.5 and of the second
+//! one from there. Grid track lists interpolate track by track when both lists have the same shape.
+//! Values that cannot be negative in CSS (sizes, padding, border, gap, flex factors) are clamped at 0,
+//! so that easing curves overshooting [0, 1] stay valid.
+
+use crate::{c_GridTrackSize, c_GridTrackSizing, c_Length, c_Rect, c_Size, c_Style, calc, OwnedStyle, OwnedTrackSizing};
+
+fn discrete<T>(a: T, b: T, t: f32) -> T {
+    if t < 0.5 {
+        a
+    } else {
+        b
+    }
+}
+
+fn number(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+/// Calc lengths with different expressions are discrete, since an interpolated one would need a new
+/// expression for every `t`
+fn length(a: c_Length, b: c_Length, t: f32) -> c_Length {
+    if a.dim == b.dim && a.dim != calc::DIM {
+        c_Length { dim: a.dim, value: number(a.value, b.value, t) }
+    } else {
+        discrete(a, b, t)
+    }
+}
+
+fn non_negative(length: c_Length) -> c_Length {
+    c_Length { dim: length.dim, value: length.value.max(0.0) }
+}
+
+fn size(a: c_Size, b: c_Size, t: f32) -> c_Size {
+    c_Size {
+        width: non_negative(length(a.width, b.width, t)),
+        height: non_negative(length(a.height, b.height, t)),
+    }
+}
+
+fn rect(a: c_Rect, b: c_Rect, t: f32, clamp: fn(c_Length) -> c_Length) -> c_Rect {
+    c_Rect {
+        left: clamp(length(a.left, b.left, t)),
+        right: clamp(length(a.right, b.right, t)),
+        top: clamp(length(a.top, b.top, t)),
+        bottom: clamp(length(a.bottom, b.bottom, t)),
+    }
+}
+
+fn track(a: &c_GridTrackSize, b: &c_GridTrackSize, t: f32) -> c_GridTrackSize {
+    c_GridTrackSize {
+        min_size: non_negative(length(a.min_size, b.min_size, t)),
+        max_size: non_negative(length(a.max_size, b.max_size, t)),
+    }
+}
+
+fn tracks(a: &[c_GridTrackSize], b: &[c_GridTrackSize], t: f32) -> Vec<c_GridTrackSize> {
+    if a.len() == b.len() {
+        a.iter().zip(b).map(|(a, b)| track(a, b, t)).collect()
+    } else {
+        discrete(a, b, t).to_vec()
+    }
+}
+
+fn sizings(a: &[OwnedTrackSizing], b: &[OwnedTrackSizing], t: f32) -> Vec<OwnedTrackSizing> {
+    let same_shape = a.len() == b.len()
+        && a.iter().zip(b).all(|pair| match pair {
+            (OwnedTrackSizing::Single(_), OwnedTrackSizing::Single(_)) => true,
+            (OwnedTrackSizing::Repeat(a_repetition, a_sizes), OwnedTrackSizing::Repeat(b_repetition, b_sizes)) => {
+                a_repetition == b_repetition && a_sizes.len() == b_sizes.len()
+            }
+            _ => false,
+        });
+    if !same_shape {
+        return discrete(a, b, t).to_vec();
+    }
+    a.iter()
+        .zip(b)
+        .map(|pair| match pair {
+            (OwnedTrackSizing::Single(a), OwnedTrackSizing::Single(b)) => OwnedTrackSizing::Single(track(a, b, t)),
+            (OwnedTrackSizing::Repeat(repetition, a_sizes), OwnedTrackSizing::Repeat(_, b_sizes)) => {
+                OwnedTrackSizing::Repeat(*repetition, tracks(a_sizes, b_sizes, t))
+            }
+            _ => unreachable!("track lists have the same shape"),
+        })
+        .collect()
+}
+
+impl OwnedStyle {
+    /// The style at `t` of a transition from this style to `other`
+    pub(crate) fn lerp(&self, other: &OwnedStyle, t: f32) -> OwnedStyle {
+        let (a, b) = (&self.raw, &other.raw);
+        let mut raw = discrete(*a, *b, t);
+        raw.scrollbar_width = number(a.scrollbar_width, b.scrollbar_width, t).max(0.0);
+        raw.inset = rect(a.inset, b.inset, t, |length| length);
+        raw.gap = size(a.gap, b.gap, t);
+        raw.margin = rect(a.margin, b.margin, t, |length| length);
+        raw.border = rect(a.border, b.border, t, non_negative);
+        raw.padding = rect(a.padding, b.padding, t, non_negative);
+        raw.size = size(a.size, b.size, t);
+        raw.min_size = size(a.min_size, b.min_size, t);
+        raw.max_size = size(a.max_size, b.max_size, t);
+        raw.flex_grow = number(a.flex_grow, b.flex_grow, t).max(0.0);
+        raw.flex_shrink = number(a.flex_shrink, b.flex_shrink, t).max(0.0);
+        raw.flex_basis = non_negative(length(a.flex_basis, b.flex_basis, t));
+        if a.has_aspect_ratio != 0 && b.has_aspect_ratio != 0 {
+            raw.aspect_ratio = number(a.aspect_ratio, b.aspect_ratio, t).max(0.0);
+        }
+
+        OwnedStyle {
+            raw,
+            grid_template_rows: sizings(&self.grid_template_rows, &other.grid_template_rows, t),
+            grid_template_columns: sizings(&self.grid_template_columns, &other.grid_template_columns, t),
+            grid_auto_rows: tracks(&self.grid_auto_rows, &other.grid_auto_rows, t),
+            grid_auto_columns: tracks(&self.grid_auto_columns, &other.grid_auto_columns, t),
+        }
+    }
+
+    /// Moves the style into a `c_Style` whose grid arrays are allocated here, to be released with `free_raw`
+    pub(crate) fn into_raw(self) -> c_Style {
+        fn leak<T>(items: Vec<T>) -> (*const T, usize) {
+            match items.len() {
+                0 => (std::ptr::null(), 0),
+                len => (Box::into_raw(items.into_boxed_slice()) as *const T, len),
+            }
+        }
+        fn sizings(tracks: Vec<OwnedTrackSizing>) -> (*const c_GridTrackSizing, usize) {
+            leak(
+                tracks
+                    .into_iter()
+                    .map(|track| match track {
+                        OwnedTrackSizing::Single(size) => c_GridTrackSizing {
+                            repetition: -2,
+                            single: Box::into_raw(Box::new(size)),
+                            repeat: std::ptr::null(),
+                            repeat_count: 0,
+                        },
+                        OwnedTrackSizing::Repeat(repetition, sizes) => {
+                            let (repeat, repeat_count) = leak(sizes);
+                            c_GridTrackSizing { repetition, single: std::ptr::null(), repeat, repeat_count }
+                        }
+                    })
+                    .collect(),
+            )
+        }
+
+        let mut raw = self.raw;
+        (raw.grid_template_rows, raw.grid_template_rows_count) = sizings(self.grid_template_rows);
+        (raw.grid_template_columns, raw.grid_template_columns_count) = sizings(self.grid_template_columns);
+        (raw.grid_auto_rows, raw.grid_auto_rows_count) = leak(self.grid_auto_rows);
+        (raw.grid_auto_columns, raw.grid_auto_columns_count) = leak(self.grid_auto_columns);
+        raw
+    }
+}
+
+/// Releases the grid arrays of a style written by `OwnedStyle::into_raw` and sets them to null.
+///
+/// # Safety
+/// The arrays must have been allocated by `into_raw` and not released yet.
+pub(crate) unsafe fn free_raw(raw: &mut c_Style) {
+    unsafe fn free<T>(ptr: &mut *const T, count: &mut usize) {
+        if !ptr.is_null() {
+            drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(*ptr as *mut T, *count)));
+        }
+        *ptr = std::ptr::null();
+        *count = 0;
+    }
+    unsafe fn free_sizings(ptr: &mut *const c_GridTrackSizing, count: &mut usize) {
+        if !ptr.is_null() {
+            for track in std::slice::from_raw_parts_mut(*ptr as *mut c_GridTrackSizing, *count) {
+                if !track.single.is_null() {
+                    drop(Box::from_raw(track.single as *mut c_GridTrackSize));
+                }
+                free(&mut track.repeat, &mut track.repeat_count);
+            }
+        }
+        free(ptr, count);
+    }
+
+    free_sizings(&mut raw.grid_template_rows, &mut raw.grid_template_rows_count);
+    free_sizings(&mut raw.grid_template_columns, &mut raw.grid_template_columns_count);
+    free(&mut raw.grid_auto_rows, &mut raw.grid_auto_rows_count);
+    free(&mut raw.grid_auto_columns, &mut raw.grid_auto_columns_count);
+}
@@ -1,10 +1,39 @@
 #![allow(non_camel_case_types)]
 #![allow(non_snake_case)]
-
-use taffy::{prelude::*, TextAlign};
+// Handles and pointers come straight from the host, every export trusts them the same way
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
+mod animation;
+mod calc;
+mod commands;
+#[cfg(feature = "debug")]
+mod debug;
+mod host_tree;
+mod layout;
+mod lerp;
+mod memory;
+mod render;
+mod stylesheet;
+mod text;
+mod units;
+
+use std::collections::{HashMap, HashSet};
+use std::ops::RangeInclusive;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use taffy::{prelude::*, LayoutInput, LayoutOutput, RequestedAxis, RunMode, SizingMode, TaffyError, TaffyResult};
+#[cfg(feature = "block_layout")]
+use taffy::TextAlign;
 use taffy::style::Style;
 use taffy::Overflow;
 
+use animation::LayoutSnapshot;
+use host_tree::HostTree;
+use stylesheet::Stylesheet;
+use text::Text;
+use units::Units;
+
 // MAIN
 
 #[repr(C)]
@@ -12,15 +41,588 @@ use taffy::Overflow;
 pub struct c_TaffyTree {
 }
 
+/// The state behind a `tree` handle: the taffy tree plus the bookkeeping taffy_ffi keeps next to it
+struct FfiTree {
+    taffy: TaffyTree<()>,
+    /// Every live node, so stale or foreign ids are rejected before taffy indexes with them
+    nodes: HashMap<NodeId, NodeData>,
+    /// How many slots taffy's storage has grown to. Its slot maps keep the slots of removed nodes for reuse.
+    slots: usize,
+    /// The open batch, if any. See `taffytree_begin_batch`
+    batch: Option<Box<Batch>>,
+    /// The stylesheet resolved into the style of every node
+    stylesheet: Option<Rc<Stylesheet>>,
+    use_rounding: bool,
+    /// See `taffytree_set_custom_layout_callback`
+    custom_layout_callback: Option<CustomLayoutCallback>,
+    /// See `taffytree_set_measure_callback`
+    measure_callback: Option<MeasureCallback>,
+    /// See `taffytree_set_batch_measure_callback`
+    batch_measure_callback: Option<BatchMeasureCallback>,
+    /// Counters from the last layout pass. See `taffytree_get_stats`
+    stats: c_LayoutStats,
+    node_stats: HashMap<NodeId, c_NodeStats>,
+    /// See `taffytree_set_logger`
+    logger: Option<Logger>,
+    /// What viewport units are relative to. See `taffytree_set_viewport`
+    viewport: Size<f32>,
+    /// What font units are relative to unless a node has a font size. See `taffytree_set_root_font_size`
+    root_font_size: f32,
+    /// Set once a node is given a font size, after which moved subtrees may inherit a different one
+    node_font_sizes: bool,
+}
+
+#[derive(Clone, Copy)]
+struct CustomLayoutCallback {
+    callback: extern "C" fn(user_data: usize, context: usize, node: u64, inputs: *const c_LayoutInput, output: *mut c_LayoutOutput),
+    user_data: usize,
+}
+
+#[derive(Clone, Copy)]
+struct MeasureCallback {
+    callback: extern "C" fn(
+        user_data: usize,
+        node: u64,
+        known_width: f32,
+        known_height: f32,
+        available_space: c_AvailableSpace,
+        output: *mut c_MeasureOutput,
+    ),
+    user_data: usize,
+}
+
+#[derive(Clone, Copy)]
+struct BatchMeasureCallback {
+    callback: extern "C" fn(user_data: usize, queries: *const c_MeasureQuery, outputs: *mut c_MeasureOutput, count: usize),
+    user_data: usize,
+}
+
+#[derive(Clone, Copy)]
+#[cfg_attr(not(feature = "debug"), allow(dead_code))]
+struct Logger {
+    callback: extern "C" fn(user_data: usize, severity: i32, node: u64, message: *const u8, len: usize),
+    user_data: usize,
+}
+
+#[derive(Clone, Default)]
+struct NodeData {
+    /// Set once the host gives the node a tag, an id or classes
+    element: Option<Box<ElementData>>,
+    /// How far the content of the node is scrolled. See `taffytree_set_scroll_offset`
+    scroll_offset: taffy::Point<f32>,
+    /// Laid out by the host's custom layout callback instead of flexbox, grid or block layout
+    custom_layout: bool,
+    /// A leaf sized by the host's measure callback
+    measured: bool,
+    /// The measurements taffy asked for the last time the node was laid out, predicted again once it is dirty
+    measure_queries: Vec<c_MeasureQuery>,
+    /// Set for text leaves, which are measured with their font before the measure callback is considered
+    text: Option<Box<Text>>,
+    /// The style the host set, kept unresolved while it uses relative lengths (see `units.rs`)
+    relative_style: Option<Box<OwnedStyle>>,
+    /// The id of the style handle the style was set from, restyled by `taffytree_update_style_handle`
+    style_handle: Option<u64>,
+    /// Inherited by descendants without one. See `taffytree_set_font_size`
+    font_size: Option<f32>,
+    unrounded_layout: Layout,
+    /// The final layout, rounded if rounding is enabled
+    layout: Layout,
+    /// What the last `taffytree_compute_layout` over the node depended on, while its layout is still from that
+    /// pass. See `taffytree_compute_layouts`
+    root_pass: Option<layout::RootPass>,
+}
+
+#[derive(Clone)]
+struct ElementData {
+    tag: String,
+    id: String,
+    classes: Vec<String>,
+    /// The style the host set, before the stylesheet is applied
+    base: Style,
+}
+
+/// A node as seen by stylesheet selectors
+struct TreeElement<'a> {
+    tree: &'a FfiTree,
+    node: NodeId,
+    element: Option<&'a ElementData>,
+}
+
+impl<'a> TreeElement<'a> {
+    fn new(tree: &'a FfiTree, node: NodeId) -> Self {
+        let element = tree.nodes.get(&node).and_then(|data| data.element.as_deref());
+        TreeElement { tree, node, element }
+    }
+}
+
+impl stylesheet::Element for TreeElement<'_> {
+    fn parent(&self) -> Option<Self> {
+        self.tree.taffy.parent(self.node).map(|parent| TreeElement::new(self.tree, parent))
+    }
+
+    fn tag(&self) -> &str {
+        self.element.map_or("", |element| &element.tag)
+    }
+
+    fn id(&self) -> &str {
+        self.element.map_or("", |element| &element.id)
+    }
+
+    fn has_class(&self, class: &str) -> bool {
+        self.element.is_some_and(|element| element.classes.iter().any(|c| c == class))
+    }
+}
+
+/// The undo log of an open batch
+#[derive(Default)]
+struct Batch {
+    /// The state of each node before the batch first changed it, `None` for nodes created by the batch
+    saved: HashMap<NodeId, Option<SavedNode>>,
+    /// Nodes removed by the batch. They stay detached in the TaffyTree until the batch is committed, so
+    /// that a rollback can attach them again under the same ids.
+    removed: Vec<NodeId>,
+    /// The settings of the tree when the batch began, `None` until the batch first changes one
+    settings: Option<TreeSettings>,
+    /// Set once an operation inside the batch failed and the tree was restored
+    failed: bool,
+}
+
+struct SavedNode {
+    data: NodeData,
+    style: Style,
+    children: Vec<NodeId>,
+}
+
+/// What the batched operations change besides the nodes
+struct TreeSettings {
+    stylesheet: Option<Rc<Stylesheet>>,
+    viewport: Size<f32>,
+    root_font_size: f32,
+    node_font_sizes: bool,
+}
+
+impl FfiTree {
+    fn new(taffy: TaffyTree<()>) -> Self {
+        FfiTree {
+            taffy,
+            nodes: HashMap::new(),
+            slots: 0,
+            batch: None,
+            stylesheet: None,
+            use_rounding: true,
+            custom_layout_callback: None,
+            measure_callback: None,
+            batch_measure_callback: None,
+            stats: c_LayoutStats::default(),
+            node_stats: HashMap::new(),
+            logger: None,
+            viewport: Size::ZERO,
+            root_font_size: units::DEFAULT_FONT_SIZE,
+            node_font_sizes: false,
+        }
+    }
+
+    /// The final layout of `node` from the last `taffytree_compute_layout`
+    fn layout(&self, node: NodeId) -> TaffyResult<&Layout> {
+        match self.nodes.get(&node) {
+            Some(data) => Ok(&data.layout),
+            None => Err(TaffyError::InvalidInputNode(node)),
+        }
+    }
+
+    fn contains(&self, node: NodeId) -> bool {
+        self.nodes.contains_key(&node)
+    }
+
+    fn insert_node(&mut self, node: NodeId, relative_style: Option<Box<OwnedStyle>>) {
+        self.nodes.insert(node, NodeData { relative_style, ..NodeData::default() });
+        // The low half of a node id is the index of its slot
+        self.slots = self.slots.max(u64::from(node) as u32 as usize + 1);
+        if let Some(batch) = self.batch.as_deref_mut() {
+            batch.saved.entry(node).or_insert(None);
+        }
+    }
+
+    /// Records the state of `node` in the open batch before the batch first changes it
+    fn journal(&mut self, node: NodeId) {
+        let FfiTree { taffy, nodes, batch, .. } = self;
+        let Some(batch) = batch.as_deref_mut() else {
+            return;
+        };
+        if let std::collections::hash_map::Entry::Vacant(entry) = batch.saved.entry(node) {
+            if let Some(data) = nodes.get(&node) {
+                entry.insert(Some(SavedNode {
+                    data: data.clone(),
+                    style: taffy.style(node).cloned().unwrap_or_default(),
+                    children: taffy.children(node).unwrap_or_default(),
+                }));
+            }
+        }
+    }
+
+    /// Records the settings of the tree in the open batch before the batch first changes one
+    fn journal_settings(&mut self) {
+        let settings = TreeSettings {
+            stylesheet: self.stylesheet.clone(),
+            viewport: self.viewport,
+            root_font_size: self.root_font_size,
+            node_font_sizes: self.node_font_sizes,
+        };
+        if let Some(batch) = self.batch.as_deref_mut() {
+            batch.settings.get_or_insert(settings);
+        }
+    }
+
+    /// Records `child`, the parent it leaves and the parent it joins, before `child` is attached to `parent`
+    fn journal_attach(&mut self, parent: NodeId, child: NodeId) {
+        self.journal(parent);
+        self.journal(child);
+        if let Some(old_parent) = self.taffy.parent(child) {
+            self.journal(old_parent);
+        }
+    }
+
+    /// Forgets that `node` was laid out as a root, now that its layout comes from its new parent. The caller
+    /// records it in the open batch.
+    fn clear_root_layout(&mut self, node: NodeId) {
+        if let Some(data) = self.nodes.get_mut(&node) {
+            data.root_pass = None;
+        }
+    }
+
+    /// The data of `node`, recorded in the open batch first since the caller is about to change it
+    fn data_mut(&mut self, node: NodeId) -> Option<&mut NodeData> {
+        self.journal(node);
+        self.nodes.get_mut(&node)
+    }
+
+    /// Removes `node`, leaving its children without a parent. Inside a batch the node is only detached
+    /// until the batch is committed.
+    fn remove_node(&mut self, node: NodeId) {
+        if self.batch.is_none() {
+            let _ = self.taffy.remove(node);
+            self.nodes.remove(&node);
+            return;
+        }
+        self.journal(node);
+        if let Some(parent) = self.taffy.parent(node) {
+            self.journal(parent);
+            let _ = self.taffy.remove_child(parent, node);
+        }
+        let _ = self.taffy.remove_children_range(node, ..);
+        self.nodes.remove(&node);
+        if let Some(batch) = self.batch.as_deref_mut() {
+            batch.removed.push(node);
+        }
+    }
+
+    /// The element data of `node`, created from its current style on first use
+    fn element_mut(&mut self, node: NodeId) -> &mut ElementData {
+        self.journal(node);
+        let taffy = &self.taffy;
+        let data = self.nodes.get_mut(&node).expect("node must be validated by the caller");
+        data.element.get_or_insert_with(|| {
+            Box::new(ElementData {
+                tag: String::new(),
+                id: String::new(),
+                classes: Vec::new(),
+                base: taffy.style(node).cloned().unwrap_or_default(),
+            })
+        })
+    }
+
+    /// The style the host set on `node`, before any stylesheet
+    fn host_style(&self, node: NodeId) -> TaffyResult<&Style> {
+        match self.nodes.get(&node).and_then(|data| data.element.as_deref()) {
+            Some(element) => Ok(&element.base),
+            None => self.taffy.style(node),
+        }
+    }
+
+    /// Sets the style the host wants for `node`, which the stylesheet is then applied on top of.
+    /// Any relative lengths or style handle of the previous style are no longer tracked.
+    fn set_host_style(&mut self, node: NodeId, style: Style) -> TaffyResult<()> {
+        self.journal(node);
+        let data = self.nodes.get_mut(&node).ok_or(TaffyError::InvalidInputNode(node))?;
+        data.relative_style = None;
+        data.style_handle = None;
+        match data.element.as_deref_mut() {
+            Some(element) => element.base = style,
+            None => self.taffy.set_style(node, style)?,
+        }
+        self.restyle(node);
+        Ok(())
+    }
+
+    /// Like `set_host_style`, but keeps tracking the relative lengths of `style`
+    fn set_converted_style(&mut self, node: NodeId, style: ConvertedStyle) -> TaffyResult<()> {
+        self.set_host_style(node, style.style)?;
+        if let Some(data) = self.nodes.get_mut(&node) {
+            data.relative_style = style.relative;
+        }
+        Ok(())
+    }
+
+    /// Like `set_converted_style`, but restyles `node` again whenever the handle is updated
+    fn set_handle_style(&mut self, node: NodeId, handle: &StyleHandle) -> TaffyResult<()> {
+        self.set_converted_style(node, handle.style.for_units(&self.units(Some(node))))?;
+        if let Some(data) = self.nodes.get_mut(&node) {
+            data.style_handle = Some(handle.id);
+        }
+        Ok(())
+    }
+
+    /// Resolves the relative lengths of every node in the subtree of `root`, or in the whole tree if
+    /// `None`, again. Only the nodes whose style changed are dirtied.
+    fn resolve_relative_styles(&mut self, root: Option<NodeId>) {
+        let is_relative = |node: &NodeId| self.nodes.get(node).is_some_and(|data| data.relative_style.is_some());
+        let nodes: Vec<NodeId> = match root {
+            Some(root) => {
+                let mut subtree = vec![root];
+                let mut i = 0;
+                while let Some(&node) = subtree.get(i) {
+                    subtree.extend(self.taffy.children(node).unwrap_or_default());
+                    i += 1;
+                }
+                subtree.into_iter().filter(is_relative).collect()
+            }
+            None => self.nodes.keys().copied().filter(is_relative).collect(),
+        };
+        let mut changed = HashSet::new();
+        for node in nodes {
+            let units = self.units(Some(node));
+            let Some((style, _)) = self.nodes.get(&node).and_then(|data| data.relative_style.as_deref()).map(|style| style.resolve(&units))
+            else {
+                continue;
+            };
+            if self.host_style(node).is_ok_and(|host| *host == style) {
+                continue;
+            }
+            self.journal(node);
+            let Some(data) = self.nodes.get_mut(&node) else {
+                continue;
+            };
+            match data.element.as_deref_mut() {
+                Some(element) => element.base = style,
+                None => {
+                    let _ = self.taffy.set_style(node, style);
+                }
+            }
+            changed.insert(node);
+        }
+        // The rules of the stylesheet may depend on the units as well
+        if self.stylesheet.as_deref().is_some_and(Stylesheet::is_relative) {
+            match root {
+                Some(root) => self.restyle(root),
+                None => self.restyle_all(),
+            }
+            return;
+        }
+        // A stylesheet restyles the whole subtree, so only start from the topmost nodes that changed
+        let has_sheet = self.stylesheet.is_some();
+        for &node in &changed {
+            let mut ancestors = std::iter::successors(self.taffy.parent(node), |&node| self.taffy.parent(node));
+            if !has_sheet || !ancestors.any(|ancestor| changed.contains(&ancestor)) {
+                self.restyle(node);
+            }
+        }
+    }
+
+    /// What the relative lengths of `node` are resolved against, or those of a node that is not in the
+    /// tree yet if `None`
+    fn units(&self, node: Option<NodeId>) -> Units {
+        let font_size = std::iter::successors(node, |&node| self.taffy.parent(node))
+            .find_map(|node| self.nodes.get(&node).and_then(|data| data.font_size))
+            .unwrap_or(self.root_font_size);
+        Units { viewport: self.viewport, font_size, root_font_size: self.root_font_size }
+    }
+
+    /// Re-resolves the styles of `node` and its descendants, parents before children so that
+    /// inherited values are up to date
+    fn restyle(&mut self, node: NodeId) {
+        let mut stack = vec![node];
+        while let Some(node) = stack.pop() {
+            // Every node can inherit from its parent, so give the ones the host never styled a base too
+            if self.stylesheet.is_some() && self.nodes.get(&node).is_some_and(|data| data.element.is_none()) {
+                self.element_mut(node);
+            }
+            let resolved = self.nodes.get(&node).and_then(|data| data.element.as_deref()).map(|element| {
+                match &self.stylesheet {
+                    Some(sheet) => {
+                        let parent = self.taffy.parent(node).and_then(|parent| self.taffy.style(parent).ok());
+                        sheet.resolve(&TreeElement::new(self, node), &element.base, parent, &self.units(Some(node)))
+                    }
+                    None => element.base.clone(),
+                }
+            });
+            if let Some(resolved) = resolved {
+                if self.taffy.style(node).is_ok_and(|style| *style != resolved) {
+                    self.journal(node);
+                    let _ = self.taffy.set_style(node, resolved);
+                }
+            }
+            // Without a stylesheet no node depends on its parent
+            if self.stylesheet.is_some() {
+                stack.extend(self.taffy.children(node).unwrap_or_default());
+            }
+        }
+    }
+
+    /// Re-resolves the whole tree, starting from every root
+    fn restyle_all(&mut self) {
+        let has_sheet = self.stylesheet.is_some();
+        let roots: Vec<NodeId> =
+            self.nodes.keys().copied().filter(|&node| !has_sheet || self.taffy.parent(node).is_none()).collect();
+        for root in roots {
+            self.restyle(root);
+        }
+    }
+
+    /// Re-resolves a subtree whose position in the tree changed, if a stylesheet or an inherited
+    /// font size could depend on it
+    fn restyle_moved(&mut self, node: NodeId) {
+        if self.node_font_sizes {
+            self.resolve_relative_styles(Some(node));
+        }
+        if self.stylesheet.is_some() {
+            self.restyle(node);
+        }
+    }
+
+    /// Whether `ancestor` is `node` or one of its ancestors
+    fn is_ancestor(&self, ancestor: NodeId, node: NodeId) -> bool {
+        let mut current = Some(node);
+        while let Some(n) = current {
+            if n == ancestor {
+                return true;
+            }
+            current = self.taffy.parent(n);
+        }
+        false
+    }
+
+    /// Whether `child` can be attached under `parent` without breaking the tree
+    fn can_attach(&self, parent: NodeId, child: NodeId) -> bool {
+        self.contains(parent) && self.contains(child) && !self.is_ancestor(child, parent)
+    }
+
+    /// Whether the open batch already failed, in which case further mutations are refused until it is closed
+    fn poisoned(&self) -> bool {
+        self.batch.as_ref().is_some_and(|batch| batch.failed)
+    }
+
+    /// Opens a batch, returning false if one is already open
+    fn begin_batch(&mut self) -> bool {
+        if self.batch.is_some() {
+            return false;
+        }
+        self.batch = Some(Box::default());
+        true
+    }
+
+    /// Closes the open batch, returning false if there was none or if it failed
+    fn commit_batch(&mut self) -> bool {
+        let Some(batch) = self.batch.take() else {
+            return false;
+        };
+        for node in batch.removed {
+            let _ = self.taffy.remove(node);
+        }
+        !batch.failed
+    }
+
+    /// Closes the open batch and undoes it, returning false if there was no open batch
+    fn rollback_batch(&mut self) -> bool {
+        match self.batch.take() {
+            Some(batch) => {
+                self.undo(*batch);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Records a failed operation and returns the error code. Inside a batch this restores the
+    /// tree to the state it had at `taffytree_begin_batch`.
+    fn fail(&mut self) -> i32 {
+        if let Some(batch) = self.batch.take() {
+            self.undo(*batch);
+            self.batch = Some(Box::new(Batch { failed: true, ..Batch::default() }));
+        }
+        1
+    }
+
+    /// Restores the nodes and settings recorded by `batch`
+    fn undo(&mut self, batch: Batch) {
+        let (created, saved): (Vec<_>, Vec<_>) = batch.saved.into_iter().partition(|(_, saved)| saved.is_none());
+        for (node, _) in created {
+            let _ = self.taffy.remove(node);
+            self.nodes.remove(&node);
+        }
+        // Detach the children of every recorded node first, so that attaching them again cannot
+        // leave a child listed under two parents
+        for (node, _) in &saved {
+            let _ = self.taffy.remove_children_range(*node, ..);
+        }
+        for (node, saved) in saved.into_iter().filter_map(|(node, saved)| Some((node, saved?))) {
+            for child in saved.children {
+                let _ = self.taffy.add_child(node, child);
+            }
+            let _ = self.taffy.set_style(node, saved.style);
+            self.nodes.insert(node, saved.data);
+        }
+        if let Some(settings) = batch.settings {
+            self.stylesheet = settings.stylesheet;
+            self.viewport = settings.viewport;
+            self.root_font_size = settings.root_font_size;
+            self.node_font_sizes = settings.node_font_sizes;
+        }
+    }
+}
+
+/// Reads a UTF-8 string passed as a pointer and a length in bytes, `None` if it is not valid UTF-8
+fn str_arg<'a>(ptr: *const u8, len: usize) -> Option<&'a str> {
+    if len == 0 {
+        return Some("");
+    }
+    if ptr.is_null() {
+        return None;
+    }
+    std::str::from_utf8(unsafe { std::slice::from_raw_parts(ptr, len) }).ok()
+}
+
+/// Copies a style passed by the host, `None` if it is null or holds a value this build cannot convert: an
+/// enum index or length dim out of range, a display mode it was compiled without (see
+/// `taffy_ffi_supported_features`) or a calc length it cannot lay out
+fn style_arg(style: *const c_Style) -> Option<OwnedStyle> {
+    if style.is_null() {
+        return None;
+    }
+    OwnedStyle::from_raw(unsafe { &*style })
+}
+
+/// Copies as much of `text` as fits into the `cap` bytes at `buf` and returns the full length of `text`,
+/// so the host can retry with a larger buffer. Nothing is written past `cap` and no terminator is added.
+fn write_to_buffer(text: &str, buf: *mut u8, cap: usize) -> i32 {
+    let len = text.len().min(cap);
+    if len > 0 && !buf.is_null() {
+        unsafe {
+            std::ptr::copy_nonoverlapping(text.as_ptr(), buf, len);
+        }
+    }
+    i32::try_from(text.len()).unwrap_or(i32::MAX)
+}
+
 #[no_mangle]
 pub extern "C" fn taffytree_new() -> usize {
-    let tree: taffy::TaffyTree<()> = taffy::TaffyTree::new();
+    let tree = FfiTree::new(TaffyTree::new());
     Box::into_raw(Box::new(tree)) as usize
 }
 
 #[no_mangle]
 pub extern "C" fn taffytree_with_capacity(capacity: usize) -> usize {
-    let tree: taffy::TaffyTree<()> = taffy::TaffyTree::with_capacity(capacity);
+    let mut tree = FfiTree::new(TaffyTree::with_capacity(capacity));
+    tree.nodes.reserve(capacity);
     Box::into_raw(Box::new(tree)) as usize
 }
 
@@ -30,24 +632,51 @@ pub extern "C" fn taffytree_new_with_children(tree: usize, style: *const c_Style
         return 0;
     }
 
-    let tree_ptr = tree as *mut TaffyTree<()>;
+    let tree_ptr = tree as *mut FfiTree;
     let tree = unsafe { &mut *tree_ptr };
-    let style = unsafe { Style::from(*style) };
+    if tree.poisoned() {
+        return 0;
+    }
+    let Some(style) = style_arg(style) else {
+        tree.fail();
+        return 0;
+    };
+    let style = ConvertedStyle::new(style, &tree.units(None));
 
     let children_slice: &[taffy::NodeId] = unsafe {
         std::slice::from_raw_parts(children as *const taffy::NodeId, children_len)
     };
+    if !children_slice.iter().all(|&child| tree.contains(child)) {
+        tree.fail();
+        return 0;
+    }
+    for &child in children_slice {
+        tree.journal(child);
+        if let Some(old_parent) = tree.taffy.parent(child) {
+            tree.journal(old_parent);
+        }
+    }
 
-    match tree.new_with_children(style, children_slice) {
-        Ok(node) => node.into(),
-        Err(_) => 0,
+    match tree.taffy.new_with_children(style.style, children_slice) {
+        Ok(node) => {
+            tree.insert_node(node, style.relative);
+            for &child in children_slice {
+                tree.clear_root_layout(child);
+            }
+            tree.restyle_moved(node);
+            node.into()
+        }
+        Err(_) => {
+            tree.fail();
+            0
+        }
     }
 }
 
 #[no_mangle]
 pub extern "C" fn taffytree_free(tree: usize) {
     if tree != 0 {
-        let tree_ptr = tree as *mut TaffyTree;
+        let tree_ptr = tree as *mut FfiTree;
         unsafe {
             drop(Box::from_raw(tree_ptr));
         }
@@ -56,16 +685,47 @@ pub extern "C" fn taffytree_free(tree: usize) {
 
 #[no_mangle]
 pub extern "C" fn taffytree_enable_rounding(tree: usize) {
-    let tree_ptr = tree as *mut TaffyTree<()>;
+    let tree_ptr = tree as *mut FfiTree;
     let tree = unsafe { &mut *tree_ptr };
-    tree.enable_rounding();
+    tree.taffy.enable_rounding();
+    tree.use_rounding = true;
 }
 
 #[no_mangle]
 pub extern "C" fn taffytree_disable_rounding(tree: usize) {
-    let tree_ptr = tree as *mut TaffyTree<()>;
+    let tree_ptr = tree as *mut FfiTree;
+    let tree = unsafe { &mut *tree_ptr };
+    tree.taffy.disable_rounding();
+    tree.use_rounding = false;
+}
+
+// BATCHES
+
+/// Starts a batch: until it is committed or rolled back, the first failing operation restores the
+/// tree to its current state and every later mutation is refused. Returns 1 if a batch is already open.
+#[no_mangle]
+pub extern "C" fn taffytree_begin_batch(tree: usize) -> i32 {
+    let tree_ptr = tree as *mut FfiTree;
+    let tree = unsafe { &mut *tree_ptr };
+    if tree.begin_batch() { 0 } else { 1 }
+}
+
+/// Closes the open batch, keeping its changes. Returns 1 if there was no open batch or if an
+/// operation in it failed, in which case the tree is back at the state it had before the batch.
+#[no_mangle]
+pub extern "C" fn taffytree_commit_batch(tree: usize) -> i32 {
+    let tree_ptr = tree as *mut FfiTree;
     let tree = unsafe { &mut *tree_ptr };
-    tree.disable_rounding();
+    if tree.commit_batch() { 0 } else { 1 }
+}
+
+/// Closes the open batch and restores the tree to the state it had before the batch.
+/// Returns 1 if there was no open batch.
+#[no_mangle]
+pub extern "C" fn taffytree_rollback_batch(tree: usize) -> i32 {
+    let tree_ptr = tree as *mut FfiTree;
+    let tree = unsafe { &mut *tree_ptr };
+    if tree.rollback_batch() { 0 } else { 1 }
 }
 
 // STYLE
@@ -77,6 +737,30 @@ pub struct c_Length {
     value: f32,
 }
 
+// Valid `c_Length::dim` values for each kind of length
+const DIMENSION: RangeInclusive<i32> = 0..=2;
+const LENGTH_PERCENTAGE: RangeInclusive<i32> = 1..=2;
+const LENGTH_PERCENTAGE_AUTO: RangeInclusive<i32> = 0..=2;
+const MIN_TRACK: RangeInclusive<i32> = 0..=4;
+const MAX_TRACK: RangeInclusive<i32> = 0..=7;
+
+// Highest valid index of each enum
+const MAX_DISPLAY: i32 = 3;
+const MAX_BOX_SIZING: i32 = 1;
+const MAX_OVERFLOW: i32 = 3;
+const MAX_POSITION: i32 = 1;
+const MAX_TEXT_ALIGN: i32 = 3;
+const MAX_FLEX_WRAP: i32 = 2;
+const MAX_FLEX_DIRECTION: i32 = 3;
+const MAX_GRID_AUTO_FLOW: i32 = 3;
+const MAX_ALIGN_ITEMS: i32 = 6;
+const MAX_ALIGN_CONTENT: i32 = 8;
+
+/// Whether `length` is one of `dims`, a relative length (see `units.rs`) or a calc length this build can lay out
+fn is_valid_length(length: c_Length, dims: &RangeInclusive<i32>) -> bool {
+    (dims.contains(&length.dim) || units::is_relative(length) || length.dim == calc::DIM) && calc::is_supported(length)
+}
+
 #[repr(C)]
 #[derive(Copy, Clone)]
 pub struct c_Size {
@@ -186,12 +870,120 @@ pub struct c_Style {
     has_justify_content: i32,
 }
 
+/// A grid track list entry owned by an [`OwnedStyle`]
+#[derive(Clone)]
+enum OwnedTrackSizing {
+    Single(c_GridTrackSize),
+    Repeat(i32, Vec<c_GridTrackSize>),
+}
+
+/// A `c_Style` that owns its grid track arrays, for styles that don't live in host memory.
+/// The pointer fields of `raw` are ignored, see [`OwnedStyle::with_raw`].
+#[derive(Clone)]
+struct OwnedStyle {
+    raw: c_Style,
+    grid_template_rows: Vec<OwnedTrackSizing>,
+    grid_template_columns: Vec<OwnedTrackSizing>,
+    grid_auto_rows: Vec<c_GridTrackSize>,
+    grid_auto_columns: Vec<c_GridTrackSize>,
+}
+
+impl OwnedStyle {
+    /// Calls `f` with a `c_Style` whose grid pointers refer to the arrays owned by this style
+    fn with_raw<R>(&self, f: impl FnOnce(&c_Style) -> R) -> R {
+        fn sizings(tracks: &[OwnedTrackSizing]) -> Vec<c_GridTrackSizing> {
+            tracks
+                .iter()
+                .map(|track| match track {
+                    OwnedTrackSizing::Single(size) => c_GridTrackSizing {
+                        repetition: -2,
+                        single: size,
+                        repeat: std::ptr::null(),
+                        repeat_count: 0,
+                    },
+                    OwnedTrackSizing::Repeat(repetition, sizes) => c_GridTrackSizing {
+                        repetition: *repetition,
+                        single: std::ptr::null(),
+                        repeat: sizes.as_ptr(),
+                        repeat_count: sizes.len(),
+                    },
+                })
+                .collect()
+        }
+
+        let rows = sizings(&self.grid_template_rows);
+        let columns = sizings(&self.grid_template_columns);
+        let mut raw = self.raw;
+        raw.grid_template_rows = rows.as_ptr();
+        raw.grid_template_rows_count = rows.len();
+        raw.grid_template_columns = columns.as_ptr();
+        raw.grid_template_columns_count = columns.len();
+        raw.grid_auto_rows = self.grid_auto_rows.as_ptr();
+        raw.grid_auto_rows_count = self.grid_auto_rows.len();
+        raw.grid_auto_columns = self.grid_auto_columns.as_ptr();
+        raw.grid_auto_columns_count = self.grid_auto_columns.len();
+        f(&raw)
+    }
+
+    /// Whether every field holds a value the conversion into a `taffy::Style` accepts, with display modes and
+    /// calc lengths limited to what this build supports
+    fn is_valid(&self) -> bool {
+        let raw = &self.raw;
+        let index = |index: i32, max: i32| (0..=max).contains(&index);
+        let optional = |value: i32, has: i32, max: i32| has == 0 || index(value, max);
+        let lengths = |lengths: &[c_Length], dims: RangeInclusive<i32>| lengths.iter().all(|length| is_valid_length(*length, &dims));
+        let rect = |rect: c_Rect| [rect.left, rect.right, rect.top, rect.bottom];
+        let size = |size: c_Size| [size.width, size.height];
+        let grid_index = |grid_index: c_GridIndex| index(grid_index.kind.into(), 2) && !(grid_index.kind == 2 && grid_index.value < 0);
+        let track = |track: &c_GridTrackSize| {
+            is_valid_length(track.min_size, &MIN_TRACK) && is_valid_length(track.max_size, &MAX_TRACK)
+        };
+        let sizing = |sizing: &OwnedTrackSizing| match sizing {
+            OwnedTrackSizing::Single(size) => track(size),
+            OwnedTrackSizing::Repeat(repetition, sizes) => {
+                (-1..=u16::MAX as i32).contains(repetition) && sizes.iter().all(track)
+            }
+        };
+
+        supports_display(raw.display)
+            && index(raw.box_sizing, MAX_BOX_SIZING)
+            && index(raw.overflow_x, MAX_OVERFLOW)
+            && index(raw.overflow_y, MAX_OVERFLOW)
+            && index(raw.position, MAX_POSITION)
+            && index(raw.text_align, MAX_TEXT_ALIGN)
+            && index(raw.flex_wrap, MAX_FLEX_WRAP)
+            && index(raw.flex_direction, MAX_FLEX_DIRECTION)
+            && index(raw.grid_auto_flow, MAX_GRID_AUTO_FLOW)
+            && optional(raw.align_items, raw.has_align_items, MAX_ALIGN_ITEMS)
+            && optional(raw.justify_items, raw.has_justify_items, MAX_ALIGN_ITEMS)
+            && optional(raw.align_self, raw.has_align_self, MAX_ALIGN_ITEMS)
+            && optional(raw.justify_self, raw.has_justify_self, MAX_ALIGN_ITEMS)
+            && optional(raw.align_content, raw.has_align_content, MAX_ALIGN_CONTENT)
+            && optional(raw.justify_content, raw.has_justify_content, MAX_ALIGN_CONTENT)
+            && lengths(&rect(raw.inset), LENGTH_PERCENTAGE_AUTO)
+            && lengths(&rect(raw.margin), LENGTH_PERCENTAGE_AUTO)
+            && lengths(&rect(raw.border), LENGTH_PERCENTAGE)
+            && lengths(&rect(raw.padding), LENGTH_PERCENTAGE)
+            && lengths(&size(raw.gap), LENGTH_PERCENTAGE)
+            && lengths(&size(raw.size), DIMENSION)
+            && lengths(&size(raw.min_size), DIMENSION)
+            && lengths(&size(raw.max_size), DIMENSION)
+            && lengths(&[raw.flex_basis], DIMENSION)
+            && [raw.grid_row.start, raw.grid_row.end, raw.grid_column.start, raw.grid_column.end].into_iter().all(grid_index)
+            && self.grid_template_rows.iter().chain(&self.grid_template_columns).all(sizing)
+            && self.grid_auto_rows.iter().chain(&self.grid_auto_columns).all(track)
+    }
+}
+
 // Conversion traits
+//
+// They panic on values that `OwnedStyle::from_raw` rejects, so styles from the host are always copied with it first.
 
 trait FromIndex<T> {
     fn from_index(index: i32) -> T;
 }
 
+#[cfg(any(feature = "flexbox", feature = "grid"))]
 trait FromIndexOptional<T> {
     fn from_index(index: i32, has: i32) -> Option<T>;
 }
@@ -200,8 +992,11 @@ impl FromIndex<Display> for Display {
     fn from_index(index: i32) -> Display {
         match index {
             0 => Display::None,
+            #[cfg(feature = "flexbox")]
             1 => Display::Flex,
+            #[cfg(feature = "grid")]
             2 => Display::Grid,
+            #[cfg(feature = "block_layout")]
             3 => Display::Block,
             _ => panic!("invalid display index {}", index),
         }
@@ -240,6 +1035,7 @@ impl FromIndex<Position> for Position {
     }
 }
 
+#[cfg(feature = "flexbox")]
 impl FromIndex<FlexWrap> for FlexWrap {
     fn from_index(index: i32) -> FlexWrap {
         match index {
@@ -251,6 +1047,7 @@ impl FromIndex<FlexWrap> for FlexWrap {
     }
 }
 
+#[cfg(feature = "block_layout")]
 impl FromIndex<TextAlign> for TextAlign {
     fn from_index(index: i32) -> TextAlign {
         match index {
@@ -263,6 +1060,7 @@ impl FromIndex<TextAlign> for TextAlign {
     }
 }
 
+#[cfg(feature = "flexbox")]
 impl FromIndex<FlexDirection> for FlexDirection {
     fn from_index(index: i32) -> FlexDirection {
         match index {
@@ -275,6 +1073,7 @@ impl FromIndex<FlexDirection> for FlexDirection {
     }
 }
 
+#[cfg(any(feature = "flexbox", feature = "grid"))]
 impl FromIndexOptional<AlignItems> for AlignItems {
     fn from_index(index: i32, has: i32) -> Option<AlignItems> {
         if has == 0 {
@@ -294,6 +1093,7 @@ impl FromIndexOptional<AlignItems> for AlignItems {
     }
 }
 
+#[cfg(any(feature = "flexbox", feature = "grid"))]
 impl FromIndexOptional<AlignContent> for AlignContent {
     fn from_index(index: i32, has: i32) -> Option<AlignContent> {
         if has == 0 {
@@ -315,6 +1115,7 @@ impl FromIndexOptional<AlignContent> for AlignContent {
     }
 }
 
+#[cfg(feature = "grid")]
 impl FromIndex<GridAutoFlow> for GridAutoFlow {
     fn from_index(index: i32) -> GridAutoFlow {
         match index {
@@ -327,12 +1128,20 @@ impl FromIndex<GridAutoFlow> for GridAutoFlow {
     }
 }
 
+/// The pointer taffy stores for a calc length, see `calc.rs`
+#[cfg(feature = "calc")]
+fn calc_pointer(length: c_Length) -> *const () {
+    calc::pointer(length.value).unwrap_or_else(|| panic!("unknown calc expression {}", length.value))
+}
+
 impl From<c_Length> for Dimension {
     fn from(length: c_Length) -> Dimension {
         match length.dim {
             0 => Dimension::auto(),
             1 => Dimension::length(length.value),
             2 => Dimension::percent(length.value),
+            #[cfg(feature = "calc")]
+            calc::DIM => Dimension::calc(calc_pointer(length)),
             _ => panic!("unsupported dimension {}", length.dim),
         }
     }
@@ -344,6 +1153,8 @@ impl From<c_Length> for LengthPercentageAuto {
             0 => LengthPercentageAuto::auto(),
             1 => LengthPercentageAuto::length(length.value),
             2 => LengthPercentageAuto::percent(length.value),
+            #[cfg(feature = "calc")]
+            calc::DIM => LengthPercentageAuto::calc(calc_pointer(length)),
             _ => panic!("unsupported dimension {}", length.dim),
         }
     }
@@ -354,6 +1165,8 @@ impl From<c_Length> for LengthPercentage {
         match length.dim {
             1 => LengthPercentage::length(length.value),
             2 => LengthPercentage::percent(length.value),
+            #[cfg(feature = "calc")]
+            calc::DIM => LengthPercentage::calc(calc_pointer(length)),
             _ => panic!("unsupported dimension {}", length.dim),
         }
     }
@@ -430,6 +1243,7 @@ impl From<c_Rect> for Rect<Dimension> {
     }
 }
 
+#[cfg(feature = "grid")]
 impl From<c_GridIndex> for GridPlacement {
     fn from(grid_index: c_GridIndex) -> Self {
         match grid_index.kind {
@@ -441,6 +1255,7 @@ impl From<c_GridIndex> for GridPlacement {
     }
 }
 
+#[cfg(feature = "grid")]
 impl From<c_GridPlacement> for Line<GridPlacement> {
     fn from(grid_placement: c_GridPlacement) -> Self {
         Self {
@@ -450,6 +1265,7 @@ impl From<c_GridPlacement> for Line<GridPlacement> {
     }
 }
 
+#[cfg(feature = "grid")]
 impl From<c_GridTrackSize> for NonRepeatedTrackSizingFunction {
     fn from(size: c_GridTrackSize) -> Self {
         NonRepeatedTrackSizingFunction {
@@ -459,6 +1275,7 @@ impl From<c_GridTrackSize> for NonRepeatedTrackSizingFunction {
     }
 }
 
+#[cfg(feature = "grid")]
 impl From<c_Length> for MinTrackSizingFunction {
     fn from(length: c_Length) -> Self {
         match length.dim {
@@ -467,11 +1284,14 @@ impl From<c_Length> for MinTrackSizingFunction {
             2 => MinTrackSizingFunction::percent(length.value),
             3 => MinTrackSizingFunction::min_content(),
             4 => MinTrackSizingFunction::max_content(),
+            #[cfg(feature = "calc")]
+            calc::DIM => MinTrackSizingFunction::calc(calc_pointer(length)),
             _ => panic!("unsupported dimension {}", length.dim),
         }
     }
 }
 
+#[cfg(feature = "grid")]
 impl From<c_Length> for MaxTrackSizingFunction {
     fn from(length: c_Length) -> Self {
         match length.dim {
@@ -483,11 +1303,14 @@ impl From<c_Length> for MaxTrackSizingFunction {
             5 => MaxTrackSizingFunction::fit_content(LengthPercentage::length(length.value)),
             6 => MaxTrackSizingFunction::fit_content(LengthPercentage::percent(length.value)),
             7 => MaxTrackSizingFunction::fr(length.value),
+            #[cfg(feature = "calc")]
+            calc::DIM => MaxTrackSizingFunction::calc(calc_pointer(length)),
             _ => panic!("unsupported dimension {}", length.dim),
         }
     }
 }
 
+#[cfg(feature = "grid")]
 impl From<c_GridTrackSizing> for TrackSizingFunction {
     fn from(value: c_GridTrackSizing) -> Self {
         if value.repetition == -2 {
@@ -521,8 +1344,8 @@ impl From<c_Style> for Style {
     fn from(raw: c_Style) -> Self {
         Style {
             display: Display::from_index(raw.display),
-            item_is_table: if raw.item_is_table == 0 { false } else { true },
-            item_is_replaced: if raw.item_is_replaced == 0 { false } else { true },
+            item_is_table: raw.item_is_table != 0,
+            item_is_replaced: raw.item_is_replaced != 0,
             box_sizing: BoxSizing::from_index(raw.box_sizing),
             overflow: taffy::geometry::Point {
                 x: Overflow::from_index(raw.overflow_x),
@@ -531,13 +1354,21 @@ impl From<c_Style> for Style {
             scrollbar_width: raw.scrollbar_width,
             position: Position::from_index(raw.position),
             inset: Rect::from(raw.inset),
+            #[cfg(any(feature = "flexbox", feature = "grid"))]
             align_items: AlignItems::from_index(raw.align_items, raw.has_align_items),
+            #[cfg(feature = "grid")]
             justify_items: AlignItems::from_index(raw.justify_items, raw.has_justify_items),
+            #[cfg(any(feature = "flexbox", feature = "grid"))]
             align_self: AlignItems::from_index(raw.align_self, raw.has_align_self),
+            #[cfg(feature = "grid")]
             justify_self: AlignItems::from_index(raw.justify_self, raw.has_justify_self),
+            #[cfg(any(feature = "flexbox", feature = "grid"))]
             align_content: AlignContent::from_index(raw.align_content, raw.has_align_content),
+            #[cfg(any(feature = "flexbox", feature = "grid"))]
             justify_content: AlignContent::from_index(raw.justify_content, raw.has_justify_content),
+            #[cfg(any(feature = "flexbox", feature = "grid"))]
             gap: Size::from(raw.gap),
+            #[cfg(feature = "block_layout")]
             text_align: TextAlign::from_index(raw.text_align),
             margin: Rect::from(raw.margin),
             border: Rect::from(raw.border),
@@ -546,11 +1377,17 @@ impl From<c_Style> for Style {
             min_size: Size::from(raw.min_size),
             max_size: Size::from(raw.max_size),
             aspect_ratio: if raw.has_aspect_ratio == 0 { None } else { Some(raw.aspect_ratio) },
+            #[cfg(feature = "flexbox")]
             flex_wrap: FlexWrap::from_index(raw.flex_wrap),
+            #[cfg(feature = "flexbox")]
             flex_direction: FlexDirection::from_index(raw.flex_direction),
+            #[cfg(feature = "flexbox")]
             flex_grow: raw.flex_grow,
+            #[cfg(feature = "flexbox")]
             flex_shrink: raw.flex_shrink,
+            #[cfg(feature = "flexbox")]
             flex_basis: Dimension::from(raw.flex_basis),
+            #[cfg(feature = "grid")]
             grid_template_rows: if raw.grid_template_rows_count > 0 && !raw.grid_template_rows.is_null() {
                 unsafe {
                     std::slice::from_raw_parts(raw.grid_template_rows, raw.grid_template_rows_count)
@@ -561,6 +1398,7 @@ impl From<c_Style> for Style {
             } else {
                 vec![]
             },
+            #[cfg(feature = "grid")]
             grid_template_columns: if raw.grid_template_columns_count > 0 && !raw.grid_template_columns.is_null() {
                 unsafe {
                     std::slice::from_raw_parts(raw.grid_template_columns, raw.grid_template_columns_count)
@@ -571,6 +1409,7 @@ impl From<c_Style> for Style {
             } else {
                 vec![]
             },
+            #[cfg(feature = "grid")]
             grid_auto_rows: if raw.grid_auto_rows_count > 0 && !raw.grid_auto_rows.is_null() {
                 unsafe {
                     std::slice::from_raw_parts(raw.grid_auto_rows, raw.grid_auto_rows_count)
@@ -581,6 +1420,7 @@ impl From<c_Style> for Style {
             } else {
                 vec![]
             },
+            #[cfg(feature = "grid")]
             grid_auto_columns: if raw.grid_auto_columns_count > 0 && !raw.grid_auto_columns.is_null() {
                 unsafe {
                     std::slice::from_raw_parts(raw.grid_auto_columns, raw.grid_auto_columns_count)
@@ -591,124 +1431,500 @@ impl From<c_Style> for Style {
             } else {
                 vec![]
             },
+            #[cfg(feature = "grid")]
             grid_auto_flow: GridAutoFlow::from_index(raw.grid_auto_flow),
+            #[cfg(feature = "grid")]
             grid_row: Line::from(raw.grid_row),
+            #[cfg(feature = "grid")]
             grid_column: Line::from(raw.grid_column),
-            ..Default::default()
         }
     }
 }
 
-// NODES
+// STYLE HANDLES
 
-#[no_mangle]
-pub extern "C" fn taffytree_new_leaf(tree: usize, style: *const c_Style) -> u64 {
-    let tree_ptr = tree as *mut TaffyTree<()>;
-    let tree = unsafe { &mut *tree_ptr };
-    let style = unsafe { Style::from(*style) };
-    match tree.new_leaf(style) {
-        Ok(node) => node.into(),
-        Err(_) => 0,
-    }
+/// A style converted for a tree, keeping the unresolved style as well if it uses relative lengths
+#[derive(Clone)]
+struct ConvertedStyle {
+    style: Style,
+    relative: Option<Box<OwnedStyle>>,
 }
 
-#[no_mangle]
-pub extern "C" fn taffytree_add_child(tree: usize, parent: u64, child: u64) -> i32 {
-    let tree_ptr = tree as *mut TaffyTree<()>;
-    let tree = unsafe { &mut *tree_ptr };
-    let parent = NodeId::from(parent);
-    let child = NodeId::from(child);
-    match tree.add_child(parent, child) {
-        Ok(_) => 0,
-        Err(_) => 1,
+impl ConvertedStyle {
+    fn new(style: OwnedStyle, units: &Units) -> Self {
+        let (resolved, relative) = style.resolve(units);
+        ConvertedStyle { style: resolved, relative: relative.then(|| Box::new(style)) }
     }
-}
 
-#[no_mangle]
-pub extern "C" fn taffytree_remove(tree: usize, node: u64) -> i32 {
-    let tree_ptr = tree as *mut TaffyTree<()>;
-    let tree = unsafe { &mut *tree_ptr };
-    let node = NodeId::from(node);
-    match tree.remove(node) {
-        Ok(_) => 0,
-        Err(_) => 1,
+    /// The style converted for a tree with `units`, which only needs a clone if there is nothing to resolve
+    fn for_units(&self, units: &Units) -> Self {
+        match &self.relative {
+            Some(relative) => ConvertedStyle::new(OwnedStyle::clone(relative), units),
+            None => self.clone(),
+        }
     }
 }
 
+/// What a style handle points to: its style, converted with an empty viewport until it is applied to a tree
+struct StyleHandle {
+    /// Tells the nodes set from this handle apart from those of a freed handle at the same address
+    id: u64,
+    style: ConvertedStyle,
+}
+
+static NEXT_STYLE_HANDLE_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Converts `style` once into a handle that can be applied to any number of nodes with
+/// `taffytree_set_style_handle`. Release it with `taffy_style_free`. Returns 0 if `style` is null
+/// or uses a display mode or calc length this build does not support.
+///
+/// Relative lengths are resolved against the tree the handle is applied to. Host trees have no
+/// viewport or font sizes, so viewport units are 0 there and font units use 16.
 #[no_mangle]
-pub extern "C" fn taffytree_clear(tree: usize) {
-    let tree_ptr = tree as *mut TaffyTree<()>;
-    let tree = unsafe { &mut *tree_ptr };
-    tree.clear();
+pub extern "C" fn taffy_style_create(style: *const c_Style) -> usize {
+    let Some(style) = style_arg(style) else {
+        return 0;
+    };
+    let id = NEXT_STYLE_HANDLE_ID.fetch_add(1, Ordering::Relaxed);
+    Box::into_raw(Box::new(StyleHandle { id, style: ConvertedStyle::new(style, &Units::DETACHED) })) as usize
 }
 
+/// Releases a handle created by `taffy_style_create`. Nodes the style was applied to keep it.
 #[no_mangle]
-pub extern "C" fn taffytree_replace_child_at_index(tree: usize, parent: u64, index: usize, child: u64) -> i32 {
-    let tree_ptr = tree as *mut TaffyTree<()>;
-    let tree = unsafe { &mut *tree_ptr };
-    let parent = NodeId::from(parent);
-    let child = NodeId::from(child);
-    match tree.replace_child_at_index(parent, index, child) {
-        Ok(_) => 0,
-        Err(_) => 1,
+pub extern "C" fn taffy_style_free(style: usize) {
+    if style != 0 {
+        let style_ptr = style as *mut StyleHandle;
+        unsafe {
+            drop(Box::from_raw(style_ptr));
+        }
     }
 }
 
+// STYLESHEETS
+
+/// Parses a stylesheet (see `stylesheet.rs` for the supported CSS) from `len` bytes of UTF-8.
+/// Returns 0 if the text is not valid UTF-8, has a syntax error or uses an unsupported property.
+/// The handle can be set on any number of trees and must be released with `taffy_stylesheet_free`.
 #[no_mangle]
-pub extern "C" fn taffytree_remove_child(tree: usize, parent: u64, child: u64) -> i32 {
-    let tree_ptr = tree as *mut TaffyTree<()>;
-    let tree = unsafe { &mut *tree_ptr };
-    let parent = NodeId::from(parent);
-    let child = NodeId::from(child);
-    match tree.remove_child(parent, child) {
-        Ok(_) => 0,
-        Err(_) => 1,
+pub extern "C" fn taffy_stylesheet_parse(css: *const u8, len: usize) -> usize {
+    match str_arg(css, len).and_then(Stylesheet::parse) {
+        Some(sheet) => Rc::into_raw(Rc::new(sheet)) as usize,
+        None => 0,
     }
 }
 
+/// Releases a stylesheet handle. Trees it is set on keep using it until it is replaced.
 #[no_mangle]
-pub extern "C" fn taffytree_remove_child_at_index(tree: usize, parent: u64, index: usize) -> i32 {
-    let tree_ptr = tree as *mut TaffyTree<()>;
-    let tree = unsafe { &mut *tree_ptr };
-    let parent = NodeId::from(parent);
-    match tree.remove_child_at_index(parent, index) {
-        Ok(_) => 0,
-        Err(_) => 1,
+pub extern "C" fn taffy_stylesheet_free(sheet: usize) {
+    if sheet != 0 {
+        unsafe {
+            drop(Rc::from_raw(sheet as *const Stylesheet));
+        }
     }
 }
 
+/// Sets the stylesheet applied to every node with a tag, an id or classes, and re-resolves their
+/// styles. Passing 0 removes the stylesheet, leaving each node with the style the host set on it.
+///
+/// The rules override the style the host set, which only provides the properties no matching rule
+/// declares. This is the reverse of CSS, where an inline style wins over the rules.
 #[no_mangle]
-pub extern "C" fn taffytree_dirty(tree: usize, node: u64) -> i32 {
-    let tree_ptr = tree as *mut TaffyTree<()>;
+pub extern "C" fn taffytree_set_stylesheet(tree: usize, sheet: usize) -> i32 {
+    let tree_ptr = tree as *mut FfiTree;
     let tree = unsafe { &mut *tree_ptr };
-    let node = NodeId::from(node);
-    match tree.dirty(node) {
-        Ok(dirty) => if dirty { 1 } else { 0 },
-        Err(_) => -1,
+    if tree.poisoned() {
+        return 1;
     }
+    tree.journal_settings();
+    tree.stylesheet = (sheet != 0).then(|| unsafe {
+        Rc::increment_strong_count(sheet as *const Stylesheet);
+        Rc::from_raw(sheet as *const Stylesheet)
+    });
+    tree.restyle_all();
+    0
 }
 
-#[no_mangle]
-pub extern "C" fn taffytree_mark_dirty(tree: usize, node: u64) -> i32 {
-    let tree_ptr = tree as *mut TaffyTree<()>;
+/// Applies `update` to the element data of `node` and re-resolves the styles of its subtree
+fn update_element(tree: usize, node: u64, value: *const u8, len: usize, update: impl FnOnce(&mut ElementData, &str)) -> i32 {
+    let tree_ptr = tree as *mut FfiTree;
     let tree = unsafe { &mut *tree_ptr };
     let node = NodeId::from(node);
-    match tree.mark_dirty(node) {
-        Ok(_) => 0,
-        Err(_) => 1,
+    if tree.poisoned() {
+        return 1;
+    }
+    let Some(value) = str_arg(value, len) else {
+        return tree.fail();
+    };
+    if !tree.contains(node) {
+        return tree.fail();
+    }
+    update(tree.element_mut(node), value);
+    tree.restyle(node);
+    0
+}
+
+/// Sets the tag that type selectors match against `node`
+#[no_mangle]
+pub extern "C" fn taffytree_set_tag(tree: usize, node: u64, tag: *const u8, len: usize) -> i32 {
+    update_element(tree, node, tag, len, |element, tag| element.tag = tag.to_owned())
+}
+
+/// Sets the id that `#id` selectors match against `node`
+#[no_mangle]
+pub extern "C" fn taffytree_set_element_id(tree: usize, node: u64, id: *const u8, len: usize) -> i32 {
+    update_element(tree, node, id, len, |element, id| element.id = id.to_owned())
+}
+
+/// Replaces the classes of `node` with the whitespace separated class names in `classes`
+#[no_mangle]
+pub extern "C" fn taffytree_set_classes(tree: usize, node: u64, classes: *const u8, len: usize) -> i32 {
+    update_element(tree, node, classes, len, |element, classes| {
+        element.classes = classes.split_whitespace().map(str::to_owned).collect();
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn taffytree_add_class(tree: usize, node: u64, class: *const u8, len: usize) -> i32 {
+    update_element(tree, node, class, len, |element, class| {
+        if !element.classes.iter().any(|c| c == class) {
+            element.classes.push(class.to_owned());
+        }
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn taffytree_remove_class(tree: usize, node: u64, class: *const u8, len: usize) -> i32 {
+    update_element(tree, node, class, len, |element, class| element.classes.retain(|c| c != class))
+}
+
+// NODES
+
+#[no_mangle]
+pub extern "C" fn taffytree_new_leaf(tree: usize, style: *const c_Style) -> u64 {
+    let tree_ptr = tree as *mut FfiTree;
+    let tree = unsafe { &mut *tree_ptr };
+    if tree.poisoned() {
+        return 0;
+    }
+    let Some(style) = style_arg(style) else {
+        tree.fail();
+        return 0;
+    };
+    let style = ConvertedStyle::new(style, &tree.units(None));
+    match tree.taffy.new_leaf(style.style) {
+        Ok(node) => {
+            tree.insert_node(node, style.relative);
+            node.into()
+        }
+        Err(_) => {
+            tree.fail();
+            0
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn taffytree_new_leaf_with_style_handle(tree: usize, style: usize) -> u64 {
+    let tree_ptr = tree as *mut FfiTree;
+    let tree = unsafe { &mut *tree_ptr };
+    if tree.poisoned() {
+        return 0;
+    }
+    if style == 0 {
+        tree.fail();
+        return 0;
+    }
+    let handle = unsafe { &*(style as *const StyleHandle) };
+    let style = handle.style.for_units(&tree.units(None));
+    match tree.taffy.new_leaf(style.style) {
+        Ok(node) => {
+            tree.insert_node(node, style.relative);
+            if let Some(data) = tree.nodes.get_mut(&node) {
+                data.style_handle = Some(handle.id);
+            }
+            node.into()
+        }
+        Err(_) => {
+            tree.fail();
+            0
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn taffytree_add_child(tree: usize, parent: u64, child: u64) -> i32 {
+    let tree_ptr = tree as *mut FfiTree;
+    let tree = unsafe { &mut *tree_ptr };
+    let parent = NodeId::from(parent);
+    let child = NodeId::from(child);
+    if tree.poisoned() {
+        return 1;
+    }
+    if !tree.can_attach(parent, child) {
+        return tree.fail();
+    }
+    tree.journal_attach(parent, child);
+    match tree.taffy.add_child(parent, child) {
+        Ok(_) => {
+            tree.clear_root_layout(child);
+            tree.restyle_moved(child);
+            0
+        }
+        Err(_) => tree.fail(),
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn taffytree_insert_child_at_index(tree: usize, parent: u64, index: usize, child: u64) -> i32 {
+    let tree_ptr = tree as *mut FfiTree;
+    let tree = unsafe { &mut *tree_ptr };
+    let parent = NodeId::from(parent);
+    let child = NodeId::from(child);
+    if tree.poisoned() {
+        return 1;
+    }
+    if !tree.can_attach(parent, child) {
+        return tree.fail();
+    }
+    tree.journal_attach(parent, child);
+    match tree.taffy.insert_child_at_index(parent, index, child) {
+        Ok(_) => {
+            tree.clear_root_layout(child);
+            tree.restyle_moved(child);
+            0
+        }
+        Err(_) => tree.fail(),
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn taffytree_remove(tree: usize, node: u64) -> i32 {
+    let tree_ptr = tree as *mut FfiTree;
+    let tree = unsafe { &mut *tree_ptr };
+    let node = NodeId::from(node);
+    if tree.poisoned() {
+        return 1;
+    }
+    if !tree.contains(node) {
+        return tree.fail();
+    }
+    let children = tree.taffy.children(node).unwrap_or_default();
+    tree.remove_node(node);
+    for child in children {
+        tree.restyle_moved(child);
+    }
+    0
+}
+
+#[no_mangle]
+pub extern "C" fn taffytree_clear(tree: usize) {
+    let tree_ptr = tree as *mut FfiTree;
+    let tree = unsafe { &mut *tree_ptr };
+    if tree.poisoned() {
+        return;
+    }
+    if tree.batch.is_some() {
+        let nodes: Vec<NodeId> = tree.nodes.keys().copied().collect();
+        for node in nodes {
+            tree.remove_node(node);
+        }
+        return;
+    }
+    tree.taffy.clear();
+    tree.nodes.clear();
+}
+
+#[no_mangle]
+pub extern "C" fn taffytree_replace_child_at_index(tree: usize, parent: u64, index: usize, child: u64) -> i32 {
+    let tree_ptr = tree as *mut FfiTree;
+    let tree = unsafe { &mut *tree_ptr };
+    let parent = NodeId::from(parent);
+    let child = NodeId::from(child);
+    if tree.poisoned() {
+        return 1;
+    }
+    if !tree.can_attach(parent, child) {
+        return tree.fail();
+    }
+    tree.journal_attach(parent, child);
+    match tree.taffy.replace_child_at_index(parent, index, child) {
+        Ok(old_child) => {
+            tree.clear_root_layout(child);
+            tree.restyle_moved(old_child);
+            tree.restyle_moved(child);
+            0
+        }
+        Err(_) => tree.fail(),
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn taffytree_remove_child(tree: usize, parent: u64, child: u64) -> i32 {
+    let tree_ptr = tree as *mut FfiTree;
+    let tree = unsafe { &mut *tree_ptr };
+    let parent = NodeId::from(parent);
+    let child = NodeId::from(child);
+    if tree.poisoned() {
+        return 1;
+    }
+    if !tree.contains(parent) || !tree.contains(child) || tree.taffy.parent(child) != Some(parent) {
+        return tree.fail();
+    }
+    tree.journal(parent);
+    match tree.taffy.remove_child(parent, child) {
+        Ok(_) => {
+            tree.restyle_moved(child);
+            0
+        }
+        Err(_) => tree.fail(),
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn taffytree_remove_child_at_index(tree: usize, parent: u64, index: usize) -> i32 {
+    let tree_ptr = tree as *mut FfiTree;
+    let tree = unsafe { &mut *tree_ptr };
+    let parent = NodeId::from(parent);
+    if tree.poisoned() {
+        return 1;
+    }
+    if !tree.contains(parent) {
+        return tree.fail();
+    }
+    tree.journal(parent);
+    match tree.taffy.remove_child_at_index(parent, index) {
+        Ok(child) => {
+            tree.restyle_moved(child);
+            0
+        }
+        Err(_) => tree.fail(),
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn taffytree_dirty(tree: usize, node: u64) -> i32 {
+    let tree_ptr = tree as *mut FfiTree;
+    let tree = unsafe { &mut *tree_ptr };
+    let node = NodeId::from(node);
+    if !tree.contains(node) {
+        return -1;
+    }
+    match tree.taffy.dirty(node) {
+        Ok(dirty) => if dirty { 1 } else { 0 },
+        Err(_) => -1,
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn taffytree_mark_dirty(tree: usize, node: u64) -> i32 {
+    let tree_ptr = tree as *mut FfiTree;
+    let tree = unsafe { &mut *tree_ptr };
+    let node = NodeId::from(node);
+    if tree.poisoned() {
+        return 1;
+    }
+    if !tree.contains(node) {
+        return tree.fail();
+    }
+    match tree.taffy.mark_dirty(node) {
+        Ok(_) => 0,
+        Err(_) => tree.fail(),
     }
 }
 
 #[no_mangle]
 pub extern "C" fn taffytree_set_style(tree: usize, node: u64, style: *const c_Style) -> i32 {
-    let tree_ptr = tree as *mut TaffyTree<()>;
+    let tree_ptr = tree as *mut FfiTree;
+    let tree = unsafe { &mut *tree_ptr };
+    let node = NodeId::from(node);
+    if tree.poisoned() {
+        return 1;
+    }
+    if !tree.contains(node) {
+        return tree.fail();
+    }
+    let Some(style) = style_arg(style) else {
+        return tree.fail();
+    };
+    let style = ConvertedStyle::new(style, &tree.units(Some(node)));
+    match tree.set_converted_style(node, style) {
+        Ok(_) => 0,
+        Err(_) => tree.fail(),
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn taffytree_set_style_handle(tree: usize, node: u64, style: usize) -> i32 {
+    let tree_ptr = tree as *mut FfiTree;
     let tree = unsafe { &mut *tree_ptr };
     let node = NodeId::from(node);
-    let style = unsafe { Style::from(*style) };
-    match tree.set_style(node, style) {
+    if tree.poisoned() {
+        return 1;
+    }
+    if style == 0 || !tree.contains(node) {
+        return tree.fail();
+    }
+    let handle = unsafe { &*(style as *const StyleHandle) };
+    match tree.set_handle_style(node, handle) {
         Ok(_) => 0,
-        Err(_) => 1,
+        Err(_) => tree.fail(),
+    }
+}
+
+/// Replaces the style of a handle from `taffy_style_create` and restyles every node of `tree` whose
+/// style was last set from it. Returns 1 if `style` is null or unsupported, leaving the handle as it was.
+///
+/// Nodes of other trees keep the previous style until the handle is set on them again or they are
+/// restyled by updating the handle with those trees. Rolling back a batch restores the styles of the
+/// nodes, but not the content of the handle.
+#[no_mangle]
+pub extern "C" fn taffytree_update_style_handle(tree: usize, handle: usize, style: *const c_Style) -> i32 {
+    let tree_ptr = tree as *mut FfiTree;
+    let tree = unsafe { &mut *tree_ptr };
+    if tree.poisoned() {
+        return 1;
+    }
+    if handle == 0 {
+        return tree.fail();
     }
+    let Some(style) = style_arg(style) else {
+        return tree.fail();
+    };
+    let handle = unsafe { &mut *(handle as *mut StyleHandle) };
+    handle.style = ConvertedStyle::new(style, &Units::DETACHED);
+    let nodes: Vec<NodeId> =
+        tree.nodes.iter().filter(|(_, data)| data.style_handle == Some(handle.id)).map(|(node, _)| *node).collect();
+    for node in nodes {
+        if tree.set_handle_style(node, handle).is_err() {
+            return tree.fail();
+        }
+    }
+    0
+}
+
+// COMMANDS
+
+/// Applies a buffer of encoded commands (see `commands.rs` for the format) in a single call.
+/// The ids of the nodes created by the buffer are written to `out_results` in creation order.
+///
+/// The buffer is applied atomically. It is decoded whole first, so a malformed buffer changes nothing,
+/// and if a command then fails on the nodes it refers to, the tree is restored to its previous state.
+/// When called inside an open batch, a failure fails that batch instead.
+///
+/// Returns the number of nodes created, -1 if the header or version is not recognized, or
+/// `-2 - n` if command `n` (counting from 0) is malformed, fails, or has no result slot left.
+#[no_mangle]
+pub extern "C" fn taffytree_apply_commands(tree: usize, buf: *const u8, len: usize, out_results: *mut u64, results_capacity: usize) -> i32 {
+    if tree == 0 || buf.is_null() {
+        return -1;
+    }
+    let bytes = unsafe { std::slice::from_raw_parts(buf, len) };
+    let results = if out_results.is_null() {
+        &mut []
+    } else {
+        unsafe { std::slice::from_raw_parts_mut(out_results, results_capacity) }
+    };
+    commands::apply(tree, bytes, results)
 }
 
 // LAYOUT
@@ -732,6 +1948,146 @@ pub struct c_Layout {
     margin: [f32; 4], // top, right, bottom, left
 }
 
+impl From<c_AvailableSpace> for Size<AvailableSpace> {
+    fn from(available_space: c_AvailableSpace) -> Self {
+        let axis = |length: c_Length| match length.dim {
+            0 => AvailableSpace::Definite(length.value),
+            1 => AvailableSpace::MinContent,
+            2 => AvailableSpace::MaxContent,
+            _ => AvailableSpace::Definite(length.value),
+        };
+        Size { width: axis(available_space.width), height: axis(available_space.height) }
+    }
+}
+
+impl From<c_Layout> for Layout {
+    fn from(layout: c_Layout) -> Self {
+        let rect = |[top, right, bottom, left]: [f32; 4]| Rect { left, right, top, bottom };
+        Layout {
+            order: layout.order as u32,
+            location: taffy::Point { x: layout.location[0], y: layout.location[1] },
+            size: Size { width: layout.size[0], height: layout.size[1] },
+            content_size: Size { width: layout.content_size[0], height: layout.content_size[1] },
+            scrollbar_size: Size { width: layout.scrollbar_size[0], height: layout.scrollbar_size[1] },
+            border: rect(layout.border),
+            padding: rect(layout.padding),
+            margin: rect(layout.margin),
+        }
+    }
+}
+
+/// The inputs of a layout pass over one node, see taffy's `LayoutInput`
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct c_LayoutInput {
+    run_mode: i32, // 0 perform layout, 1 compute size, 2 perform hidden layout
+    sizing_mode: i32, // 0 content size, 1 inherent size
+    axis: i32, // 0 horizontal, 1 vertical, 2 both
+    known_dimensions: [f32; 2], // width, height, NaN if not known
+    parent_size: [f32; 2], // width, height, NaN if not known
+    available_space: c_AvailableSpace,
+}
+
+/// The result of a layout pass over one node, see taffy's `LayoutOutput`
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct c_LayoutOutput {
+    size: [f32; 2], // width, height
+    content_size: [f32; 2], // width, height
+    first_baselines: [f32; 2], // x, y, NaN if the node has no baseline
+}
+
+fn option_to_nan(value: Option<f32>) -> f32 {
+    value.unwrap_or(f32::NAN)
+}
+
+fn nan_to_option(value: f32) -> Option<f32> {
+    (!value.is_nan()).then_some(value)
+}
+
+impl From<LayoutInput> for c_LayoutInput {
+    fn from(inputs: LayoutInput) -> Self {
+        c_LayoutInput {
+            run_mode: match inputs.run_mode {
+                RunMode::PerformLayout => 0,
+                RunMode::ComputeSize => 1,
+                RunMode::PerformHiddenLayout => 2,
+            },
+            sizing_mode: match inputs.sizing_mode {
+                SizingMode::ContentSize => 0,
+                SizingMode::InherentSize => 1,
+            },
+            axis: match inputs.axis {
+                RequestedAxis::Horizontal => 0,
+                RequestedAxis::Vertical => 1,
+                RequestedAxis::Both => 2,
+            },
+            known_dimensions: [option_to_nan(inputs.known_dimensions.width), option_to_nan(inputs.known_dimensions.height)],
+            parent_size: [option_to_nan(inputs.parent_size.width), option_to_nan(inputs.parent_size.height)],
+            available_space: c_AvailableSpace::from(inputs.available_space),
+        }
+    }
+}
+
+impl From<c_LayoutInput> for LayoutInput {
+    fn from(inputs: c_LayoutInput) -> Self {
+        LayoutInput {
+            run_mode: match inputs.run_mode {
+                1 => RunMode::ComputeSize,
+                2 => RunMode::PerformHiddenLayout,
+                _ => RunMode::PerformLayout,
+            },
+            sizing_mode: match inputs.sizing_mode {
+                0 => SizingMode::ContentSize,
+                _ => SizingMode::InherentSize,
+            },
+            axis: match inputs.axis {
+                0 => RequestedAxis::Horizontal,
+                1 => RequestedAxis::Vertical,
+                _ => RequestedAxis::Both,
+            },
+            known_dimensions: Size {
+                width: nan_to_option(inputs.known_dimensions[0]),
+                height: nan_to_option(inputs.known_dimensions[1]),
+            },
+            parent_size: Size { width: nan_to_option(inputs.parent_size[0]), height: nan_to_option(inputs.parent_size[1]) },
+            available_space: Size::from(inputs.available_space),
+            vertical_margins_are_collapsible: Line::FALSE,
+        }
+    }
+}
+
+impl From<LayoutOutput> for c_LayoutOutput {
+    fn from(output: LayoutOutput) -> Self {
+        c_LayoutOutput {
+            size: [output.size.width, output.size.height],
+            content_size: [output.content_size.width, output.content_size.height],
+            first_baselines: [option_to_nan(output.first_baselines.x), option_to_nan(output.first_baselines.y)],
+        }
+    }
+}
+
+impl From<c_LayoutOutput> for LayoutOutput {
+    fn from(output: c_LayoutOutput) -> Self {
+        LayoutOutput::from_sizes_and_baselines(
+            Size { width: output.size[0], height: output.size[1] },
+            Size { width: output.content_size[0], height: output.content_size[1] },
+            taffy::Point { x: nan_to_option(output.first_baselines[0]), y: nan_to_option(output.first_baselines[1]) },
+        )
+    }
+}
+
+impl From<Size<AvailableSpace>> for c_AvailableSpace {
+    fn from(available_space: Size<AvailableSpace>) -> Self {
+        let axis = |space: AvailableSpace| match space {
+            AvailableSpace::Definite(value) => c_Length { dim: 0, value },
+            AvailableSpace::MinContent => c_Length { dim: 1, value: 0.0 },
+            AvailableSpace::MaxContent => c_Length { dim: 2, value: 0.0 },
+        };
+        c_AvailableSpace { width: axis(available_space.width), height: axis(available_space.height) }
+    }
+}
+
 impl From<Layout> for c_Layout {
     fn from(layout: Layout) -> Self {
         c_Layout {
@@ -753,35 +2109,25 @@ pub extern "C" fn taffytree_compute_layout(
     node: u64,
     available_space: c_AvailableSpace,
 ) -> i32 {
-    let tree_ptr = tree as *mut TaffyTree<()>;
+    let tree_ptr = tree as *mut FfiTree;
     let tree = unsafe { &mut *tree_ptr };
     let node = NodeId::from(node);
-
-    let width = match available_space.width.dim {
-        0 => taffy::style::AvailableSpace::Definite(available_space.width.value),
-        1 => taffy::style::AvailableSpace::MinContent,
-        2 => taffy::style::AvailableSpace::MaxContent,
-        _ => taffy::style::AvailableSpace::Definite(available_space.width.value),
-    };
-
-    let height = match available_space.height.dim {
-        0 => taffy::style::AvailableSpace::Definite(available_space.height.value),
-        1 => taffy::style::AvailableSpace::MinContent,
-        2 => taffy::style::AvailableSpace::MaxContent,
-        _ => taffy::style::AvailableSpace::Definite(available_space.height.value),
-    };
-
-    match tree.compute_layout(node, Size { width: width, height: height }) {
-        Ok(_) => 0,
-        Err(_) => 1,
+    if !tree.contains(node) {
+        return 1;
     }
+
+    layout::compute_layout(tree, node, Size::from(available_space));
+    0
 }
 
 #[no_mangle]
 pub extern "C" fn taffytree_layout(tree: usize, node: u64, layout: *mut c_Layout) -> i32 {
-    let tree_ptr = tree as *mut TaffyTree<()>;
+    let tree_ptr = tree as *mut FfiTree;
     let tree = unsafe { &mut *tree_ptr };
     let node = NodeId::from(node);
+    if !tree.contains(node) {
+        return 1;
+    }
     match tree.layout(node) {
         Ok(l) => {
             unsafe {
@@ -793,4 +2139,1364 @@ pub extern "C" fn taffytree_layout(tree: usize, node: u64, layout: *mut c_Layout
     }
 }
 
+// SCROLLING
+
+impl FfiTree {
+    /// How far the content of `node` can be scrolled: the part of its content size that extends past its padding box
+    fn scroll_extent(&self, node: NodeId) -> taffy::Point<f32> {
+        let Ok(layout) = self.layout(node) else {
+            return taffy::Point::ZERO;
+        };
+        // content_size is measured from the border box origin, so compare it against the far edges of the padding box
+        let padding_box_right = layout.size.width - layout.border.right - layout.scrollbar_size.width;
+        let padding_box_bottom = layout.size.height - layout.border.bottom - layout.scrollbar_size.height;
+        taffy::Point {
+            x: (layout.content_size.width - padding_box_right).max(0.0),
+            y: (layout.content_size.height - padding_box_bottom).max(0.0),
+        }
+    }
+
+    /// The scroll offset of `node`, clamped to its current extent in case the layout shrank since it was set
+    fn scroll_offset(&self, node: NodeId) -> taffy::Point<f32> {
+        let offset = self.nodes.get(&node).map_or(taffy::Point::ZERO, |data| data.scroll_offset);
+        let extent = self.scroll_extent(node);
+        taffy::Point { x: offset.x.clamp(0.0, extent.x), y: offset.y.clamp(0.0, extent.y) }
+    }
+
+    /// The location of the border box of `node` relative to its root, after the scroll offsets of its ancestors
+    fn absolute_location(&self, node: NodeId) -> taffy::Point<f32> {
+        let mut location = self.layout(node).map_or(taffy::Point::ZERO, |layout| layout.location);
+        let mut current = node;
+        while let Some(parent) = self.taffy.parent(current) {
+            let parent_location = self.layout(parent).map_or(taffy::Point::ZERO, |layout| layout.location);
+            let scroll = self.scroll_offset(parent);
+            location.x += parent_location.x - scroll.x;
+            location.y += parent_location.y - scroll.y;
+            current = parent;
+        }
+        location
+    }
+
+    /// The topmost node under `point`, which is relative to the parent of `node`. Children are only hit inside
+    /// the padding box of `node` on the axes where it clips its overflow, as in `taffytree_clip_rects`, so
+    /// content that overflows an axis with visible overflow can still be hit outside of `node`.
+    fn hit_test(&self, node: NodeId, point: taffy::Point<f32>) -> Option<NodeId> {
+        let layout = self.layout(node).ok()?;
+        let style = self.taffy.style(node).ok()?;
+        if style.display == Display::None {
+            return None;
+        }
+        let local = taffy::Point { x: point.x - layout.location.x, y: point.y - layout.location.y };
+        let inside = local.x >= 0.0 && local.y >= 0.0 && local.x < layout.size.width && local.y < layout.size.height;
+        let padding_box_right = layout.size.width - layout.border.right - layout.scrollbar_size.width;
+        let padding_box_bottom = layout.size.height - layout.border.bottom - layout.scrollbar_size.height;
+        let in_content_x = style.overflow.x == Overflow::Visible || (local.x >= layout.border.left && local.x < padding_box_right);
+        let in_content_y = style.overflow.y == Overflow::Visible || (local.y >= layout.border.top && local.y < padding_box_bottom);
+
+        if in_content_x && in_content_y {
+            let scroll = self.scroll_offset(node);
+            let content_point = taffy::Point { x: local.x + scroll.x, y: local.y + scroll.y };
+            let mut children = self.taffy.children(node).unwrap_or_default();
+            // Later paint order is on top
+            children.sort_by_key(|&child| std::cmp::Reverse(self.layout(child).map_or(0, |layout| layout.order)));
+            if let Some(hit) = children.into_iter().find_map(|child| self.hit_test(child, content_point)) {
+                return Some(hit);
+            }
+        }
+        inside.then_some(node)
+    }
+}
+
+/// Writes `point` to the optional `out_x` and `out_y`
+fn write_point(point: taffy::Point<f32>, out_x: *mut f32, out_y: *mut f32) {
+    unsafe {
+        if !out_x.is_null() {
+            *out_x = point.x;
+        }
+        if !out_y.is_null() {
+            *out_y = point.y;
+        }
+    }
+}
+
+/// Scrolls the content of `node` to (`x`, `y`), clamped between zero and the extent reported by
+/// `taffytree_scroll_extent` for the last computed layout.
+#[no_mangle]
+pub extern "C" fn taffytree_set_scroll_offset(tree: usize, node: u64, x: f32, y: f32) -> i32 {
+    let tree_ptr = tree as *mut FfiTree;
+    let tree = unsafe { &mut *tree_ptr };
+    let node = NodeId::from(node);
+    if tree.poisoned() {
+        return 1;
+    }
+    if !tree.contains(node) || x.is_nan() || y.is_nan() {
+        return tree.fail();
+    }
+    let extent = tree.scroll_extent(node);
+    if let Some(data) = tree.data_mut(node) {
+        data.scroll_offset = taffy::Point { x: x.clamp(0.0, extent.x), y: y.clamp(0.0, extent.y) };
+    }
+    0
+}
+
+/// Gets the scroll offset of `node`, clamped to the extent of the last computed layout
+#[no_mangle]
+pub extern "C" fn taffytree_scroll_offset(tree: usize, node: u64, out_x: *mut f32, out_y: *mut f32) -> i32 {
+    let tree_ptr = tree as *mut FfiTree;
+    let tree = unsafe { &mut *tree_ptr };
+    let node = NodeId::from(node);
+    if !tree.contains(node) {
+        return 1;
+    }
+    write_point(tree.scroll_offset(node), out_x, out_y);
+    0
+}
 
+/// Gets the largest scroll offset of `node`: how far its content size extends past its padding box
+#[no_mangle]
+pub extern "C" fn taffytree_scroll_extent(tree: usize, node: u64, out_x: *mut f32, out_y: *mut f32) -> i32 {
+    let tree_ptr = tree as *mut FfiTree;
+    let tree = unsafe { &mut *tree_ptr };
+    let node = NodeId::from(node);
+    if !tree.contains(node) {
+        return 1;
+    }
+    write_point(tree.scroll_extent(node), out_x, out_y);
+    0
+}
+
+/// Gets the location of `node` relative to the root of its tree, taking the scroll offsets of its ancestors into account
+#[no_mangle]
+pub extern "C" fn taffytree_absolute_location(tree: usize, node: u64, out_x: *mut f32, out_y: *mut f32) -> i32 {
+    let tree_ptr = tree as *mut FfiTree;
+    let tree = unsafe { &mut *tree_ptr };
+    let node = NodeId::from(node);
+    if !tree.contains(node) {
+        return 1;
+    }
+    write_point(tree.absolute_location(node), out_x, out_y);
+    0
+}
+
+/// Finds the topmost node in the subtree of `root` at (`x`, `y`), in the same coordinates as
+/// `taffytree_absolute_location`. Returns 0 if no node is hit.
+#[no_mangle]
+pub extern "C" fn taffytree_hit_test(tree: usize, root: u64, x: f32, y: f32) -> u64 {
+    let tree_ptr = tree as *mut FfiTree;
+    let tree = unsafe { &mut *tree_ptr };
+    let root = NodeId::from(root);
+    if !tree.contains(root) {
+        return 0;
+    }
+    // Bring the point into the coordinates of the parent of `root`
+    let root_location = tree.absolute_location(root);
+    let root_layout_location = tree.layout(root).map_or(taffy::Point::ZERO, |layout| layout.location);
+    let point = taffy::Point {
+        x: x - root_location.x + root_layout_location.x,
+        y: y - root_location.y + root_layout_location.y,
+    };
+    tree.hit_test(root, point).map_or(0, u64::from)
+}
+
+// CLIPPING
+
+/// The clip rect of a node in the coordinates of `taffytree_absolute_location`. An axis that no ancestor
+/// clips spans from -infinity to infinity, a node that is clipped away entirely has `max <= min` on some axis.
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct c_ClipRect {
+    node: u64,
+    min: [f32; 2], // x, y
+    max: [f32; 2], // x, y
+}
+
+impl FfiTree {
+    /// Narrows `clip` to the padding box of `node`, at `location`, in the axes where `node` clips its overflow
+    fn clip_to_padding_box(&self, node: NodeId, location: taffy::Point<f32>, clip: &mut c_ClipRect) {
+        let (Ok(style), Ok(layout)) = (self.taffy.style(node), self.layout(node)) else {
+            return;
+        };
+        if style.overflow.x != Overflow::Visible {
+            clip.min[0] = clip.min[0].max(location.x + layout.border.left);
+            clip.max[0] = clip.max[0].min(location.x + layout.size.width - layout.border.right - layout.scrollbar_size.width);
+        }
+        if style.overflow.y != Overflow::Visible {
+            clip.min[1] = clip.min[1].max(location.y + layout.border.top);
+            clip.max[1] = clip.max[1].min(location.y + layout.size.height - layout.border.bottom - layout.scrollbar_size.height);
+        }
+    }
+}
+
+/// Computes the clip rect of every node in the subtree of `root`, including `root`, from the padding boxes
+/// of its ancestors that don't have visible overflow. Up to `capacity` rects are written to `out_rects`, parents
+/// before children. Returns the number of nodes in the subtree, or -1 if `root` is not a valid node.
+#[no_mangle]
+pub extern "C" fn taffytree_clip_rects(tree: usize, root: u64, out_rects: *mut c_ClipRect, capacity: usize) -> i32 {
+    let tree_ptr = tree as *mut FfiTree;
+    let tree = unsafe { &mut *tree_ptr };
+    let root = NodeId::from(root);
+    if !tree.contains(root) || (out_rects.is_null() && capacity > 0) {
+        return -1;
+    }
+
+    let mut root_clip = c_ClipRect { node: root.into(), min: [f32::NEG_INFINITY; 2], max: [f32::INFINITY; 2] };
+    let mut ancestor = tree.taffy.parent(root);
+    while let Some(node) = ancestor {
+        tree.clip_to_padding_box(node, tree.absolute_location(node), &mut root_clip);
+        ancestor = tree.taffy.parent(node);
+    }
+
+    let mut count = 0;
+    let mut stack = vec![(root, tree.absolute_location(root), root_clip)];
+    while let Some((node, location, clip)) = stack.pop() {
+        if count < capacity {
+            unsafe {
+                *out_rects.add(count) = c_ClipRect { node: node.into(), ..clip };
+            }
+        }
+        count += 1;
+
+        let mut content_clip = clip;
+        tree.clip_to_padding_box(node, location, &mut content_clip);
+        let scroll = tree.scroll_offset(node);
+        let children = tree.taffy.children(node).unwrap_or_default();
+        // Reversed so that children pop off the stack in order
+        for child in children.into_iter().rev() {
+            let child_location = tree.layout(child).map_or(taffy::Point::ZERO, |layout| layout.location);
+            let child_location = taffy::Point {
+                x: location.x + child_location.x - scroll.x,
+                y: location.y + child_location.y - scroll.y,
+            };
+            stack.push((child, child_location, content_clip));
+        }
+    }
+    count as i32
+}
+
+// RENDERING
+
+/// Renders the computed layout of the subtree of `root` as an SVG image for debugging: the margin, border,
+/// padding and content box of every node, coloured by display mode and labelled with the node's tag, id and
+/// classes, or its node id. Writes up to `cap` bytes of UTF-8 to `buf` and returns the full length of the
+/// SVG, or -1 if `root` is not a valid node.
+#[no_mangle]
+pub extern "C" fn taffytree_render_svg(tree: usize, root: u64, buf: *mut u8, cap: usize) -> i32 {
+    let tree_ptr = tree as *mut FfiTree;
+    let tree = unsafe { &mut *tree_ptr };
+    let root = NodeId::from(root);
+    if !tree.contains(root) {
+        return -1;
+    }
+    write_to_buffer(&render::svg(tree, root), buf, cap)
+}
+
+/// Rasterizes the computed layout of the subtree of `root` into a character grid, for text-based snapshot
+/// tests. Each node's border box is drawn with box-drawing characters at `scale` cells per layout unit and
+/// labelled like `taffytree_render_svg`, trailing spaces are trimmed and every line ends with `\n`.
+/// Writes up to `cap` bytes of UTF-8 to `buf` and returns the full length of the text, or -1 if `root` is
+/// not a valid node, `scale` is not positive, or the grid would exceed 4096 cells in either direction.
+#[no_mangle]
+pub extern "C" fn taffytree_render_ascii(tree: usize, root: u64, scale: f32, buf: *mut u8, cap: usize) -> i32 {
+    let tree_ptr = tree as *mut FfiTree;
+    let tree = unsafe { &mut *tree_ptr };
+    let root = NodeId::from(root);
+    if !tree.contains(root) {
+        return -1;
+    }
+    match render::ascii(tree, root, scale) {
+        Some(text) => write_to_buffer(&text, buf, cap),
+        None => -1,
+    }
+}
+
+// HOST TREES
+
+/// Callbacks through which taffy_ffi lays out a tree owned by the host. Nodes are the host's own
+/// non-zero handles. `parent` is optional and lets `taffy_hosttree_mark_dirty` reach the ancestors
+/// of a node, `measure` is optional and sizes the content of leaves, every other callback is required.
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct c_HostTreeCallbacks {
+    user_data: usize,
+    child_count: Option<extern "C" fn(user_data: usize, node: u64) -> usize>,
+    child_at: Option<extern "C" fn(user_data: usize, node: u64, index: usize) -> u64>,
+    parent: Option<extern "C" fn(user_data: usize, node: u64) -> u64>,
+    /// Returns a handle from `taffy_style_create` that stays alive during the layout, or 0 for the default style.
+    /// The handle is trusted: anything else is undefined behavior. Its viewport units resolve to 0 and its
+    /// font units against a font size of 16, since a host tree has neither a viewport nor font sizes.
+    style: Option<extern "C" fn(user_data: usize, node: u64) -> usize>,
+    /// Receives the final layout of each node
+    set_layout: Option<extern "C" fn(user_data: usize, node: u64, layout: *const c_Layout)>,
+    /// Sizes the content of a node without children, like the callback of `taffytree_set_measure_callback`.
+    /// Without it, leaves are sized from their styles only.
+    measure: Option<
+        extern "C" fn(
+            user_data: usize,
+            node: u64,
+            known_width: f32,
+            known_height: f32,
+            available_space: c_AvailableSpace,
+            output: *mut c_MeasureOutput,
+        ),
+    >,
+}
+
+/// Creates a handle that runs flexbox, grid and block layout over a tree owned by the host, keeping
+/// only the layout caches on the native side. Returns 0 if a required callback is missing.
+#[no_mangle]
+pub extern "C" fn taffy_hosttree_new(callbacks: c_HostTreeCallbacks) -> usize {
+    match HostTree::new(callbacks) {
+        Some(host) => Box::into_raw(Box::new(host)) as usize,
+        None => 0,
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn taffy_hosttree_free(host: usize) {
+    if host != 0 {
+        unsafe {
+            drop(Box::from_raw(host as *mut HostTree));
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn taffy_hosttree_enable_rounding(host: usize) {
+    let host = unsafe { &mut *(host as *mut HostTree) };
+    host.use_rounding = true;
+}
+
+#[no_mangle]
+pub extern "C" fn taffy_hosttree_disable_rounding(host: usize) {
+    let host = unsafe { &mut *(host as *mut HostTree) };
+    host.use_rounding = false;
+}
+
+/// Lays out the host tree below `root` and passes every node's layout to the `set_layout` callback
+#[no_mangle]
+pub extern "C" fn taffy_hosttree_compute_layout(host: usize, root: u64, available_space: c_AvailableSpace) -> i32 {
+    let host = unsafe { &mut *(host as *mut HostTree) };
+    if root == 0 {
+        return 1;
+    }
+    host.compute_layout(NodeId::from(root), Size::from(available_space));
+    0
+}
+
+/// Discards the cached layout of `node`, and of its ancestors when the `parent` callback is set.
+/// Call this whenever the style or the children of a host node change.
+#[no_mangle]
+pub extern "C" fn taffy_hosttree_mark_dirty(host: usize, node: u64) -> i32 {
+    let host = unsafe { &mut *(host as *mut HostTree) };
+    if node == 0 {
+        return 1;
+    }
+    host.mark_dirty(NodeId::from(node));
+    0
+}
+
+/// Releases the cached layout kept for a host node that no longer exists
+#[no_mangle]
+pub extern "C" fn taffy_hosttree_remove_node(host: usize, node: u64) {
+    let host = unsafe { &mut *(host as *mut HostTree) };
+    host.remove_node(NodeId::from(node));
+}
+
+// CUSTOM LAYOUT
+
+/// Registers the function that lays out nodes flagged with `taffytree_set_custom_layout`. It is called
+/// with `user_data`, a context handle for the `taffy_layoutcontext_*` helpers, the node and the layout
+/// inputs, and writes the node's size to `output`. When `inputs.run_mode` is 0 it must also place every
+/// child with `taffy_layoutcontext_set_child_layout`. Passing no callback lays flagged nodes out as leaves.
+#[no_mangle]
+pub extern "C" fn taffytree_set_custom_layout_callback(
+    tree: usize,
+    callback: Option<extern "C" fn(user_data: usize, context: usize, node: u64, inputs: *const c_LayoutInput, output: *mut c_LayoutOutput)>,
+    user_data: usize,
+) {
+    let tree_ptr = tree as *mut FfiTree;
+    let tree = unsafe { &mut *tree_ptr };
+    tree.custom_layout_callback = callback.map(|callback| CustomLayoutCallback { callback, user_data });
+}
+
+/// Sets whether `node` is laid out by the custom layout callback instead of by its display mode
+#[no_mangle]
+pub extern "C" fn taffytree_set_custom_layout(tree: usize, node: u64, enabled: i32) -> i32 {
+    let tree_ptr = tree as *mut FfiTree;
+    let tree = unsafe { &mut *tree_ptr };
+    let node = NodeId::from(node);
+    if tree.poisoned() {
+        return 1;
+    }
+    let Some(data) = tree.data_mut(node) else {
+        return tree.fail();
+    };
+    data.custom_layout = enabled != 0;
+    let _ = tree.taffy.mark_dirty(node);
+    0
+}
+
+#[no_mangle]
+pub extern "C" fn taffy_layoutcontext_child_count(context: usize, node: u64) -> usize {
+    let view = unsafe { &mut *(context as *mut layout::FfiView) };
+    view.child_count_checked(NodeId::from(node))
+}
+
+/// Returns the child of `node` at `index`, or 0 if there is none
+#[no_mangle]
+pub extern "C" fn taffy_layoutcontext_child_at(context: usize, node: u64, index: usize) -> u64 {
+    let view = unsafe { &mut *(context as *mut layout::FfiView) };
+    view.child_at_checked(NodeId::from(node), index).map_or(0, u64::from)
+}
+
+/// Runs the layout of `child` with `inputs` from inside the custom layout callback, writing its size to `output`.
+/// Fails if `child` is not a child of the node being laid out.
+#[no_mangle]
+pub extern "C" fn taffy_layoutcontext_compute_child(context: usize, child: u64, inputs: *const c_LayoutInput, output: *mut c_LayoutOutput) -> i32 {
+    let view = unsafe { &mut *(context as *mut layout::FfiView) };
+    if inputs.is_null() || output.is_null() {
+        return 1;
+    }
+    match view.compute_child_checked(NodeId::from(child), LayoutInput::from(unsafe { *inputs })) {
+        Some(result) => {
+            unsafe {
+                *output = c_LayoutOutput::from(result);
+            }
+            0
+        }
+        None => 1,
+    }
+}
+
+/// Places `child` from inside the custom layout callback. The location is relative to the border box of its parent.
+/// Fails if `child` is not a child of the node being laid out.
+#[no_mangle]
+pub extern "C" fn taffy_layoutcontext_set_child_layout(context: usize, child: u64, layout: *const c_Layout) -> i32 {
+    let view = unsafe { &mut *(context as *mut layout::FfiView) };
+    if layout.is_null() {
+        return 1;
+    }
+    if view.set_layout_checked(NodeId::from(child), Layout::from(unsafe { std::ptr::read(layout) })) { 0 } else { 1 }
+}
+
+// MEASURING
+
+/// The size of a leaf's content, as reported by the measure callback
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct c_MeasureOutput {
+    pub width: f32,
+    pub height: f32,
+    /// The distance from the top of the content box to the first baseline of the content, NaN if it has none.
+    /// Used to line up the leaf when its parent aligns items to their baselines.
+    pub first_baseline: f32,
+}
+
+/// Registers the function that sizes the content of leaves flagged with `taffytree_set_measured`. It is called
+/// with `user_data`, the node, the known width and height (NaN if unknown) and the space available to the
+/// content box, and writes the content size and first baseline to `output`. Passing no callback sizes flagged
+/// leaves from their styles only.
+#[no_mangle]
+pub extern "C" fn taffytree_set_measure_callback(
+    tree: usize,
+    callback: Option<
+        extern "C" fn(
+            user_data: usize,
+            node: u64,
+            known_width: f32,
+            known_height: f32,
+            available_space: c_AvailableSpace,
+            output: *mut c_MeasureOutput,
+        ),
+    >,
+    user_data: usize,
+) {
+    let tree_ptr = tree as *mut FfiTree;
+    let tree = unsafe { &mut *tree_ptr };
+    tree.measure_callback = callback.map(|callback| MeasureCallback { callback, user_data });
+    mark_measured_dirty(tree);
+}
+
+/// A measurement taffy is expected to ask for during `taffytree_compute_layout`
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct c_MeasureQuery {
+    pub node: u64,
+    /// NaN if unknown
+    pub known_width: f32,
+    /// NaN if unknown
+    pub known_height: f32,
+    pub available_space: c_AvailableSpace,
+}
+
+/// Registers a function that answers many measurements at once, to save a call into the host per leaf.
+/// Before each layout pass taffy_ffi predicts the measurements it will need for the dirty leaves flagged with
+/// `taffytree_set_measured` and calls it once with all of them, writing one output per query. Measurements
+/// it did not predict are asked for one at a time, through the single measure callback if there is one and
+/// otherwise through this function with a `count` of 1.
+#[no_mangle]
+pub extern "C" fn taffytree_set_batch_measure_callback(
+    tree: usize,
+    callback: Option<extern "C" fn(user_data: usize, queries: *const c_MeasureQuery, outputs: *mut c_MeasureOutput, count: usize)>,
+    user_data: usize,
+) {
+    let tree_ptr = tree as *mut FfiTree;
+    let tree = unsafe { &mut *tree_ptr };
+    tree.batch_measure_callback = callback.map(|callback| BatchMeasureCallback { callback, user_data });
+    mark_measured_dirty(tree);
+}
+
+fn mark_measured_dirty(tree: &mut FfiTree) {
+    for (node, data) in &tree.nodes {
+        if data.measured {
+            let _ = tree.taffy.mark_dirty(*node);
+        }
+    }
+}
+
+/// Sets whether the content of `node` is sized by the measure callbacks. Only used while `node` has no children.
+#[no_mangle]
+pub extern "C" fn taffytree_set_measured(tree: usize, node: u64, enabled: i32) -> i32 {
+    let tree_ptr = tree as *mut FfiTree;
+    let tree = unsafe { &mut *tree_ptr };
+    let node = NodeId::from(node);
+    if tree.poisoned() {
+        return 1;
+    }
+    let Some(data) = tree.data_mut(node) else {
+        return tree.fail();
+    };
+    data.measured = enabled != 0;
+    let _ = tree.taffy.mark_dirty(node);
+    0
+}
+
+// TEXT
+
+/// Loads a TrueType or OpenType font from `len` bytes, which are copied. Returns 0 if the data is not a font.
+/// The handle can be used for any number of text leaves and must be released with `taffy_font_free`.
+#[no_mangle]
+pub extern "C" fn taffy_font_load(data: *const u8, len: usize) -> usize {
+    if data.is_null() {
+        return 0;
+    }
+    let bytes = unsafe { std::slice::from_raw_parts(data, len) }.to_vec();
+    match ab_glyph::FontVec::try_from_vec(bytes) {
+        Ok(font) => Rc::into_raw(Rc::new(font)) as usize,
+        Err(_) => 0,
+    }
+}
+
+/// Releases a font handle. Text leaves using it keep it alive until their text is replaced.
+#[no_mangle]
+pub extern "C" fn taffy_font_free(font: usize) {
+    if font != 0 {
+        unsafe {
+            drop(Rc::from_raw(font as *const ab_glyph::FontVec));
+        }
+    }
+}
+
+/// Makes `node` a text leaf showing `len` bytes of UTF-8 in `font` at `font_size` pixels. Its content is
+/// wrapped at word boundaries to fit the available width during layout. `line_height` is in pixels, 0 uses
+/// the metrics of the font. Passing a `font` of 0 removes the text. Only used while `node` has no children.
+#[no_mangle]
+pub extern "C" fn taffytree_set_text(
+    tree: usize,
+    node: u64,
+    font: usize,
+    text: *const u8,
+    len: usize,
+    font_size: f32,
+    line_height: f32,
+) -> i32 {
+    let tree_ptr = tree as *mut FfiTree;
+    let tree = unsafe { &mut *tree_ptr };
+    let node = NodeId::from(node);
+    if tree.poisoned() {
+        return 1;
+    }
+    let text = if font == 0 {
+        None
+    } else {
+        let Some(text) = str_arg(text, len) else {
+            return tree.fail();
+        };
+        if !(font_size.is_finite() && font_size > 0.0 && line_height.is_finite() && line_height >= 0.0) {
+            return tree.fail();
+        }
+        let font = unsafe {
+            Rc::increment_strong_count(font as *const ab_glyph::FontVec);
+            Rc::from_raw(font as *const ab_glyph::FontVec)
+        };
+        Some(Box::new(Text { font, text: text.to_owned(), font_size, line_height }))
+    };
+    let Some(data) = tree.data_mut(node) else {
+        return tree.fail();
+    };
+    data.text = text;
+    let _ = tree.taffy.mark_dirty(node);
+    0
+}
+
+// STATS
+
+/// Counters from the last `taffytree_compute_layout`
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+pub struct c_LayoutStats {
+    /// The number of distinct nodes laid out
+    pub nodes_visited: u64,
+    /// The number of times the layout of a node was asked for, across all nodes
+    pub layout_calls: u64,
+    pub cache_hits: u64,
+    pub cache_misses: u64,
+    /// Calls into the host's measure callbacks. A batch counts once.
+    pub measure_calls: u64,
+    /// Measurements of leaves flagged with `taffytree_set_measured`, predicted or not
+    pub measurements: u64,
+    pub total_ms: f64,
+    /// The time spent in each algorithm, not counting the time spent laying out children
+    pub flexbox_ms: f64,
+    pub grid_ms: f64,
+    pub block_ms: f64,
+    pub leaf_ms: f64,
+    pub custom_ms: f64,
+}
+
+/// Counters for one node from the last `taffytree_compute_layout`
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+pub struct c_NodeStats {
+    pub layout_calls: u64,
+    pub cache_hits: u64,
+    pub cache_misses: u64,
+    pub measurements: u64,
+    /// The time spent laying out the node, including its subtree
+    pub total_ms: f64,
+}
+
+/// Writes the counters from the last `taffytree_compute_layout` to `out`
+#[no_mangle]
+pub extern "C" fn taffytree_get_stats(tree: usize, out: *mut c_LayoutStats) -> i32 {
+    let tree_ptr = tree as *mut FfiTree;
+    let tree = unsafe { &mut *tree_ptr };
+    if out.is_null() {
+        return 1;
+    }
+    unsafe {
+        *out = tree.stats;
+    }
+    0
+}
+
+/// Writes the counters for `node` from the last `taffytree_compute_layout` to `out`, all zero if it was not laid out
+#[no_mangle]
+pub extern "C" fn taffytree_get_node_stats(tree: usize, node: u64, out: *mut c_NodeStats) -> i32 {
+    let tree_ptr = tree as *mut FfiTree;
+    let tree = unsafe { &mut *tree_ptr };
+    let node = NodeId::from(node);
+    if out.is_null() || !tree.contains(node) {
+        return 1;
+    }
+    unsafe {
+        *out = tree.node_stats.get(&node).copied().unwrap_or_default();
+    }
+    0
+}
+
+/// Zeroes the counters of the tree and of every node until the next `taffytree_compute_layout`
+#[no_mangle]
+pub extern "C" fn taffytree_reset_stats(tree: usize) -> i32 {
+    let tree_ptr = tree as *mut FfiTree;
+    let tree = unsafe { &mut *tree_ptr };
+    tree.stats = c_LayoutStats::default();
+    tree.node_stats.clear();
+    0
+}
+
+// LOGGING
+
+/// Registers a function that receives a log of each node laid out by `taffytree_compute_layout`. It is called
+/// once the pass is over, for every line in order, with `user_data`, a severity (0 for the inputs a node is
+/// about to be laid out with, 1 for how it was laid out, 2 for likely mistakes), the node and `len` bytes of UTF-8.
+/// Returns 1 if taffy_ffi was built without the `debug` feature, in which case nothing is logged.
+#[no_mangle]
+pub extern "C" fn taffytree_set_logger(
+    tree: usize,
+    callback: Option<extern "C" fn(user_data: usize, severity: i32, node: u64, message: *const u8, len: usize)>,
+    user_data: usize,
+) -> i32 {
+    let tree_ptr = tree as *mut FfiTree;
+    let tree = unsafe { &mut *tree_ptr };
+    if cfg!(not(feature = "debug")) {
+        return 1;
+    }
+    tree.logger = callback.map(|callback| Logger { callback, user_data });
+    0
+}
+
+// MEMORY
+
+/// An estimate of the memory held by a tree, in bytes
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+pub struct c_MemoryUsage {
+    /// The nodes of the TaffyTree, with their layouts and their parent and child links, and the vacant slots left by
+    /// removed nodes
+    pub nodes: u64,
+    /// Styles, including their grid track lists and the base styles of nodes with element data
+    pub styles: u64,
+    /// The layout cache of each node
+    pub caches: u64,
+    /// What taffy_ffi stores for each node, such as element data, text and counters, and the undo log of an open batch
+    pub context: u64,
+    pub total: u64,
+}
+
+/// Writes an estimate of the memory held by `tree` to `out`. taffy's storage is estimated from the number of
+/// slots it has grown to, which includes those of removed nodes, taffy_ffi's own storage from its capacities.
+#[no_mangle]
+pub extern "C" fn taffytree_memory_usage(tree: usize, out: *mut c_MemoryUsage) -> i32 {
+    let tree_ptr = tree as *mut FfiTree;
+    let tree = unsafe { &mut *tree_ptr };
+    if out.is_null() {
+        return 1;
+    }
+    unsafe {
+        *out = memory::usage(tree);
+    }
+    0
+}
+
+/// Releases the spare capacity of the vectors and maps `tree` stores its nodes in. Node ids stay valid, so the
+/// slots taffy keeps for removed nodes are not released: they are only reused by new nodes.
+#[no_mangle]
+pub extern "C" fn taffytree_shrink_to_fit(tree: usize) {
+    let tree_ptr = tree as *mut FfiTree;
+    let tree = unsafe { &mut *tree_ptr };
+    memory::shrink_to_fit(tree);
+}
+
+// ABI
+
+/// The version of the exports and `#[repr(C)]` types of taffy_ffi. Bumped whenever any of them changes in a way
+/// that bindings compiled against an earlier version would misread, which includes any change to the exports
+/// listed in `c_Api` or to the types listed in `c_StructSizes`.
+pub const TAFFY_FFI_ABI_VERSION: u32 = 2;
+
+/// Returns `TAFFY_FFI_ABI_VERSION`, so that bindings can refuse to use a library they were not generated for
+#[no_mangle]
+pub extern "C" fn taffy_ffi_abi_version() -> u32 {
+    TAFFY_FFI_ABI_VERSION
+}
+
+/// The size and alignment of a `#[repr(C)]` type, in bytes
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct c_TypeLayout {
+    pub size: u32,
+    pub align: u32,
+}
+
+impl c_TypeLayout {
+    const fn of<T>() -> Self {
+        c_TypeLayout { size: std::mem::size_of::<T>() as u32, align: std::mem::align_of::<T>() as u32 }
+    }
+}
+
+/// The layout of every `#[repr(C)]` type taffy_ffi exchanges with the host
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct c_StructSizes {
+    pub length: c_TypeLayout,
+    pub size: c_TypeLayout,
+    pub rect: c_TypeLayout,
+    pub grid_index: c_TypeLayout,
+    pub grid_placement: c_TypeLayout,
+    pub grid_track_size: c_TypeLayout,
+    pub grid_track_sizing: c_TypeLayout,
+    pub style: c_TypeLayout,
+    pub available_space: c_TypeLayout,
+    pub layout: c_TypeLayout,
+    pub layout_input: c_TypeLayout,
+    pub layout_output: c_TypeLayout,
+    pub clip_rect: c_TypeLayout,
+    pub host_tree_callbacks: c_TypeLayout,
+    pub measure_output: c_TypeLayout,
+    pub measure_query: c_TypeLayout,
+    pub layout_stats: c_TypeLayout,
+    pub node_stats: c_TypeLayout,
+    pub memory_usage: c_TypeLayout,
+    pub api: c_TypeLayout,
+    pub animated_rect: c_TypeLayout,
+}
+
+/// Writes the size and alignment of every `#[repr(C)]` type to `out`, so that bindings can check their
+/// mirrored definitions against the library before using it. `len` is the size of `c_StructSizes` the bindings
+/// were built with. Only the first `len` bytes are written, so bindings with fewer types are not overrun, and
+/// the types they know past what this library writes are left as they were.
+#[no_mangle]
+pub extern "C" fn taffy_ffi_struct_sizes(out: *mut c_StructSizes, len: usize) -> i32 {
+    if out.is_null() {
+        return 1;
+    }
+    let sizes = c_StructSizes {
+        length: c_TypeLayout::of::<c_Length>(),
+        size: c_TypeLayout::of::<c_Size>(),
+        rect: c_TypeLayout::of::<c_Rect>(),
+        grid_index: c_TypeLayout::of::<c_GridIndex>(),
+        grid_placement: c_TypeLayout::of::<c_GridPlacement>(),
+        grid_track_size: c_TypeLayout::of::<c_GridTrackSize>(),
+        grid_track_sizing: c_TypeLayout::of::<c_GridTrackSizing>(),
+        style: c_TypeLayout::of::<c_Style>(),
+        available_space: c_TypeLayout::of::<c_AvailableSpace>(),
+        layout: c_TypeLayout::of::<c_Layout>(),
+        layout_input: c_TypeLayout::of::<c_LayoutInput>(),
+        layout_output: c_TypeLayout::of::<c_LayoutOutput>(),
+        clip_rect: c_TypeLayout::of::<c_ClipRect>(),
+        host_tree_callbacks: c_TypeLayout::of::<c_HostTreeCallbacks>(),
+        measure_output: c_TypeLayout::of::<c_MeasureOutput>(),
+        measure_query: c_TypeLayout::of::<c_MeasureQuery>(),
+        layout_stats: c_TypeLayout::of::<c_LayoutStats>(),
+        node_stats: c_TypeLayout::of::<c_NodeStats>(),
+        memory_usage: c_TypeLayout::of::<c_MemoryUsage>(),
+        api: c_TypeLayout::of::<c_Api>(),
+        animated_rect: c_TypeLayout::of::<c_AnimatedRect>(),
+    };
+    let len = len.min(std::mem::size_of::<c_StructSizes>());
+    unsafe {
+        std::ptr::copy_nonoverlapping(&sizes as *const c_StructSizes as *const u8, out as *mut u8, len);
+    }
+    0
+}
+
+/// Every export of taffy_ffi, in the order they are declared, for hosts that load the library dynamically
+#[repr(C)]
+pub struct c_Api {
+    /// `TAFFY_FFI_ABI_VERSION`, checked before anything else in the table is used
+    pub abi_version: u32,
+    pub taffytree_new: extern "C" fn() -> usize,
+    pub taffytree_with_capacity: extern "C" fn(capacity: usize) -> usize,
+    pub taffytree_new_with_children: extern "C" fn(tree: usize, style: *const c_Style, children: *const u64, children_len: usize) -> u64,
+    pub taffytree_free: extern "C" fn(tree: usize),
+    pub taffytree_enable_rounding: extern "C" fn(tree: usize),
+    pub taffytree_disable_rounding: extern "C" fn(tree: usize),
+    pub taffytree_begin_batch: extern "C" fn(tree: usize) -> i32,
+    pub taffytree_commit_batch: extern "C" fn(tree: usize) -> i32,
+    pub taffytree_rollback_batch: extern "C" fn(tree: usize) -> i32,
+    pub taffy_style_create: extern "C" fn(style: *const c_Style) -> usize,
+    pub taffy_style_free: extern "C" fn(style: usize),
+    pub taffy_stylesheet_parse: extern "C" fn(css: *const u8, len: usize) -> usize,
+    pub taffy_stylesheet_free: extern "C" fn(sheet: usize),
+    pub taffytree_set_stylesheet: extern "C" fn(tree: usize, sheet: usize) -> i32,
+    pub taffytree_set_tag: extern "C" fn(tree: usize, node: u64, tag: *const u8, len: usize) -> i32,
+    pub taffytree_set_element_id: extern "C" fn(tree: usize, node: u64, id: *const u8, len: usize) -> i32,
+    pub taffytree_set_classes: extern "C" fn(tree: usize, node: u64, classes: *const u8, len: usize) -> i32,
+    pub taffytree_add_class: extern "C" fn(tree: usize, node: u64, class: *const u8, len: usize) -> i32,
+    pub taffytree_remove_class: extern "C" fn(tree: usize, node: u64, class: *const u8, len: usize) -> i32,
+    pub taffytree_new_leaf: extern "C" fn(tree: usize, style: *const c_Style) -> u64,
+    pub taffytree_new_leaf_with_style_handle: extern "C" fn(tree: usize, style: usize) -> u64,
+    pub taffytree_add_child: extern "C" fn(tree: usize, parent: u64, child: u64) -> i32,
+    pub taffytree_insert_child_at_index: extern "C" fn(tree: usize, parent: u64, index: usize, child: u64) -> i32,
+    pub taffytree_remove: extern "C" fn(tree: usize, node: u64) -> i32,
+    pub taffytree_clear: extern "C" fn(tree: usize),
+    pub taffytree_replace_child_at_index: extern "C" fn(tree: usize, parent: u64, index: usize, child: u64) -> i32,
+    pub taffytree_remove_child: extern "C" fn(tree: usize, parent: u64, child: u64) -> i32,
+    pub taffytree_remove_child_at_index: extern "C" fn(tree: usize, parent: u64, index: usize) -> i32,
+    pub taffytree_dirty: extern "C" fn(tree: usize, node: u64) -> i32,
+    pub taffytree_mark_dirty: extern "C" fn(tree: usize, node: u64) -> i32,
+    pub taffytree_set_style: extern "C" fn(tree: usize, node: u64, style: *const c_Style) -> i32,
+    pub taffytree_set_style_handle: extern "C" fn(tree: usize, node: u64, style: usize) -> i32,
+    pub taffytree_update_style_handle: extern "C" fn(tree: usize, handle: usize, style: *const c_Style) -> i32,
+    pub taffytree_apply_commands: extern "C" fn(tree: usize, buf: *const u8, len: usize, out_results: *mut u64, results_capacity: usize) -> i32,
+    pub taffytree_compute_layout: extern "C" fn(tree: usize, node: u64, available_space: c_AvailableSpace) -> i32,
+    pub taffytree_layout: extern "C" fn(tree: usize, node: u64, layout: *mut c_Layout) -> i32,
+    pub taffytree_set_scroll_offset: extern "C" fn(tree: usize, node: u64, x: f32, y: f32) -> i32,
+    pub taffytree_scroll_offset: extern "C" fn(tree: usize, node: u64, out_x: *mut f32, out_y: *mut f32) -> i32,
+    pub taffytree_scroll_extent: extern "C" fn(tree: usize, node: u64, out_x: *mut f32, out_y: *mut f32) -> i32,
+    pub taffytree_absolute_location: extern "C" fn(tree: usize, node: u64, out_x: *mut f32, out_y: *mut f32) -> i32,
+    pub taffytree_hit_test: extern "C" fn(tree: usize, root: u64, x: f32, y: f32) -> u64,
+    pub taffytree_clip_rects: extern "C" fn(tree: usize, root: u64, out_rects: *mut c_ClipRect, capacity: usize) -> i32,
+    pub taffytree_render_svg: extern "C" fn(tree: usize, root: u64, buf: *mut u8, cap: usize) -> i32,
+    pub taffytree_render_ascii: extern "C" fn(tree: usize, root: u64, scale: f32, buf: *mut u8, cap: usize) -> i32,
+    pub taffy_hosttree_new: extern "C" fn(callbacks: c_HostTreeCallbacks) -> usize,
+    pub taffy_hosttree_free: extern "C" fn(host: usize),
+    pub taffy_hosttree_enable_rounding: extern "C" fn(host: usize),
+    pub taffy_hosttree_disable_rounding: extern "C" fn(host: usize),
+    pub taffy_hosttree_compute_layout: extern "C" fn(host: usize, root: u64, available_space: c_AvailableSpace) -> i32,
+    pub taffy_hosttree_mark_dirty: extern "C" fn(host: usize, node: u64) -> i32,
+    pub taffy_hosttree_remove_node: extern "C" fn(host: usize, node: u64),
+    pub taffytree_set_custom_layout_callback: extern "C" fn(tree: usize, callback: Option<extern "C" fn(user_data: usize, context: usize, node: u64, inputs: *const c_LayoutInput, output: *mut c_LayoutOutput)>, user_data: usize),
+    pub taffytree_set_custom_layout: extern "C" fn(tree: usize, node: u64, enabled: i32) -> i32,
+    pub taffy_layoutcontext_child_count: extern "C" fn(context: usize, node: u64) -> usize,
+    pub taffy_layoutcontext_child_at: extern "C" fn(context: usize, node: u64, index: usize) -> u64,
+    pub taffy_layoutcontext_compute_child: extern "C" fn(context: usize, child: u64, inputs: *const c_LayoutInput, output: *mut c_LayoutOutput) -> i32,
+    pub taffy_layoutcontext_set_child_layout: extern "C" fn(context: usize, child: u64, layout: *const c_Layout) -> i32,
+    pub taffytree_set_measure_callback: extern "C" fn(tree: usize, callback: Option<extern "C" fn(user_data: usize, node: u64, known_width: f32, known_height: f32, available_space: c_AvailableSpace, output: *mut c_MeasureOutput)>, user_data: usize),
+    pub taffytree_set_batch_measure_callback: extern "C" fn(tree: usize, callback: Option<extern "C" fn(user_data: usize, queries: *const c_MeasureQuery, outputs: *mut c_MeasureOutput, count: usize)>, user_data: usize),
+    pub taffytree_set_measured: extern "C" fn(tree: usize, node: u64, enabled: i32) -> i32,
+    pub taffy_font_load: extern "C" fn(data: *const u8, len: usize) -> usize,
+    pub taffy_font_free: extern "C" fn(font: usize),
+    pub taffytree_set_text: extern "C" fn(tree: usize, node: u64, font: usize, text: *const u8, len: usize, font_size: f32, line_height: f32) -> i32,
+    pub taffytree_get_stats: extern "C" fn(tree: usize, out: *mut c_LayoutStats) -> i32,
+    pub taffytree_get_node_stats: extern "C" fn(tree: usize, node: u64, out: *mut c_NodeStats) -> i32,
+    pub taffytree_reset_stats: extern "C" fn(tree: usize) -> i32,
+    pub taffytree_set_logger: extern "C" fn(tree: usize, callback: Option<extern "C" fn(user_data: usize, severity: i32, node: u64, message: *const u8, len: usize)>, user_data: usize) -> i32,
+    pub taffytree_memory_usage: extern "C" fn(tree: usize, out: *mut c_MemoryUsage) -> i32,
+    pub taffytree_shrink_to_fit: extern "C" fn(tree: usize),
+    pub taffy_ffi_abi_version: extern "C" fn() -> u32,
+    pub taffy_ffi_struct_sizes: extern "C" fn(out: *mut c_StructSizes, len: usize) -> i32,
+    pub taffy_ffi_supported_features: extern "C" fn() -> u32,
+    pub taffytree_set_viewport: extern "C" fn(tree: usize, width: f32, height: f32) -> i32,
+    pub taffytree_set_root_font_size: extern "C" fn(tree: usize, font_size: f32) -> i32,
+    pub taffytree_set_font_size: extern "C" fn(tree: usize, node: u64, font_size: f32) -> i32,
+    pub taffytree_font_size: extern "C" fn(tree: usize, node: u64, out: *mut f32) -> i32,
+    pub taffy_calc_parse: extern "C" fn(text: *const u8, len: usize) -> u32,
+    pub taffy_style_lerp: extern "C" fn(a: *const c_Style, b: *const c_Style, t: f32, out: *mut c_Style) -> i32,
+    pub taffy_style_lerp_free: extern "C" fn(style: *mut c_Style),
+    pub taffytree_snapshot_layouts: extern "C" fn(tree: usize, root: u64) -> usize,
+    pub taffy_layout_snapshot_free: extern "C" fn(snapshot: usize),
+    pub taffytree_interpolate_layouts:
+        extern "C" fn(tree: usize, root: u64, snapshot: usize, t: f32, out_rects: *mut c_AnimatedRect, capacity: usize) -> i32,
+    pub taffytree_compute_layout_fit: extern "C" fn(
+        tree: usize,
+        root: u64,
+        min_width: f32,
+        min_height: f32,
+        max_width: f32,
+        max_height: f32,
+        out_width: *mut f32,
+        out_height: *mut f32,
+    ) -> i32,
+    pub taffytree_compute_layouts: extern "C" fn(
+        tree: usize,
+        roots: *const u64,
+        available_spaces: *const c_AvailableSpace,
+        count: usize,
+        out_results: *mut i32,
+    ) -> i32,
+}
+
+static API: c_Api = c_Api {
+    abi_version: TAFFY_FFI_ABI_VERSION,
+    taffytree_new,
+    taffytree_with_capacity,
+    taffytree_new_with_children,
+    taffytree_free,
+    taffytree_enable_rounding,
+    taffytree_disable_rounding,
+    taffytree_begin_batch,
+    taffytree_commit_batch,
+    taffytree_rollback_batch,
+    taffy_style_create,
+    taffy_style_free,
+    taffy_stylesheet_parse,
+    taffy_stylesheet_free,
+    taffytree_set_stylesheet,
+    taffytree_set_tag,
+    taffytree_set_element_id,
+    taffytree_set_classes,
+    taffytree_add_class,
+    taffytree_remove_class,
+    taffytree_new_leaf,
+    taffytree_new_leaf_with_style_handle,
+    taffytree_add_child,
+    taffytree_insert_child_at_index,
+    taffytree_remove,
+    taffytree_clear,
+    taffytree_replace_child_at_index,
+    taffytree_remove_child,
+    taffytree_remove_child_at_index,
+    taffytree_dirty,
+    taffytree_mark_dirty,
+    taffytree_set_style,
+    taffytree_set_style_handle,
+    taffytree_update_style_handle,
+    taffytree_apply_commands,
+    taffytree_compute_layout,
+    taffytree_layout,
+    taffytree_set_scroll_offset,
+    taffytree_scroll_offset,
+    taffytree_scroll_extent,
+    taffytree_absolute_location,
+    taffytree_hit_test,
+    taffytree_clip_rects,
+    taffytree_render_svg,
+    taffytree_render_ascii,
+    taffy_hosttree_new,
+    taffy_hosttree_free,
+    taffy_hosttree_enable_rounding,
+    taffy_hosttree_disable_rounding,
+    taffy_hosttree_compute_layout,
+    taffy_hosttree_mark_dirty,
+    taffy_hosttree_remove_node,
+    taffytree_set_custom_layout_callback,
+    taffytree_set_custom_layout,
+    taffy_layoutcontext_child_count,
+    taffy_layoutcontext_child_at,
+    taffy_layoutcontext_compute_child,
+    taffy_layoutcontext_set_child_layout,
+    taffytree_set_measure_callback,
+    taffytree_set_batch_measure_callback,
+    taffytree_set_measured,
+    taffy_font_load,
+    taffy_font_free,
+    taffytree_set_text,
+    taffytree_get_stats,
+    taffytree_get_node_stats,
+    taffytree_reset_stats,
+    taffytree_set_logger,
+    taffytree_memory_usage,
+    taffytree_shrink_to_fit,
+    taffy_ffi_abi_version,
+    taffy_ffi_struct_sizes,
+    taffy_ffi_supported_features,
+    taffytree_set_viewport,
+    taffytree_set_root_font_size,
+    taffytree_set_font_size,
+    taffytree_font_size,
+    taffy_calc_parse,
+    taffy_style_lerp,
+    taffy_style_lerp_free,
+    taffytree_snapshot_layouts,
+    taffy_layout_snapshot_free,
+    taffytree_interpolate_layouts,
+    taffytree_compute_layout_fit,
+    taffytree_compute_layouts,
+};
+
+/// Returns the table of every export. It lives as long as the library is loaded.
+#[no_mangle]
+pub extern "C" fn taffy_ffi_get_api() -> *const c_Api {
+    &API
+}
+
+// FEATURES
+
+/// `display: flex` and the flexbox properties
+pub const TAFFY_FFI_FEATURE_FLEXBOX: u32 = 1 << 0;
+/// `display: grid` and the grid properties
+pub const TAFFY_FFI_FEATURE_GRID: u32 = 1 << 1;
+/// `display: block` and `text_align`
+pub const TAFFY_FFI_FEATURE_BLOCK_LAYOUT: u32 = 1 << 2;
+/// `calc()` lengths, see `taffy_calc_parse`
+pub const TAFFY_FFI_FEATURE_CALC: u32 = 1 << 3;
+pub const TAFFY_FFI_FEATURE_SERDE: u32 = 1 << 4;
+/// The logger set with `taffytree_set_logger`
+pub const TAFFY_FFI_FEATURE_DEBUG: u32 = 1 << 5;
+
+/// Returns the `TAFFY_FFI_FEATURE_*` flags of the cargo features this library was built with. Styles that use
+/// a display mode of a missing algorithm are rejected, and so are stylesheet and command buffer properties
+/// that only apply to it.
+#[no_mangle]
+pub extern "C" fn taffy_ffi_supported_features() -> u32 {
+    let features = [
+        (cfg!(feature = "flexbox"), TAFFY_FFI_FEATURE_FLEXBOX),
+        (cfg!(feature = "grid"), TAFFY_FFI_FEATURE_GRID),
+        (cfg!(feature = "block_layout"), TAFFY_FFI_FEATURE_BLOCK_LAYOUT),
+        (cfg!(feature = "calc"), TAFFY_FFI_FEATURE_CALC),
+        (cfg!(feature = "serde"), TAFFY_FFI_FEATURE_SERDE),
+        (cfg!(feature = "debug"), TAFFY_FFI_FEATURE_DEBUG),
+    ];
+    features.iter().filter(|(enabled, _)| *enabled).fold(0, |flags, (_, flag)| flags | flag)
+}
+
+/// Whether `display` is the index of a display mode whose algorithm this library was built with
+fn supports_display(display: i32) -> bool {
+    let required = match display {
+        0 => 0,
+        1 => TAFFY_FFI_FEATURE_FLEXBOX,
+        2 => TAFFY_FFI_FEATURE_GRID,
+        3 => TAFFY_FFI_FEATURE_BLOCK_LAYOUT,
+        _ => return false,
+    };
+    taffy_ffi_supported_features() & required == required
+}
+
+// UNITS
+
+/// Sets the size that viewport units (see `units.rs`) are relative to, initially 0 x 0. Nodes whose style
+/// uses them are resolved again and marked dirty if their style changed.
+#[no_mangle]
+pub extern "C" fn taffytree_set_viewport(tree: usize, width: f32, height: f32) -> i32 {
+    let tree_ptr = tree as *mut FfiTree;
+    let tree = unsafe { &mut *tree_ptr };
+    if tree.poisoned() {
+        return 1;
+    }
+    let viewport = Size { width, height };
+    if tree.viewport != viewport {
+        tree.journal_settings();
+        tree.viewport = viewport;
+        tree.resolve_relative_styles(None);
+    }
+    0
+}
+
+/// Sets the font size that `rem` units are relative to, and `em` units of nodes that do not inherit
+/// one from an ancestor, initially 16. Fails if it is not a positive finite number.
+#[no_mangle]
+pub extern "C" fn taffytree_set_root_font_size(tree: usize, font_size: f32) -> i32 {
+    let tree_ptr = tree as *mut FfiTree;
+    let tree = unsafe { &mut *tree_ptr };
+    if tree.poisoned() {
+        return 1;
+    }
+    if !(font_size.is_finite() && font_size > 0.0) {
+        return tree.fail();
+    }
+    if tree.root_font_size != font_size {
+        tree.journal_settings();
+        tree.root_font_size = font_size;
+        tree.resolve_relative_styles(None);
+    }
+    0
+}
+
+/// Sets the font size that `em` units of `node` and of its descendants without their own are relative
+/// to. NaN removes it so that the node inherits one again. Nodes of the subtree using font units are
+/// resolved again and marked dirty if their style changed.
+#[no_mangle]
+pub extern "C" fn taffytree_set_font_size(tree: usize, node: u64, font_size: f32) -> i32 {
+    let tree_ptr = tree as *mut FfiTree;
+    let tree = unsafe { &mut *tree_ptr };
+    let node = NodeId::from(node);
+    if tree.poisoned() {
+        return 1;
+    }
+    let font_size = (!font_size.is_nan()).then_some(font_size);
+    if !tree.contains(node) || font_size.is_some_and(|size| !(size.is_finite() && size > 0.0)) {
+        return tree.fail();
+    }
+    if tree.nodes.get(&node).is_some_and(|data| data.font_size != font_size) {
+        tree.journal_settings();
+        if let Some(data) = tree.data_mut(node) {
+            data.font_size = font_size;
+        }
+        tree.node_font_sizes |= font_size.is_some();
+        tree.resolve_relative_styles(Some(node));
+    }
+    0
+}
+
+/// Gets the font size `em` units of `node` are relative to: its own, inherited or the tree's
+#[no_mangle]
+pub extern "C" fn taffytree_font_size(tree: usize, node: u64, out: *mut f32) -> i32 {
+    let tree_ptr = tree as *mut FfiTree;
+    let tree = unsafe { &mut *tree_ptr };
+    let node = NodeId::from(node);
+    if out.is_null() || !tree.contains(node) {
+        return 1;
+    }
+    unsafe {
+        *out = tree.units(Some(node)).font_size;
+    }
+    0
+}
+
+// CALC
+
+/// Parses a `calc()`, `min()`, `max()` or `clamp()` expression (see `calc.rs`) from `len` bytes of UTF-8.
+/// Returns its id, to be used as the value of a length with dim 14, or 0 if the expression is invalid
+/// or this build does not support calc (see `taffy_ffi_supported_features`). Lengths in an expression are
+/// in `px` or `%`, so relative units such as `vw` and `em` cannot be combined with calc. Stylesheet
+/// declarations accept the same expressions. Parsing the same expression again returns the same id. Parsed
+/// expressions are leaked for the lifetime of the process, and once 2^24 distinct ones have been parsed,
+/// new ones return 0, so expressions should not be generated from values that keep changing.
+#[no_mangle]
+pub extern "C" fn taffy_calc_parse(text: *const u8, len: usize) -> u32 {
+    if !cfg!(feature = "calc") {
+        return 0;
+    }
+    str_arg(text, len).and_then(calc::parse).unwrap_or(0)
+}
+
+// INTERPOLATION
+
+/// Writes the style at `t` of a transition from `a` to `b` (see `lerp.rs`) to `out`: `a` at 0 and `b` at 1,
+/// extrapolating outside of that. The grid track arrays of `out` are allocated by this library, release
+/// them with `taffy_style_lerp_free`. Returns 1 if a style is null or uses a display mode or calc length
+/// this build does not support, or if `t` is NaN.
+#[no_mangle]
+pub extern "C" fn taffy_style_lerp(a: *const c_Style, b: *const c_Style, t: f32, out: *mut c_Style) -> i32 {
+    let (Some(a), Some(b)) = (style_arg(a), style_arg(b)) else {
+        return 1;
+    };
+    if out.is_null() || t.is_nan() {
+        return 1;
+    }
+    unsafe {
+        *out = a.lerp(&b, t).into_raw();
+    }
+    0
+}
+
+/// Releases the grid track arrays of a style written by `taffy_style_lerp`, setting them to null.
+/// The style can still be used afterwards, without grid tracks.
+#[no_mangle]
+pub extern "C" fn taffy_style_lerp_free(style: *mut c_Style) {
+    if !style.is_null() {
+        unsafe { lerp::free_raw(&mut *style) }
+    }
+}
+
+// LAYOUT ANIMATION
+
+/// The box of a node at some point of an animated layout change, in the coordinates of
+/// `taffytree_absolute_location`. See `animation.rs`.
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct c_AnimatedRect {
+    node: u64,
+    location: [f32; 2], // x, y
+    size: [f32; 2],     // width, height
+    change: i32,        // 0 moved, 1 appeared, 2 disappeared
+    presence: f32,      // 0 to 1, fades appearing and disappearing nodes
+}
+
+/// Records the boxes of `root` and its descendants from the last `taffytree_compute_layout`, to animate
+/// from them with `taffytree_interpolate_layouts` once the layout changed. Release the handle with
+/// `taffy_layout_snapshot_free`. Returns 0 if `root` is not a valid node.
+#[no_mangle]
+pub extern "C" fn taffytree_snapshot_layouts(tree: usize, root: u64) -> usize {
+    let tree_ptr = tree as *mut FfiTree;
+    let tree = unsafe { &mut *tree_ptr };
+    let root = NodeId::from(root);
+    if !tree.contains(root) {
+        return 0;
+    }
+    Box::into_raw(Box::new(LayoutSnapshot::new(tree.absolute_boxes(root)))) as usize
+}
+
+/// Releases a snapshot created by `taffytree_snapshot_layouts`
+#[no_mangle]
+pub extern "C" fn taffy_layout_snapshot_free(snapshot: usize) {
+    if snapshot != 0 {
+        let snapshot_ptr = snapshot as *mut LayoutSnapshot;
+        unsafe {
+            drop(Box::from_raw(snapshot_ptr));
+        }
+    }
+}
+
+/// Interpolates the boxes of `snapshot` towards the current boxes of `root` and its descendants at `t`,
+/// from 0 (the snapshot) to 1 (the current layout). The nodes of the subtree come first, parents before
+/// children, followed by the nodes of the snapshot that are no longer in it. Up to `capacity` rects are
+/// written to `out_rects`. Returns the number of rects, or -1 if `root` or `snapshot` is not valid.
+#[no_mangle]
+pub extern "C" fn taffytree_interpolate_layouts(
+    tree: usize,
+    root: u64,
+    snapshot: usize,
+    t: f32,
+    out_rects: *mut c_AnimatedRect,
+    capacity: usize,
+) -> i32 {
+    let tree_ptr = tree as *mut FfiTree;
+    let tree = unsafe { &mut *tree_ptr };
+    let root = NodeId::from(root);
+    if !tree.contains(root) || snapshot == 0 || t.is_nan() || (out_rects.is_null() && capacity > 0) {
+        return -1;
+    }
+    let snapshot = unsafe { &*(snapshot as *const LayoutSnapshot) };
+    let rects = snapshot.interpolate(&tree.absolute_boxes(root), t);
+    let written = rects.len().min(capacity);
+    if written > 0 {
+        unsafe {
+            std::ptr::copy_nonoverlapping(rects.as_ptr(), out_rects, written);
+        }
+    }
+    rects.len() as i32
+}
+
+// FIT LAYOUT
+
+/// Computes the layout of `root` sized to its content, as a popup or tooltip would be: its fit-content
+/// size within the max bounds, clamped between the min and max bounds. Max bounds can be infinite, and
+/// percentages of the root resolve against the finite ones. The chosen size, before rounding, is
+/// written to the optional `out_width` and `out_height`. Returns 1 if `root` is not a valid node or
+/// if the bounds are negative, NaN or a min bound is greater than its max bound.
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub extern "C" fn taffytree_compute_layout_fit(
+    tree: usize,
+    root: u64,
+    min_width: f32,
+    min_height: f32,
+    max_width: f32,
+    max_height: f32,
+    out_width: *mut f32,
+    out_height: *mut f32,
+) -> i32 {
+    let tree_ptr = tree as *mut FfiTree;
+    let tree = unsafe { &mut *tree_ptr };
+    let root = NodeId::from(root);
+    let valid_bounds = |min: f32, max: f32| min.is_finite() && min >= 0.0 && max >= min;
+    if !tree.contains(root) || !valid_bounds(min_width, max_width) || !valid_bounds(min_height, max_height) {
+        return 1;
+    }
+
+    let min_size = Size { width: min_width, height: min_height };
+    let max_size = Size { width: max_width, height: max_height };
+    let size = layout::compute_layout_fit(tree, root, min_size, max_size);
+    unsafe {
+        if !out_width.is_null() {
+            *out_width = size.width;
+        }
+        if !out_height.is_null() {
+            *out_height = size.height;
+        }
+    }
+    0
+}
+
+// BATCH LAYOUT
+
+/// Computes the layouts of `count` roots, each in its own available space, as `taffytree_compute_layout`
+/// would, for hosts with one root per window. Roots whose layout is up to date are skipped: those that
+/// are not dirty and were last laid out as a root in the same available space, with the same rounding,
+/// viewport and root font size as the tree has now. For each root, writes to
+/// the optional `out_results` 1 if it was laid out, 0 if it was skipped or -1 if it is not a valid node.
+/// `taffytree_get_stats` then reports the last root laid out. Returns the number of roots laid out, or -1
+/// if `roots` or `available_spaces` is null.
+#[no_mangle]
+pub extern "C" fn taffytree_compute_layouts(
+    tree: usize,
+    roots: *const u64,
+    available_spaces: *const c_AvailableSpace,
+    count: usize,
+    out_results: *mut i32,
+) -> i32 {
+    let tree_ptr = tree as *mut FfiTree;
+    let tree = unsafe { &mut *tree_ptr };
+    if count == 0 {
+        return 0;
+    }
+    if roots.is_null() || available_spaces.is_null() {
+        return -1;
+    }
+    let roots = unsafe { std::slice::from_raw_parts(roots, count) };
+    let available_spaces = unsafe { std::slice::from_raw_parts(available_spaces, count) };
+
+    let mut computed = 0;
+    for (i, (&root, &available_space)) in roots.iter().zip(available_spaces).enumerate() {
+        let root = NodeId::from(root);
+        let available_space = Size::from(available_space);
+        let result = if !tree.contains(root) {
+            -1
+        } else if layout::needs_layout(tree, root, available_space) {
+            layout::compute_layout(tree, root, available_space);
+            computed += 1;
+            1
+        } else {
+            0
+        };
+        if !out_results.is_null() {
+            unsafe {
+                *out_results.add(i) = result;
+            }
+        }
+    }
+    computed
+}
@@ -0,0 +1,130 @@
+//! Estimates of the memory held by an `FfiTree`.
+//!
+//! taffy does not expose the capacity of its storage, so its part is estimated from the sizes of the
+//! types it stores in each slot, for every slot its slot maps have grown to. A removed node leaves a
+//! vacant slot as large as a live one behind. taffy_ffi's own storage is measured from its capacities.
+
+use std::mem::size_of;
+
+use taffy::prelude::*;
+use taffy::style::Style;
+use taffy::Cache;
+#[cfg(feature = "grid")]
+use taffy::TrackSizingFunction;
+
+use crate::{c_GridTrackSize, c_MeasureQuery, c_MemoryUsage, c_NodeStats, ElementData, FfiTree, NodeData, OwnedStyle, OwnedTrackSizing,
+    SavedNode,
+};
+
+pub(crate) fn usage(tree: &FfiTree) -> c_MemoryUsage {
+    let mut usage = c_MemoryUsage::default();
+    let slots = tree.slots;
+
+    // The node, the layouts, the parent and the children of each slot in the TaffyTree, with the version of the slot
+    let version = size_of::<u32>();
+    usage.nodes = (slots * (3 * version + 2 * size_of::<Layout>() + size_of::<Option<NodeId>>() + size_of::<Vec<NodeId>>()))
+        as u64;
+    usage.styles = (slots * size_of::<Style>()) as u64;
+    usage.caches = (slots * size_of::<Cache>()) as u64;
+    usage.context = (tree.nodes.capacity() * size_of::<(NodeId, NodeData)>()
+        + tree.node_stats.capacity() * size_of::<(NodeId, c_NodeStats)>()) as u64;
+
+    for (node, data) in &tree.nodes {
+        usage.nodes += (tree.taffy.child_count(*node) * size_of::<NodeId>()) as u64;
+        if let Ok(style) = tree.taffy.style(*node) {
+            usage.styles += style_heap(style) as u64;
+        }
+        if let Some(element) = &data.element {
+            usage.styles += (size_of::<Style>() + style_heap(&element.base)) as u64;
+            usage.context += element_heap(element) as u64;
+        }
+        if let Some(relative) = &data.relative_style {
+            usage.styles += owned_style_size(relative) as u64;
+        }
+        if let Some(text) = &data.text {
+            usage.context += (size_of_val(&**text) + text.text.capacity()) as u64;
+        }
+        usage.context += (data.measure_queries.capacity() * size_of::<c_MeasureQuery>()) as u64;
+    }
+
+    // The undo log of an open batch keeps a copy of each node it changed
+    if let Some(batch) = &tree.batch {
+        usage.context += (batch.saved.capacity() * size_of::<(NodeId, Option<SavedNode>)>()
+            + batch.removed.capacity() * size_of::<NodeId>()) as u64;
+        for saved in batch.saved.values().flatten() {
+            usage.styles += style_heap(&saved.style) as u64;
+            usage.nodes += (saved.children.capacity() * size_of::<NodeId>()) as u64;
+        }
+    }
+
+    usage.total = usage.nodes + usage.styles + usage.caches + usage.context;
+    usage
+}
+
+/// Releases the spare capacity of the storage of `tree`. The vacant slots of removed nodes stay, as node ids
+/// index the slots and would have to change to compact them.
+pub(crate) fn shrink_to_fit(tree: &mut FfiTree) {
+    // Cloning allocates every vector of the TaffyTree at its length, vacant slots included
+    tree.taffy = tree.taffy.clone();
+    tree.nodes.shrink_to_fit();
+    tree.node_stats.shrink_to_fit();
+    for data in tree.nodes.values_mut() {
+        data.measure_queries.shrink_to_fit();
+        if let Some(element) = &mut data.element {
+            element.classes.shrink_to_fit();
+        }
+    }
+}
+
+/// The heap memory of the grid track lists of `style`
+#[cfg(feature = "grid")]
+fn style_heap(style: &Style) -> usize {
+    let repetitions = |tracks: &Vec<TrackSizingFunction>| {
+        tracks
+            .iter()
+            .map(|track| match track {
+                TrackSizingFunction::Repeat(_, repeated) => repeated.capacity() * size_of::<NonRepeatedTrackSizingFunction>(),
+                TrackSizingFunction::Single(_) => 0,
+            })
+            .sum::<usize>()
+    };
+    style.grid_template_rows.capacity() * size_of::<TrackSizingFunction>()
+        + style.grid_template_columns.capacity() * size_of::<TrackSizingFunction>()
+        + style.grid_auto_rows.capacity() * size_of::<NonRepeatedTrackSizingFunction>()
+        + style.grid_auto_columns.capacity() * size_of::<NonRepeatedTrackSizingFunction>()
+        + repetitions(&style.grid_template_rows)
+        + repetitions(&style.grid_template_columns)
+}
+
+/// Without grid, styles own no heap memory
+#[cfg(not(feature = "grid"))]
+fn style_heap(_style: &Style) -> usize {
+    0
+}
+
+/// The memory of an unresolved style kept for its relative lengths
+fn owned_style_size(style: &OwnedStyle) -> usize {
+    let sizings = |tracks: &Vec<OwnedTrackSizing>| {
+        tracks.capacity() * size_of::<OwnedTrackSizing>()
+            + tracks
+                .iter()
+                .map(|track| match track {
+                    OwnedTrackSizing::Repeat(_, sizes) => sizes.capacity() * size_of::<c_GridTrackSize>(),
+                    OwnedTrackSizing::Single(_) => 0,
+                })
+                .sum::<usize>()
+    };
+    size_of::<OwnedStyle>()
+        + sizings(&style.grid_template_rows)
+        + sizings(&style.grid_template_columns)
+        + (style.grid_auto_rows.capacity() + style.grid_auto_columns.capacity()) * size_of::<c_GridTrackSize>()
+}
+
+/// The memory of `element` besides its base style, which counts as style storage
+fn element_heap(element: &ElementData) -> usize {
+    size_of::<ElementData>() - size_of::<Style>()
+        + element.tag.capacity()
+        + element.id.capacity()
+        + element.classes.capacity() * size_of::<String>()
+        + element.classes.iter().map(String::capacity).sum::<usize>()
+}
@@ -0,0 +1,223 @@
+//! Debug renderings of a computed layout
+
+use std::fmt::Write;
+
+use taffy::prelude::*;
+
+use crate::FfiTree;
+
+/// Visits every displayed node in the subtree of `root`, parents before children, with the location of its
+/// border box relative to the border box of `root`
+fn visit(tree: &FfiTree, root: NodeId, mut f: impl FnMut(NodeId, &Layout, taffy::Point<f32>)) {
+    let mut stack = vec![(root, taffy::Point::ZERO)];
+    while let Some((node, location)) = stack.pop() {
+        let (Ok(layout), Ok(style)) = (tree.layout(node), tree.taffy.style(node)) else {
+            continue;
+        };
+        if style.display == Display::None {
+            continue;
+        }
+        f(node, layout, location);
+        let children = tree.taffy.children(node).unwrap_or_default();
+        for child in children.into_iter().rev() {
+            let child_location = tree.layout(child).map_or(taffy::Point::ZERO, |layout| layout.location);
+            stack.push((child, taffy::Point { x: location.x + child_location.x, y: location.y + child_location.y }));
+        }
+    }
+}
+
+/// The tag, id and classes of `node` in selector form, or its node id if it has none
+pub(crate) fn label(tree: &FfiTree, node: NodeId) -> String {
+    let element = tree.nodes.get(&node).and_then(|data| data.element.as_deref());
+    let mut label = String::new();
+    if let Some(element) = element {
+        label.push_str(&element.tag);
+        if !element.id.is_empty() {
+            let _ = write!(label, "#{}", element.id);
+        }
+        for class in &element.classes {
+            let _ = write!(label, ".{class}");
+        }
+    }
+    if label.is_empty() {
+        label = u64::from(node).to_string();
+    }
+    label
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn display_color(display: Display) -> &'static str {
+    match display {
+        #[cfg(feature = "flexbox")]
+        Display::Flex => "#2f7ed8",
+        #[cfg(feature = "grid")]
+        Display::Grid => "#d9534f",
+        #[cfg(feature = "block_layout")]
+        Display::Block => "#5cb85c",
+        Display::None => "#999999",
+    }
+}
+
+fn svg_rect(out: &mut String, x: f32, y: f32, width: f32, height: f32, attributes: &str) {
+    let _ = writeln!(
+        out,
+        r#"    <rect x="{x}" y="{y}" width="{}" height="{}" {attributes}/>"#,
+        width.max(0.0),
+        height.max(0.0)
+    );
+}
+
+pub(crate) fn svg(tree: &FfiTree, root: NodeId) -> String {
+    let root_margin = tree.layout(root).map_or(Rect::zero(), |layout| layout.margin);
+    let origin = taffy::Point { x: root_margin.left.max(0.0), y: root_margin.top.max(0.0) };
+
+    let mut body = String::new();
+    let mut extent = Size::zero();
+    visit(tree, root, |node, layout, location| {
+        let display = tree.taffy.style(node).map_or(Display::DEFAULT, |style| style.display);
+        let color = display_color(display);
+        let (x, y) = (origin.x + location.x, origin.y + location.y);
+        let (size, margin, border, padding) = (layout.size, layout.margin, layout.border, layout.padding);
+        extent.width = f32::max(extent.width, x + size.width + margin.right.max(0.0));
+        extent.height = f32::max(extent.height, y + size.height + margin.bottom.max(0.0));
+
+        let display = format!("{display:?}").to_lowercase();
+        let _ = writeln!(body, r#"  <g data-node="{}" class="{display}">"#, u64::from(node));
+        svg_rect(
+            &mut body,
+            x - margin.left,
+            y - margin.top,
+            size.width + margin.left + margin.right,
+            size.height + margin.top + margin.bottom,
+            r##"fill="none" stroke="#f0ad4e" stroke-dasharray="4 2""##,
+        );
+        svg_rect(&mut body, x, y, size.width, size.height, &format!(r#"fill="{color}" fill-opacity="0.12" stroke="{color}""#));
+        svg_rect(
+            &mut body,
+            x + border.left,
+            y + border.top,
+            size.width - border.left - border.right,
+            size.height - border.top - border.bottom,
+            &format!(r#"fill="none" stroke="{color}" stroke-dasharray="2 2""#),
+        );
+        svg_rect(
+            &mut body,
+            x + border.left + padding.left,
+            y + border.top + padding.top,
+            layout.content_box_width(),
+            layout.content_box_height(),
+            &format!(r#"fill="{color}" fill-opacity="0.08" stroke="{color}" stroke-opacity="0.5""#),
+        );
+        let _ = writeln!(
+            body,
+            r#"    <text x="{}" y="{}" font-family="monospace" font-size="10" fill="{color}">{}</text>"#,
+            x + 2.0,
+            y + 11.0,
+            escape_xml(&label(tree, node))
+        );
+        body.push_str("  </g>\n");
+    });
+
+    let mut out = String::with_capacity(body.len() + 200);
+    let _ = writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}">"#,
+        extent.width.ceil(),
+        extent.height.ceil()
+    );
+    out.push_str(&body);
+    out.push_str("</svg>\n");
+    out
+}
+
+// Directions a box-drawing line leaves a cell in
+const UP: u8 = 1;
+const DOWN: u8 = 2;
+const LEFT: u8 = 4;
+const RIGHT: u8 = 8;
+
+/// The largest grid `ascii` draws in either direction, so a bad scale can't allocate without bound
+const MAX_ASCII_CELLS: usize = 4096;
+
+fn box_char(directions: u8) -> char {
+    match directions {
+        0 => ' ',
+        d if d == LEFT | RIGHT || d == LEFT || d == RIGHT => '─',
+        d if d == UP | DOWN || d == UP || d == DOWN => '│',
+        d if d == DOWN | RIGHT => '┌',
+        d if d == DOWN | LEFT => '┐',
+        d if d == UP | RIGHT => '└',
+        d if d == UP | LEFT => '┘',
+        d if d == UP | DOWN | RIGHT => '├',
+        d if d == UP | DOWN | LEFT => '┤',
+        d if d == LEFT | RIGHT | DOWN => '┬',
+        d if d == LEFT | RIGHT | UP => '┴',
+        _ => '┼',
+    }
+}
+
+/// Rasterizes the border boxes in the subtree of `root` into a character grid with `scale` cells per
+/// layout unit, labelling each box in its top-left corner. Returns `None` if `scale` is not positive or
+/// the grid would be larger than `MAX_ASCII_CELLS` in either direction.
+pub(crate) fn ascii(tree: &FfiTree, root: NodeId, scale: f32) -> Option<String> {
+    if !(scale.is_finite() && scale > 0.0) {
+        return None;
+    }
+    let cell = |value: f32| (value * scale).round();
+
+    let mut boxes = Vec::new();
+    visit(tree, root, |node, layout, location| {
+        let (left, top) = (cell(location.x), cell(location.y));
+        let (right, bottom) = (cell(location.x + layout.size.width), cell(location.y + layout.size.height));
+        if right > left && bottom > top && right >= 0.0 && bottom >= 0.0 {
+            boxes.push((node, left.max(0.0) as usize, top.max(0.0) as usize, right as usize, bottom as usize));
+        }
+    });
+
+    let width = boxes.iter().map(|b| b.3 + 1).max().unwrap_or(0);
+    let height = boxes.iter().map(|b| b.4 + 1).max().unwrap_or(0);
+    if width > MAX_ASCII_CELLS || height > MAX_ASCII_CELLS {
+        return None;
+    }
+
+    let mut lines = vec![0u8; width * height];
+    for &(_, left, top, right, bottom) in &boxes {
+        for x in left..=right {
+            for y in [top, bottom] {
+                lines[y * width + x] |= if x > left { LEFT } else { 0 } | if x < right { RIGHT } else { 0 };
+            }
+        }
+        for y in top..=bottom {
+            for x in [left, right] {
+                lines[y * width + x] |= if y > top { UP } else { 0 } | if y < bottom { DOWN } else { 0 };
+            }
+        }
+    }
+
+    // A label only covers cells that its box owns: inside the box, off any line, and not inside a descendant
+    let mut owners = vec![usize::MAX; width * height];
+    for (index, &(_, left, top, right, bottom)) in boxes.iter().enumerate() {
+        for y in top + 1..bottom {
+            owners[y * width + left + 1..y * width + right].fill(index);
+        }
+    }
+    let mut grid: Vec<char> = lines.iter().copied().map(box_char).collect();
+    for (index, &(node, left, top, right, _)) in boxes.iter().enumerate() {
+        let row = (top + 1) * width;
+        let cells = (left + 1..right).take_while(|&x| owners[row + x] == index && lines[row + x] == 0);
+        for (x, c) in cells.zip(label(tree, node).chars()) {
+            grid[row + x] = c;
+        }
+    }
+
+    let mut out = String::with_capacity(grid.len() + height);
+    for row in grid.chunks(width.max(1)) {
+        let line: String = row.iter().collect();
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    Some(out)
+}
@@ -0,0 +1,912 @@
+//! Stylesheets: a small CSS subset that is matched against node tags, ids and classes and
+//! resolved into each node's `taffy::Style`.
+//!
+//! Selectors support `*`, tags, `#id` and `.class` compounds joined by descendant (whitespace) and
+//! child (`>`) combinators, and comma separated lists. Declarations use CSS property names for the
+//! fields of `taffy::Style`, with lengths in `px` (or unitless), `%`, the relative units of `units.rs`
+//! and `calc()` expressions (see `calc.rs`), and the `inherit` and `initial` keywords. `text-align` is
+//! inherited by default, as in CSS.
+//!
+//! A node's resolved style starts from the style the host set on it, then applies the matching
+//! rules in order of specificity and source order. Unlike a CSS inline style, the host's style does not
+//! win over the rules: it only provides the values of the properties no matching rule declares.
+//! Declarations with relative lengths are resolved against the units of each node they apply to.
+
+use std::ops::RangeInclusive;
+
+use taffy::prelude::*;
+use taffy::style::Style;
+use taffy::Overflow;
+#[cfg(feature = "block_layout")]
+use taffy::TextAlign;
+
+use crate::units::Units;
+use crate::{c_Length, calc, DIMENSION, LENGTH_PERCENTAGE, LENGTH_PERCENTAGE_AUTO};
+#[cfg(feature = "grid")]
+use crate::{MAX_TRACK, MIN_TRACK};
+
+/// What the stylesheet needs to know about a node to match selectors against it
+pub(crate) trait Element: Sized {
+    fn parent(&self) -> Option<Self>;
+    fn tag(&self) -> &str;
+    fn id(&self) -> &str;
+    fn has_class(&self, class: &str) -> bool;
+}
+
+pub(crate) struct Stylesheet {
+    rules: Vec<Rule>,
+}
+
+struct Rule {
+    /// Alternatives of a selector list, each in left to right order
+    selectors: Vec<Vec<Part>>,
+    /// The declared values, read for the properties listed in `declarations`
+    values: Style,
+    declarations: Vec<(Property, Origin)>,
+    /// The name and value of the declarations with relative lengths, parsed again for every node
+    relative: Vec<(String, String)>,
+}
+
+/// A compound selector and the combinator linking it to the compound on its left
+struct Part {
+    compound: Compound,
+    combinator: Combinator,
+}
+
+#[derive(Default)]
+struct Compound {
+    tag: Option<String>,
+    id: Option<String>,
+    classes: Vec<String>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Combinator {
+    Descendant,
+    Child,
+}
+
+/// Where a declaration takes its value from
+#[derive(Clone, Copy)]
+enum Origin {
+    Declared,
+    /// The declaration at this index of `Rule::relative`
+    Relative(usize),
+    Inherit,
+    Initial,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Property {
+    Display,
+    Position,
+    BoxSizing,
+    OverflowX,
+    OverflowY,
+    ScrollbarWidth,
+    Width,
+    Height,
+    MinWidth,
+    MinHeight,
+    MaxWidth,
+    MaxHeight,
+    AspectRatio,
+    MarginLeft,
+    MarginRight,
+    MarginTop,
+    MarginBottom,
+    PaddingLeft,
+    PaddingRight,
+    PaddingTop,
+    PaddingBottom,
+    BorderLeft,
+    BorderRight,
+    BorderTop,
+    BorderBottom,
+    Left,
+    Right,
+    Top,
+    Bottom,
+    #[cfg(any(feature = "flexbox", feature = "grid"))]
+    ColumnGap,
+    #[cfg(any(feature = "flexbox", feature = "grid"))]
+    RowGap,
+    #[cfg(feature = "flexbox")]
+    FlexDirection,
+    #[cfg(feature = "flexbox")]
+    FlexWrap,
+    #[cfg(feature = "flexbox")]
+    FlexGrow,
+    #[cfg(feature = "flexbox")]
+    FlexShrink,
+    #[cfg(feature = "flexbox")]
+    FlexBasis,
+    #[cfg(any(feature = "flexbox", feature = "grid"))]
+    AlignItems,
+    #[cfg(any(feature = "flexbox", feature = "grid"))]
+    AlignSelf,
+    #[cfg(feature = "grid")]
+    JustifyItems,
+    #[cfg(feature = "grid")]
+    JustifySelf,
+    #[cfg(any(feature = "flexbox", feature = "grid"))]
+    AlignContent,
+    #[cfg(any(feature = "flexbox", feature = "grid"))]
+    JustifyContent,
+    #[cfg(feature = "block_layout")]
+    TextAlign,
+    #[cfg(feature = "grid")]
+    GridTemplateRows,
+    #[cfg(feature = "grid")]
+    GridTemplateColumns,
+    #[cfg(feature = "grid")]
+    GridAutoRows,
+    #[cfg(feature = "grid")]
+    GridAutoColumns,
+    #[cfg(feature = "grid")]
+    GridAutoFlow,
+    #[cfg(feature = "grid")]
+    GridRowStart,
+    #[cfg(feature = "grid")]
+    GridRowEnd,
+    #[cfg(feature = "grid")]
+    GridColumnStart,
+    #[cfg(feature = "grid")]
+    GridColumnEnd,
+}
+
+impl Stylesheet {
+    /// Parses a stylesheet, returning `None` on any syntax error or unsupported property
+    pub(crate) fn parse(css: &str) -> Option<Stylesheet> {
+        let css = strip_comments(css);
+        let mut rules = Vec::new();
+        let mut rest = css.trim();
+        while !rest.is_empty() {
+            let (selectors, after) = rest.split_once('{')?;
+            let (block, after) = after.split_once('}')?;
+            let selectors = selectors.split(',').map(parse_selector).collect::<Option<Vec<_>>>()?;
+
+            let mut values = Style::default();
+            let mut declarations: Vec<(Property, Origin)> = Vec::new();
+            let mut relative = Vec::new();
+            for declaration in block.split(';').map(str::trim).filter(|d| !d.is_empty()) {
+                let (name, value) = declaration.split_once(':')?;
+                let (name, value) = (name.trim(), value.trim());
+                let (properties, origin) = match value {
+                    "inherit" => (longhands(name)?, Origin::Inherit),
+                    "initial" => (longhands(name)?, Origin::Initial),
+                    _ => match parse_declaration(name, value, &mut values, None) {
+                        Some(properties) => (properties, Origin::Declared),
+                        // Only declarations with relative lengths need units to parse
+                        None => {
+                            let properties = parse_declaration(name, value, &mut Style::default(), Some(&Units::DETACHED))?;
+                            relative.push((name.to_string(), value.to_string()));
+                            (properties, Origin::Relative(relative.len() - 1))
+                        }
+                    },
+                };
+                for property in properties {
+                    declarations.retain(|(p, _)| *p != property);
+                    declarations.push((property, origin));
+                }
+            }
+
+            rules.push(Rule { selectors, values, declarations, relative });
+            rest = after.trim();
+        }
+        Some(Stylesheet { rules })
+    }
+
+    /// Whether a rule uses relative lengths, so that styles have to be resolved again when the units change
+    pub(crate) fn is_relative(&self) -> bool {
+        self.rules.iter().any(|rule| !rule.relative.is_empty())
+    }
+
+    /// Resolves the style of `element` from the style the host set on it and its parent's resolved style,
+    /// with relative lengths resolved against `units`
+    pub(crate) fn resolve<E: Element>(&self, element: &E, base: &Style, parent: Option<&Style>, units: &Units) -> Style {
+        let mut matched: Vec<(u32, usize)> = self
+            .rules
+            .iter()
+            .enumerate()
+            .filter_map(|(order, rule)| {
+                rule.selectors
+                    .iter()
+                    .filter(|selector| matches(selector, selector.len() - 1, element))
+                    .map(|selector| specificity(selector))
+                    .max()
+                    .map(|specificity| (specificity, order))
+            })
+            .collect();
+        matched.sort();
+
+        let initial = Style::default();
+        let mut relative = Style::default();
+        let mut style = base.clone();
+        #[cfg(feature = "block_layout")]
+        let mut text_align_declared = false;
+        for (_, order) in matched {
+            let rule = &self.rules[order];
+            for &(property, origin) in &rule.declarations {
+                let source = match origin {
+                    Origin::Declared => &rule.values,
+                    Origin::Relative(index) => {
+                        let (name, value) = &rule.relative[index];
+                        parse_declaration(name, value, &mut relative, Some(units));
+                        &relative
+                    }
+                    Origin::Inherit => parent.unwrap_or(&initial),
+                    Origin::Initial => &initial,
+                };
+                copy_property(property, source, &mut style);
+                #[cfg(feature = "block_layout")]
+                {
+                    text_align_declared |= property == Property::TextAlign;
+                }
+            }
+        }
+
+        #[cfg(feature = "block_layout")]
+        if let Some(parent) = parent {
+            if !text_align_declared && base.text_align == initial.text_align {
+                style.text_align = parent.text_align;
+            }
+        }
+        style
+    }
+}
+
+fn strip_comments(css: &str) -> String {
+    let mut out = String::with_capacity(css.len());
+    let mut rest = css;
+    while let Some(start) = rest.find("/*") {
+        out.push_str(&rest[..start]);
+        rest = match rest[start + 2..].find("*/") {
+            Some(end) => &rest[start + 2 + end + 2..],
+            None => "",
+        };
+    }
+    out.push_str(rest);
+    out
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '-' || c == '_'
+}
+
+fn parse_selector(text: &str) -> Option<Vec<Part>> {
+    let mut parts: Vec<Part> = Vec::new();
+    let mut combinator = Combinator::Descendant;
+    let mut chars = text.trim().chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        if c == '>' {
+            chars.next();
+            if parts.is_empty() || combinator == Combinator::Child {
+                return None;
+            }
+            combinator = Combinator::Child;
+            continue;
+        }
+
+        let mut compound = Compound::default();
+        let mut empty = true;
+        if c == '*' {
+            chars.next();
+            empty = false;
+        }
+        loop {
+            let kind = match chars.peek() {
+                Some(&c) if c == '#' || c == '.' => {
+                    chars.next();
+                    Some(c)
+                }
+                Some(&c) if is_ident_char(c) && empty => None,
+                _ => break,
+            };
+            let mut ident = String::new();
+            while let Some(&c) = chars.peek().filter(|&&c| is_ident_char(c)) {
+                ident.push(c);
+                chars.next();
+            }
+            if ident.is_empty() {
+                return None;
+            }
+            match kind {
+                Some('#') => compound.id = Some(ident),
+                Some(_) => compound.classes.push(ident),
+                None => compound.tag = Some(ident),
+            }
+            empty = false;
+        }
+        if empty {
+            return None;
+        }
+
+        parts.push(Part { compound, combinator });
+        combinator = Combinator::Descendant;
+    }
+    if parts.is_empty() || combinator == Combinator::Child {
+        return None;
+    }
+    Some(parts)
+}
+
+/// Packs (ids, classes, tags) so that comparing the packed values compares specificity
+fn specificity(selector: &[Part]) -> u32 {
+    selector.iter().fold(0, |total, part| {
+        let compound = &part.compound;
+        total
+            + ((compound.id.is_some() as u32) << 20)
+            + ((compound.classes.len() as u32) << 10)
+            + compound.tag.is_some() as u32
+    })
+}
+
+fn matches<E: Element>(selector: &[Part], index: usize, element: &E) -> bool {
+    let part = &selector[index];
+    let compound = &part.compound;
+    if compound.tag.as_deref().is_some_and(|tag| tag != element.tag())
+        || compound.id.as_deref().is_some_and(|id| id != element.id())
+        || !compound.classes.iter().all(|class| element.has_class(class))
+    {
+        return false;
+    }
+    if index == 0 {
+        return true;
+    }
+
+    let mut ancestor = element.parent();
+    while let Some(candidate) = ancestor {
+        if matches(selector, index - 1, &candidate) {
+            return true;
+        }
+        if part.combinator == Combinator::Child {
+            return false;
+        }
+        ancestor = candidate.parent();
+    }
+    false
+}
+
+/// The longhand properties set by a (possibly shorthand) property name
+fn longhands(name: &str) -> Option<Vec<Property>> {
+    use Property::*;
+    Some(match name {
+        "display" => vec![Display],
+        "position" => vec![Position],
+        "box-sizing" => vec![BoxSizing],
+        "overflow" => vec![OverflowX, OverflowY],
+        "overflow-x" => vec![OverflowX],
+        "overflow-y" => vec![OverflowY],
+        "scrollbar-width" => vec![ScrollbarWidth],
+        "width" => vec![Width],
+        "height" => vec![Height],
+        "min-width" => vec![MinWidth],
+        "min-height" => vec![MinHeight],
+        "max-width" => vec![MaxWidth],
+        "max-height" => vec![MaxHeight],
+        "aspect-ratio" => vec![AspectRatio],
+        "margin" => vec![MarginTop, MarginRight, MarginBottom, MarginLeft],
+        "margin-left" => vec![MarginLeft],
+        "margin-right" => vec![MarginRight],
+        "margin-top" => vec![MarginTop],
+        "margin-bottom" => vec![MarginBottom],
+        "padding" => vec![PaddingTop, PaddingRight, PaddingBottom, PaddingLeft],
+        "padding-left" => vec![PaddingLeft],
+        "padding-right" => vec![PaddingRight],
+        "padding-top" => vec![PaddingTop],
+        "padding-bottom" => vec![PaddingBottom],
+        "border-width" => vec![BorderTop, BorderRight, BorderBottom, BorderLeft],
+        "border-left-width" => vec![BorderLeft],
+        "border-right-width" => vec![BorderRight],
+        "border-top-width" => vec![BorderTop],
+        "border-bottom-width" => vec![BorderBottom],
+        "inset" => vec![Top, Right, Bottom, Left],
+        "left" => vec![Left],
+        "right" => vec![Right],
+        "top" => vec![Top],
+        "bottom" => vec![Bottom],
+        #[cfg(any(feature = "flexbox", feature = "grid"))]
+        "gap" => vec![RowGap, ColumnGap],
+        #[cfg(any(feature = "flexbox", feature = "grid"))]
+        "row-gap" => vec![RowGap],
+        #[cfg(any(feature = "flexbox", feature = "grid"))]
+        "column-gap" => vec![ColumnGap],
+        #[cfg(feature = "flexbox")]
+        "flex" => vec![FlexGrow, FlexShrink, FlexBasis],
+        #[cfg(feature = "flexbox")]
+        "flex-direction" => vec![FlexDirection],
+        #[cfg(feature = "flexbox")]
+        "flex-wrap" => vec![FlexWrap],
+        #[cfg(feature = "flexbox")]
+        "flex-grow" => vec![FlexGrow],
+        #[cfg(feature = "flexbox")]
+        "flex-shrink" => vec![FlexShrink],
+        #[cfg(feature = "flexbox")]
+        "flex-basis" => vec![FlexBasis],
+        #[cfg(any(feature = "flexbox", feature = "grid"))]
+        "align-items" => vec![AlignItems],
+        #[cfg(any(feature = "flexbox", feature = "grid"))]
+        "align-self" => vec![AlignSelf],
+        #[cfg(feature = "grid")]
+        "justify-items" => vec![JustifyItems],
+        #[cfg(feature = "grid")]
+        "justify-self" => vec![JustifySelf],
+        #[cfg(any(feature = "flexbox", feature = "grid"))]
+        "align-content" => vec![AlignContent],
+        #[cfg(any(feature = "flexbox", feature = "grid"))]
+        "justify-content" => vec![JustifyContent],
+        #[cfg(feature = "block_layout")]
+        "text-align" => vec![TextAlign],
+        #[cfg(feature = "grid")]
+        "grid-template-rows" => vec![GridTemplateRows],
+        #[cfg(feature = "grid")]
+        "grid-template-columns" => vec![GridTemplateColumns],
+        #[cfg(feature = "grid")]
+        "grid-auto-rows" => vec![GridAutoRows],
+        #[cfg(feature = "grid")]
+        "grid-auto-columns" => vec![GridAutoColumns],
+        #[cfg(feature = "grid")]
+        "grid-auto-flow" => vec![GridAutoFlow],
+        #[cfg(feature = "grid")]
+        "grid-row" => vec![GridRowStart, GridRowEnd],
+        #[cfg(feature = "grid")]
+        "grid-row-start" => vec![GridRowStart],
+        #[cfg(feature = "grid")]
+        "grid-row-end" => vec![GridRowEnd],
+        #[cfg(feature = "grid")]
+        "grid-column" => vec![GridColumnStart, GridColumnEnd],
+        #[cfg(feature = "grid")]
+        "grid-column-start" => vec![GridColumnStart],
+        #[cfg(feature = "grid")]
+        "grid-column-end" => vec![GridColumnEnd],
+        _ => return None,
+    })
+}
+
+/// Parses the value of `name` into `values`, returning the longhand properties it set. Relative lengths are
+/// resolved against `units`, and are an error without them.
+fn parse_declaration(name: &str, value: &str, values: &mut Style, units: Option<&Units>) -> Option<Vec<Property>> {
+    let properties = longhands(name)?;
+    let words = split_words(value);
+    let length = |word: &str, dims: RangeInclusive<i32>| length(word, dims, units);
+    let single = || match words.as_slice() {
+        [word] => Some(*word),
+        _ => None,
+    };
+
+    match name {
+        "display" => values.display = display(single()?)?,
+        "position" => values.position = position(single()?)?,
+        "box-sizing" => values.box_sizing = box_sizing(single()?)?,
+        "overflow" => {
+            let (x, y) = match words.as_slice() {
+                [both] => (*both, *both),
+                [x, y] => (*x, *y),
+                _ => return None,
+            };
+            values.overflow = taffy::Point { x: overflow(x)?, y: overflow(y)? };
+        }
+        "overflow-x" => values.overflow.x = overflow(single()?)?,
+        "overflow-y" => values.overflow.y = overflow(single()?)?,
+        "scrollbar-width" => values.scrollbar_width = px(single()?)?,
+        "width" => values.size.width = Dimension::from(length(single()?, DIMENSION)?),
+        "height" => values.size.height = Dimension::from(length(single()?, DIMENSION)?),
+        "min-width" => values.min_size.width = Dimension::from(length(single()?, DIMENSION)?),
+        "min-height" => values.min_size.height = Dimension::from(length(single()?, DIMENSION)?),
+        "max-width" => values.max_size.width = Dimension::from(length(single()?, DIMENSION)?),
+        "max-height" => values.max_size.height = Dimension::from(length(single()?, DIMENSION)?),
+        "aspect-ratio" => {
+            values.aspect_ratio = match words.as_slice() {
+                ["auto"] => None,
+                [ratio] => Some(number(ratio)?),
+                [width, "/", height] => Some(number(width)? / number(height)?),
+                _ => return None,
+            }
+        }
+        "margin" => values.margin = box_sides(&words, LENGTH_PERCENTAGE_AUTO, units, LengthPercentageAuto::from)?,
+        "margin-left" => values.margin.left = LengthPercentageAuto::from(length(single()?, LENGTH_PERCENTAGE_AUTO)?),
+        "margin-right" => values.margin.right = LengthPercentageAuto::from(length(single()?, LENGTH_PERCENTAGE_AUTO)?),
+        "margin-top" => values.margin.top = LengthPercentageAuto::from(length(single()?, LENGTH_PERCENTAGE_AUTO)?),
+        "margin-bottom" => values.margin.bottom = LengthPercentageAuto::from(length(single()?, LENGTH_PERCENTAGE_AUTO)?),
+        "padding" => values.padding = box_sides(&words, LENGTH_PERCENTAGE, units, LengthPercentage::from)?,
+        "padding-left" => values.padding.left = LengthPercentage::from(length(single()?, LENGTH_PERCENTAGE)?),
+        "padding-right" => values.padding.right = LengthPercentage::from(length(single()?, LENGTH_PERCENTAGE)?),
+        "padding-top" => values.padding.top = LengthPercentage::from(length(single()?, LENGTH_PERCENTAGE)?),
+        "padding-bottom" => values.padding.bottom = LengthPercentage::from(length(single()?, LENGTH_PERCENTAGE)?),
+        "border-width" => values.border = box_sides(&words, LENGTH_PERCENTAGE, units, LengthPercentage::from)?,
+        "border-left-width" => values.border.left = LengthPercentage::from(length(single()?, LENGTH_PERCENTAGE)?),
+        "border-right-width" => values.border.right = LengthPercentage::from(length(single()?, LENGTH_PERCENTAGE)?),
+        "border-top-width" => values.border.top = LengthPercentage::from(length(single()?, LENGTH_PERCENTAGE)?),
+        "border-bottom-width" => values.border.bottom = LengthPercentage::from(length(single()?, LENGTH_PERCENTAGE)?),
+        "inset" => values.inset = box_sides(&words, LENGTH_PERCENTAGE_AUTO, units, LengthPercentageAuto::from)?,
+        "left" => values.inset.left = LengthPercentageAuto::from(length(single()?, LENGTH_PERCENTAGE_AUTO)?),
+        "right" => values.inset.right = LengthPercentageAuto::from(length(single()?, LENGTH_PERCENTAGE_AUTO)?),
+        "top" => values.inset.top = LengthPercentageAuto::from(length(single()?, LENGTH_PERCENTAGE_AUTO)?),
+        "bottom" => values.inset.bottom = LengthPercentageAuto::from(length(single()?, LENGTH_PERCENTAGE_AUTO)?),
+        #[cfg(any(feature = "flexbox", feature = "grid"))]
+        "gap" => {
+            let (row, column) = match words.as_slice() {
+                [both] => (*both, *both),
+                [row, column] => (*row, *column),
+                _ => return None,
+            };
+            values.gap = Size {
+                width: LengthPercentage::from(length(column, LENGTH_PERCENTAGE)?),
+                height: LengthPercentage::from(length(row, LENGTH_PERCENTAGE)?),
+            };
+        }
+        #[cfg(any(feature = "flexbox", feature = "grid"))]
+        "row-gap" => values.gap.height = LengthPercentage::from(length(single()?, LENGTH_PERCENTAGE)?),
+        #[cfg(any(feature = "flexbox", feature = "grid"))]
+        "column-gap" => values.gap.width = LengthPercentage::from(length(single()?, LENGTH_PERCENTAGE)?),
+        #[cfg(feature = "flexbox")]
+        "flex" => {
+            let (grow, shrink, basis) = match words.as_slice() {
+                ["none"] => (0.0, 0.0, Dimension::auto()),
+                ["auto"] => (1.0, 1.0, Dimension::auto()),
+                [grow] => (number(grow)?, 1.0, Dimension::percent(0.0)),
+                [grow, shrink] => (number(grow)?, number(shrink)?, Dimension::percent(0.0)),
+                [grow, shrink, basis] => (number(grow)?, number(shrink)?, Dimension::from(length(basis, DIMENSION)?)),
+                _ => return None,
+            };
+            values.flex_grow = grow;
+            values.flex_shrink = shrink;
+            values.flex_basis = basis;
+        }
+        #[cfg(feature = "flexbox")]
+        "flex-direction" => values.flex_direction = flex_direction(single()?)?,
+        #[cfg(feature = "flexbox")]
+        "flex-wrap" => values.flex_wrap = flex_wrap(single()?)?,
+        #[cfg(feature = "flexbox")]
+        "flex-grow" => values.flex_grow = number(single()?)?,
+        #[cfg(feature = "flexbox")]
+        "flex-shrink" => values.flex_shrink = number(single()?)?,
+        #[cfg(feature = "flexbox")]
+        "flex-basis" => values.flex_basis = Dimension::from(length(single()?, DIMENSION)?),
+        #[cfg(any(feature = "flexbox", feature = "grid"))]
+        "align-items" => values.align_items = align_items(single()?)?,
+        #[cfg(any(feature = "flexbox", feature = "grid"))]
+        "align-self" => values.align_self = align_items(single()?)?,
+        #[cfg(feature = "grid")]
+        "justify-items" => values.justify_items = align_items(single()?)?,
+        #[cfg(feature = "grid")]
+        "justify-self" => values.justify_self = align_items(single()?)?,
+        #[cfg(any(feature = "flexbox", feature = "grid"))]
+        "align-content" => values.align_content = align_content(single()?)?,
+        #[cfg(any(feature = "flexbox", feature = "grid"))]
+        "justify-content" => values.justify_content = align_content(single()?)?,
+        #[cfg(feature = "block_layout")]
+        "text-align" => values.text_align = text_align(single()?)?,
+        #[cfg(feature = "grid")]
+        "grid-template-rows" => values.grid_template_rows = track_list(&words, units)?.into_iter().map(TrackSizingFunction::Single).collect(),
+        #[cfg(feature = "grid")]
+        "grid-template-columns" => values.grid_template_columns = track_list(&words, units)?.into_iter().map(TrackSizingFunction::Single).collect(),
+        #[cfg(feature = "grid")]
+        "grid-auto-rows" => values.grid_auto_rows = track_list(&words, units)?,
+        #[cfg(feature = "grid")]
+        "grid-auto-columns" => values.grid_auto_columns = track_list(&words, units)?,
+        #[cfg(feature = "grid")]
+        "grid-auto-flow" => values.grid_auto_flow = grid_auto_flow(&words)?,
+        #[cfg(feature = "grid")]
+        "grid-row" => values.grid_row = grid_line(&words)?,
+        #[cfg(feature = "grid")]
+        "grid-row-start" => values.grid_row.start = grid_placement(&words)?,
+        #[cfg(feature = "grid")]
+        "grid-row-end" => values.grid_row.end = grid_placement(&words)?,
+        #[cfg(feature = "grid")]
+        "grid-column" => values.grid_column = grid_line(&words)?,
+        #[cfg(feature = "grid")]
+        "grid-column-start" => values.grid_column.start = grid_placement(&words)?,
+        #[cfg(feature = "grid")]
+        "grid-column-end" => values.grid_column.end = grid_placement(&words)?,
+        _ => return None,
+    }
+    Some(properties)
+}
+
+/// Splits a value into words at whitespace outside of parentheses, keeping `calc()` expressions whole
+fn split_words(value: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    let mut depth = 0usize;
+    let mut start = None;
+    for (i, c) in value.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            _ if c.is_whitespace() && depth == 0 => {
+                if let Some(start) = start.take() {
+                    words.push(&value[start..i]);
+                }
+                continue;
+            }
+            _ => {}
+        }
+        start.get_or_insert(i);
+    }
+    if let Some(start) = start {
+        words.push(&value[start..]);
+    }
+    words
+}
+
+fn number(word: &str) -> Option<f32> {
+    word.parse().ok().filter(|n: &f32| n.is_finite())
+}
+
+fn px(word: &str) -> Option<f32> {
+    number(word.strip_suffix("px").unwrap_or(word))
+}
+
+/// The units of the relative `c_Length` dims, longest suffix first
+const RELATIVE_UNITS: [(&str, i32); 6] = [("vmin", 10), ("vmax", 11), ("rem", 13), ("vw", 8), ("vh", 9), ("em", 12)];
+
+/// Parses a length into its `c_Length` encoding, accepting only the dims in `dims` and calc expressions.
+/// Relative lengths are resolved against `units`, and rejected without them.
+fn length(word: &str, dims: RangeInclusive<i32>, units: Option<&Units>) -> Option<c_Length> {
+    if ["calc(", "min(", "max(", "clamp("].iter().any(|function| word.starts_with(function)) {
+        let length = c_Length { dim: calc::DIM, value: calc::parse(word)? as f32 };
+        return calc::is_supported(length).then_some(length);
+    }
+    let length = match word {
+        "auto" => c_Length { dim: 0, value: 0.0 },
+        "min-content" => c_Length { dim: 3, value: 0.0 },
+        "max-content" => c_Length { dim: 4, value: 0.0 },
+        _ => match word.strip_suffix('%') {
+            Some(percent) => c_Length { dim: 2, value: number(percent)? / 100.0 },
+            None => match word.strip_suffix("fr") {
+                Some(fr) => c_Length { dim: 7, value: number(fr)? },
+                None => match RELATIVE_UNITS.iter().find_map(|(unit, dim)| Some((word.strip_suffix(unit)?, *dim))) {
+                    Some((value, dim)) => units?.resolve(c_Length { dim, value: number(value)? }),
+                    None => c_Length { dim: 1, value: px(word)? },
+                },
+            },
+        },
+    };
+    dims.contains(&length.dim).then_some(length)
+}
+
+/// Parses the CSS 1-4 value box shorthand (top, right, bottom, left)
+fn box_sides<T>(words: &[&str], dims: RangeInclusive<i32>, units: Option<&Units>, convert: fn(c_Length) -> T) -> Option<Rect<T>> {
+    let lengths = words.iter().map(|word| length(word, dims.clone(), units)).collect::<Option<Vec<_>>>()?;
+    let (top, right, bottom, left) = match lengths.as_slice() {
+        [all] => (*all, *all, *all, *all),
+        [vertical, horizontal] => (*vertical, *horizontal, *vertical, *horizontal),
+        [top, horizontal, bottom] => (*top, *horizontal, *bottom, *horizontal),
+        [top, right, bottom, left] => (*top, *right, *bottom, *left),
+        _ => return None,
+    };
+    Some(Rect { left: convert(left), right: convert(right), top: convert(top), bottom: convert(bottom) })
+}
+
+#[cfg(feature = "grid")]
+fn track_list(words: &[&str], units: Option<&Units>) -> Option<Vec<NonRepeatedTrackSizingFunction>> {
+    words
+        .iter()
+        .map(|word| {
+            let min = length(word, MIN_TRACK, units).unwrap_or(c_Length { dim: 0, value: 0.0 });
+            let max = length(word, MAX_TRACK, units)?;
+            Some(NonRepeatedTrackSizingFunction {
+                min: MinTrackSizingFunction::from(min),
+                max: MaxTrackSizingFunction::from(max),
+            })
+        })
+        .collect()
+}
+
+#[cfg(feature = "grid")]
+fn grid_auto_flow(words: &[&str]) -> Option<GridAutoFlow> {
+    Some(match words {
+        ["row"] => GridAutoFlow::Row,
+        ["column"] => GridAutoFlow::Column,
+        ["row", "dense"] | ["dense"] => GridAutoFlow::RowDense,
+        ["column", "dense"] => GridAutoFlow::ColumnDense,
+        _ => return None,
+    })
+}
+
+#[cfg(feature = "grid")]
+fn grid_placement(words: &[&str]) -> Option<GridPlacement> {
+    Some(match words {
+        ["auto"] => GridPlacement::Auto,
+        ["span", count] => GridPlacement::from_span(count.parse::<u16>().ok().filter(|count| *count > 0)?),
+        [line] => GridPlacement::from_line_index(line.parse::<i16>().ok().filter(|line| *line != 0)?),
+        _ => return None,
+    })
+}
+
+#[cfg(feature = "grid")]
+fn grid_line(words: &[&str]) -> Option<Line<GridPlacement>> {
+    match words.iter().position(|word| *word == "/") {
+        Some(slash) => Some(Line { start: grid_placement(&words[..slash])?, end: grid_placement(&words[slash + 1..])? }),
+        None => Some(Line { start: grid_placement(words)?, end: GridPlacement::Auto }),
+    }
+}
+
+fn display(word: &str) -> Option<Display> {
+    Some(match word {
+        "none" => Display::None,
+        #[cfg(feature = "flexbox")]
+        "flex" => Display::Flex,
+        #[cfg(feature = "grid")]
+        "grid" => Display::Grid,
+        #[cfg(feature = "block_layout")]
+        "block" => Display::Block,
+        _ => return None,
+    })
+}
+
+fn position(word: &str) -> Option<Position> {
+    Some(match word {
+        "relative" => Position::Relative,
+        "absolute" => Position::Absolute,
+        _ => return None,
+    })
+}
+
+fn box_sizing(word: &str) -> Option<BoxSizing> {
+    Some(match word {
+        "border-box" => BoxSizing::BorderBox,
+        "content-box" => BoxSizing::ContentBox,
+        _ => return None,
+    })
+}
+
+fn overflow(word: &str) -> Option<Overflow> {
+    Some(match word {
+        "visible" => Overflow::Visible,
+        "hidden" => Overflow::Hidden,
+        "scroll" => Overflow::Scroll,
+        "clip" => Overflow::Clip,
+        _ => return None,
+    })
+}
+
+#[cfg(feature = "flexbox")]
+fn flex_direction(word: &str) -> Option<FlexDirection> {
+    Some(match word {
+        "row" => FlexDirection::Row,
+        "column" => FlexDirection::Column,
+        "row-reverse" => FlexDirection::RowReverse,
+        "column-reverse" => FlexDirection::ColumnReverse,
+        _ => return None,
+    })
+}
+
+#[cfg(feature = "flexbox")]
+fn flex_wrap(word: &str) -> Option<FlexWrap> {
+    Some(match word {
+        "nowrap" => FlexWrap::NoWrap,
+        "wrap" => FlexWrap::Wrap,
+        "wrap-reverse" => FlexWrap::WrapReverse,
+        _ => return None,
+    })
+}
+
+#[cfg(any(feature = "flexbox", feature = "grid"))]
+fn align_items(word: &str) -> Option<Option<AlignItems>> {
+    Some(Some(match word {
+        "normal" => return Some(None),
+        "start" => AlignItems::Start,
+        "end" => AlignItems::End,
+        "flex-start" => AlignItems::FlexStart,
+        "flex-end" => AlignItems::FlexEnd,
+        "center" => AlignItems::Center,
+        "baseline" => AlignItems::Baseline,
+        "stretch" => AlignItems::Stretch,
+        _ => return None,
+    }))
+}
+
+#[cfg(any(feature = "flexbox", feature = "grid"))]
+fn align_content(word: &str) -> Option<Option<AlignContent>> {
+    Some(Some(match word {
+        "normal" => return Some(None),
+        "start" => AlignContent::Start,
+        "end" => AlignContent::End,
+        "flex-start" => AlignContent::FlexStart,
+        "flex-end" => AlignContent::FlexEnd,
+        "center" => AlignContent::Center,
+        "stretch" => AlignContent::Stretch,
+        "space-between" => AlignContent::SpaceBetween,
+        "space-evenly" => AlignContent::SpaceEvenly,
+        "space-around" => AlignContent::SpaceAround,
+        _ => return None,
+    }))
+}
+
+#[cfg(feature = "block_layout")]
+fn text_align(word: &str) -> Option<TextAlign> {
+    Some(match word {
+        "auto" => TextAlign::Auto,
+        "left" => TextAlign::LegacyLeft,
+        "right" => TextAlign::LegacyRight,
+        "center" => TextAlign::LegacyCenter,
+        _ => return None,
+    })
+}
+
+fn copy_property(property: Property, from: &Style, to: &mut Style) {
+    use Property::*;
+    match property {
+        Display => to.display = from.display,
+        Position => to.position = from.position,
+        BoxSizing => to.box_sizing = from.box_sizing,
+        OverflowX => to.overflow.x = from.overflow.x,
+        OverflowY => to.overflow.y = from.overflow.y,
+        ScrollbarWidth => to.scrollbar_width = from.scrollbar_width,
+        Width => to.size.width = from.size.width,
+        Height => to.size.height = from.size.height,
+        MinWidth => to.min_size.width = from.min_size.width,
+        MinHeight => to.min_size.height = from.min_size.height,
+        MaxWidth => to.max_size.width = from.max_size.width,
+        MaxHeight => to.max_size.height = from.max_size.height,
+        AspectRatio => to.aspect_ratio = from.aspect_ratio,
+        MarginLeft => to.margin.left = from.margin.left,
+        MarginRight => to.margin.right = from.margin.right,
+        MarginTop => to.margin.top = from.margin.top,
+        MarginBottom => to.margin.bottom = from.margin.bottom,
+        PaddingLeft => to.padding.left = from.padding.left,
+        PaddingRight => to.padding.right = from.padding.right,
+        PaddingTop => to.padding.top = from.padding.top,
+        PaddingBottom => to.padding.bottom = from.padding.bottom,
+        BorderLeft => to.border.left = from.border.left,
+        BorderRight => to.border.right = from.border.right,
+        BorderTop => to.border.top = from.border.top,
+        BorderBottom => to.border.bottom = from.border.bottom,
+        Left => to.inset.left = from.inset.left,
+        Right => to.inset.right = from.inset.right,
+        Top => to.inset.top = from.inset.top,
+        Bottom => to.inset.bottom = from.inset.bottom,
+        #[cfg(any(feature = "flexbox", feature = "grid"))]
+        ColumnGap => to.gap.width = from.gap.width,
+        #[cfg(any(feature = "flexbox", feature = "grid"))]
+        RowGap => to.gap.height = from.gap.height,
+        #[cfg(feature = "flexbox")]
+        FlexDirection => to.flex_direction = from.flex_direction,
+        #[cfg(feature = "flexbox")]
+        FlexWrap => to.flex_wrap = from.flex_wrap,
+        #[cfg(feature = "flexbox")]
+        FlexGrow => to.flex_grow = from.flex_grow,
+        #[cfg(feature = "flexbox")]
+        FlexShrink => to.flex_shrink = from.flex_shrink,
+        #[cfg(feature = "flexbox")]
+        FlexBasis => to.flex_basis = from.flex_basis,
+        #[cfg(any(feature = "flexbox", feature = "grid"))]
+        AlignItems => to.align_items = from.align_items,
+        #[cfg(any(feature = "flexbox", feature = "grid"))]
+        AlignSelf => to.align_self = from.align_self,
+        #[cfg(feature = "grid")]
+        JustifyItems => to.justify_items = from.justify_items,
+        #[cfg(feature = "grid")]
+        JustifySelf => to.justify_self = from.justify_self,
+        #[cfg(any(feature = "flexbox", feature = "grid"))]
+        AlignContent => to.align_content = from.align_content,
+        #[cfg(any(feature = "flexbox", feature = "grid"))]
+        JustifyContent => to.justify_content = from.justify_content,
+        #[cfg(feature = "block_layout")]
+        TextAlign => to.text_align = from.text_align,
+        #[cfg(feature = "grid")]
+        GridTemplateRows => to.grid_template_rows = from.grid_template_rows.clone(),
+        #[cfg(feature = "grid")]
+        GridTemplateColumns => to.grid_template_columns = from.grid_template_columns.clone(),
+        #[cfg(feature = "grid")]
+        GridAutoRows => to.grid_auto_rows = from.grid_auto_rows.clone(),
+        #[cfg(feature = "grid")]
+        GridAutoColumns => to.grid_auto_columns = from.grid_auto_columns.clone(),
+        #[cfg(feature = "grid")]
+        GridAutoFlow => to.grid_auto_flow = from.grid_auto_flow,
+        #[cfg(feature = "grid")]
+        GridRowStart => to.grid_row.start = from.grid_row.start,
+        #[cfg(feature = "grid")]
+        GridRowEnd => to.grid_row.end = from.grid_row.end,
+        #[cfg(feature = "grid")]
+        GridColumnStart => to.grid_column.start = from.grid_column.start,
+        #[cfg(feature = "grid")]
+        GridColumnEnd => to.grid_column.end = from.grid_column.end,
+    }
+}
@@ -0,0 +1,73 @@
+//! Text leaves, measured natively with a font loaded by the host
+
+use std::rc::Rc;
+
+use ab_glyph::{Font, FontVec, PxScale, PxScaleFont, ScaleFont};
+use taffy::prelude::*;
+
+#[derive(Clone)]
+pub(crate) struct Text {
+    pub(crate) font: Rc<FontVec>,
+    pub(crate) text: String,
+    pub(crate) font_size: f32,
+    /// The height of each line in pixels, or 0 to use the ascent, descent and line gap of the font
+    pub(crate) line_height: f32,
+}
+
+impl Text {
+    /// Breaks the text into lines no wider than the known or available width, greedily between words and
+    /// always at `\n`. Returns the size of the lines and the distance from their top to the first baseline.
+    pub(crate) fn measure(&self, known_dimensions: Size<Option<f32>>, available_space: Size<AvailableSpace>) -> (Size<f32>, Option<f32>) {
+        if self.text.is_empty() {
+            return (Size::ZERO, None);
+        }
+
+        let font = self.font.as_scaled(PxScale::from(self.font_size));
+        let max_width = known_dimensions.width.unwrap_or(match available_space.width {
+            AvailableSpace::Definite(width) => width,
+            AvailableSpace::MinContent => 0.0,
+            AvailableSpace::MaxContent => f32::INFINITY,
+        });
+        let space = font.h_advance(font.glyph_id(' '));
+
+        let mut lines = 0;
+        let mut widest: f32 = 0.0;
+        for paragraph in self.text.split('\n') {
+            let mut line: Option<f32> = None;
+            for word in paragraph.split_whitespace() {
+                let word = width(&font, word);
+                line = Some(match line {
+                    Some(line) if line + space + word <= max_width => line + space + word,
+                    Some(line) => {
+                        lines += 1;
+                        widest = widest.max(line);
+                        word
+                    }
+                    None => word,
+                });
+            }
+            lines += 1;
+            widest = widest.max(line.unwrap_or(0.0));
+        }
+
+        let content_height = font.ascent() - font.descent();
+        let line_height = if self.line_height > 0.0 { self.line_height } else { content_height + font.line_gap() };
+        let baseline = (line_height - content_height) / 2.0 + font.ascent();
+        (Size { width: widest, height: lines as f32 * line_height }, Some(baseline))
+    }
+}
+
+/// The advance width of `word`, including kerning between its glyphs
+fn width(font: &PxScaleFont<&FontVec>, word: &str) -> f32 {
+    let mut width = 0.0;
+    let mut previous = None;
+    for c in word.chars() {
+        let glyph = font.glyph_id(c);
+        if let Some(previous) = previous {
+            width += font.kern(previous, glyph);
+        }
+        width += font.h_advance(glyph);
+        previous = Some(glyph);
+    }
+    width
+}
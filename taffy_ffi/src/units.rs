@@ -0,0 +1,142 @@
+//! Relative lengths: `c_Length` dims that depend on the tree rather than on the node's containing block.
+//!
+//! taffy only knows absolute lengths and percentages, so relative lengths are resolved to absolute ones
+//! when a style is converted. A node whose style uses them keeps the unresolved style next to it, which
+//! is resolved again whenever what it depends on changes.
+//!
+//! | dim | unit   | one unit is                                   |
+//! |-----|--------|-----------------------------------------------|
+//! | 8   | `vw`   | 1% of the viewport width                      |
+//! | 9   | `vh`   | 1% of the viewport height                     |
+//! | 10  | `vmin` | 1% of the smaller side of the viewport        |
+//! | 11  | `vmax` | 1% of the larger side of the viewport         |
+//! | 12  | `em`   | the font size of the node                     |
+//! | 13  | `rem`  | the font size of the tree                     |
+//!
+//! A node's font size is the one set on it or on its closest ancestor that has one, and the tree's
+//! otherwise. See `taffytree_set_font_size` and `taffytree_set_root_font_size`.
+
+use std::ops::RangeInclusive;
+
+use taffy::prelude::*;
+use taffy::style::Style;
+
+use crate::{c_GridTrackSize, c_GridTrackSizing, c_Length, c_Rect, c_Size, c_Style, OwnedStyle, OwnedTrackSizing};
+
+/// The `c_Length::dim` values of the viewport units
+pub(crate) const VIEWPORT: RangeInclusive<i32> = 8..=11;
+
+/// The `c_Length::dim` values of the font units
+pub(crate) const FONT: RangeInclusive<i32> = 12..=13;
+
+/// The font size of a tree until the host sets one, as in browsers
+pub(crate) const DEFAULT_FONT_SIZE: f32 = 16.0;
+
+/// What relative lengths are resolved against
+#[derive(Clone, Copy)]
+pub(crate) struct Units {
+    pub(crate) viewport: Size<f32>,
+    pub(crate) font_size: f32,
+    pub(crate) root_font_size: f32,
+}
+
+impl Units {
+    /// The units of a style outside of any tree: no viewport, and the default font sizes
+    pub(crate) const DETACHED: Units =
+        Units { viewport: Size::ZERO, font_size: DEFAULT_FONT_SIZE, root_font_size: DEFAULT_FONT_SIZE };
+
+    /// Converts a relative length into an absolute one, returning any other length unchanged
+    pub(crate) fn resolve(&self, length: c_Length) -> c_Length {
+        let value = match length.dim {
+            8 => length.value * self.viewport.width / 100.0,
+            9 => length.value * self.viewport.height / 100.0,
+            10 => length.value * self.viewport.width.min(self.viewport.height) / 100.0,
+            11 => length.value * self.viewport.width.max(self.viewport.height) / 100.0,
+            12 => length.value * self.font_size,
+            13 => length.value * self.root_font_size,
+            _ => return length,
+        };
+        c_Length { dim: 1, value }
+    }
+}
+
+pub(crate) fn is_relative(length: c_Length) -> bool {
+    VIEWPORT.contains(&length.dim) || FONT.contains(&length.dim)
+}
+
+impl OwnedStyle {
+    /// Copies a style from host memory, including the grid track arrays it points to. Returns `None` if the
+    /// style holds a value it cannot be converted with, see `OwnedStyle::is_valid`.
+    pub(crate) fn from_raw(raw: &c_Style) -> Option<OwnedStyle> {
+        fn slice<'a, T>(ptr: *const T, count: usize) -> &'a [T] {
+            if count > 0 && !ptr.is_null() {
+                unsafe { std::slice::from_raw_parts(ptr, count) }
+            } else {
+                &[]
+            }
+        }
+        fn sizing(track: &c_GridTrackSizing) -> OwnedTrackSizing {
+            if track.repetition == -2 && !track.single.is_null() {
+                OwnedTrackSizing::Single(unsafe { *track.single })
+            } else {
+                OwnedTrackSizing::Repeat(track.repetition, slice(track.repeat, track.repeat_count).to_vec())
+            }
+        }
+
+        let mut owned = OwnedStyle {
+            raw: *raw,
+            grid_template_rows: slice(raw.grid_template_rows, raw.grid_template_rows_count).iter().map(sizing).collect(),
+            grid_template_columns: slice(raw.grid_template_columns, raw.grid_template_columns_count).iter().map(sizing).collect(),
+            grid_auto_rows: slice(raw.grid_auto_rows, raw.grid_auto_rows_count).to_vec(),
+            grid_auto_columns: slice(raw.grid_auto_columns, raw.grid_auto_columns_count).to_vec(),
+        };
+        owned.raw.grid_template_rows = std::ptr::null();
+        owned.raw.grid_template_columns = std::ptr::null();
+        owned.raw.grid_auto_rows = std::ptr::null();
+        owned.raw.grid_auto_columns = std::ptr::null();
+        owned.is_valid().then_some(owned)
+    }
+
+    /// Calls `f` with every length of the style
+    pub(crate) fn for_each_length(&mut self, mut f: impl FnMut(&mut c_Length)) {
+        let mut size = |size: &mut c_Size| {
+            f(&mut size.width);
+            f(&mut size.height);
+        };
+        size(&mut self.raw.gap);
+        size(&mut self.raw.size);
+        size(&mut self.raw.min_size);
+        size(&mut self.raw.max_size);
+        let raw = &mut self.raw;
+        for rect in [&mut raw.inset, &mut raw.margin, &mut raw.border, &mut raw.padding] {
+            let c_Rect { left, right, top, bottom } = rect;
+            for length in [left, right, top, bottom] {
+                f(length);
+            }
+        }
+        f(&mut raw.flex_basis);
+
+        let tracks = self.grid_template_rows.iter_mut().chain(self.grid_template_columns.iter_mut()).flat_map(|track| match track {
+            OwnedTrackSizing::Single(size) => std::slice::from_mut(size),
+            OwnedTrackSizing::Repeat(_, sizes) => sizes.as_mut_slice(),
+        });
+        for c_GridTrackSize { min_size, max_size } in
+            tracks.chain(self.grid_auto_rows.iter_mut()).chain(self.grid_auto_columns.iter_mut())
+        {
+            f(min_size);
+            f(max_size);
+        }
+    }
+
+    /// Converts the style into a `taffy::Style`, resolving its relative lengths against `units`. Also returns
+    /// whether there were any, in which case the style has to be resolved again when the units change.
+    pub(crate) fn resolve(&self, units: &Units) -> (Style, bool) {
+        let mut resolved = self.clone();
+        let mut relative = false;
+        resolved.for_each_length(|length| {
+            relative |= is_relative(*length);
+            *length = units.resolve(*length);
+        });
+        (resolved.with_raw(|raw| Style::from(*raw)), relative)
+    }
+}